@@ -0,0 +1,40 @@
+// Servidor de Catan en red.
+//
+//   cargo run --bin catan_server -- [dirección] [jugadores] [--dados modo]
+//
+// Por defecto escucha en 127.0.0.1:7878; `jugadores` es el tamaño de las
+// partidas rápidas (`Join`) y `--dados normales|mazo|equilibrados|fisicos`
+// de dónde salen sus dados. Se pueden jugar varias partidas a la vez:
+// los clientes las listan, crean y se unen desde la sala de espera.
+// Para probar en local alcanza con abrir una terminal por jugador y
+// conectarse con `nc 127.0.0.1 7878`, escribiendo mensajes como
+// `{"type":"Join","seat":null}` o `{"type":"ListGames"}`.
+
+use bevy_hexx_demo::dice::DiceMode;
+use bevy_hexx_demo::net::{run_server, GameOptions};
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut quick_game = GameOptions::default();
+    if let Some(i) = args.iter().position(|arg| arg == "--dados") {
+        let mode = args.get(i + 1).cloned().unwrap_or_default();
+        match DiceMode::parse(&mode) {
+            Some(dice) => quick_game.rules.dice = dice,
+            None => println!("Modo de dados desconocido: {}", mode),
+        }
+        args.drain(i..(i + 2).min(args.len()));
+    }
+
+    let mut args = args.into_iter();
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:7878".to_string());
+    quick_game.players = args
+        .next()
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|n| (2..=4).contains(n))
+        .unwrap_or(2);
+
+    if let Err(e) = run_server(&addr, quick_game) {
+        eprintln!("Error del servidor: {}", e);
+        std::process::exit(1);
+    }
+}
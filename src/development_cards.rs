@@ -2,7 +2,7 @@
 
 use crate::types::*;
 // Importa las funciones de lógica que necesitamos
-use crate::game_logic::{place_robber, place_road, update_largest_army}; 
+use crate::game_logic::{move_robber, place_robber, place_road, update_largest_army}; 
 
 /**
  * Función auxiliar para encontrar y quitar una carta de la
@@ -20,26 +20,31 @@ fn consume_card(player: &mut Player, card: DevelopmentCard) -> bool {
     }
 }
 
+/// ¿Ya jugó este turno todas las cartas que permiten las reglas?
+pub fn dev_card_limit_reached(board: &Board, player_index: usize) -> bool {
+    board.players[player_index].dev_cards_played_this_turn >= board.rules.dev_cards_per_turn
+}
+
 // --- FUNCIONES PÚBLICAS DE JUEGO ---
 
 /**
  * Juega una carta de Caballero (Knight).
- * Mueve el ladrón y roba a un jugador.
+ * Mueve el ladrón y roba a un jugador (si hay alguno en la casilla).
  * Devuelve `true` si se jugó con éxito.
  */
 pub fn play_knight_card(
     board: &mut Board,
     player_id: PlayerType,
     new_tile_pos: TileId,
-    player_to_rob_id: PlayerType
+    player_to_rob_id: Option<PlayerType>
 ) -> Option<PlayerType> {
     
     // Paso 1: Encontrar al jugador
     let player_index = board.players.iter().position(|p| p.id == player_id).unwrap();
     
     // Paso 2: Chequear reglas
-    if board.players[player_index].played_dev_card_this_turn {
-        println!("Error: Ya has jugado una carta de desarrollo este turno.");
+    if dev_card_limit_reached(board, player_index) {
+        println!("Error: Ya jugaste las cartas de desarrollo permitidas este turno.");
         return None;
     }
     
@@ -51,13 +56,20 @@ pub fn play_knight_card(
     
     // Paso 4: Ejecutar la lógica
     let player = &mut board.players[player_index];
-    player.played_dev_card_this_turn = true;
+    player.dev_cards_played_this_turn += 1;
     player.knights_played += 1;
     
     println!("¡{:?} ha jugado un Caballero! (Total: {})", player_id, player.knights_played);
     
     // Llamamos a la función de `game_logic` para mover el ladrón
-    place_robber(board, player_id, new_tile_pos, player_to_rob_id);
+    match player_to_rob_id {
+        Some(target) => place_robber(board, player_id, new_tile_pos, target),
+        None => {
+            if let Err(msg) = move_robber(board, new_tile_pos) {
+                println!("{}", msg);
+            }
+        }
+    }
     
     // (Aquí deberías llamar a una función `update_largest_army(board)`)
     
@@ -82,8 +94,8 @@ pub fn play_road_building_card(
     };
 
     // --- Chequeos ---
-    if board.players[player_index].played_dev_card_this_turn {
-        return Err("Error: Ya has jugado una carta de desarrollo este turno.");
+    if dev_card_limit_reached(board, player_index) {
+        return Err("Error: Ya jugaste las cartas de desarrollo permitidas este turno.");
     }
     if board.players[player_index].road_quantity < 2 {
         return Err("Error: No tienes suficientes piezas de camino (necesitas 2).");
@@ -93,7 +105,7 @@ pub fn play_road_building_card(
     }
 
     // --- Lógica ---
-    board.players[player_index].played_dev_card_this_turn = true;
+    board.players[player_index].dev_cards_played_this_turn += 1;
     println!("¡{:?} ha jugado Construcción de Caminos!", player_id);
 
     // 2. Colocar el primer camino
@@ -129,8 +141,8 @@ pub fn play_year_of_plenty_card(
     let player_index = board.players.iter().position(|p| p.id == player_id).unwrap();
 
     // Paso 2: Chequear reglas
-    if board.players[player_index].played_dev_card_this_turn {
-        println!("Error: Ya has jugado una carta de desarrollo este turno.");
+    if dev_card_limit_reached(board, player_index) {
+        println!("Error: Ya jugaste las cartas de desarrollo permitidas este turno.");
         return false;
     }
 
@@ -142,7 +154,7 @@ pub fn play_year_of_plenty_card(
 
     // Paso 4: Ejecutar la lógica
     let player = &mut board.players[player_index];
-    player.played_dev_card_this_turn = true;
+    player.dev_cards_played_this_turn += 1;
 
     *player.resources.entry(material1).or_insert(0) += 1;
     *player.resources.entry(material2).or_insert(0) += 1;
@@ -167,8 +179,8 @@ pub fn play_monopoly_card(
     let player_playing_index = board.players.iter().position(|p| p.id == player_id).unwrap();
 
     // Paso 2: Chequear reglas
-    if board.players[player_playing_index].played_dev_card_this_turn {
-        println!("Error: Ya has jugado una carta de desarrollo este turno.");
+    if dev_card_limit_reached(board, player_playing_index) {
+        println!("Error: Ya jugaste las cartas de desarrollo permitidas este turno.");
         return false;
     }
 
@@ -179,7 +191,7 @@ pub fn play_monopoly_card(
     }
     
     // Paso 4: Ejecutar la lógica
-    board.players[player_playing_index].played_dev_card_this_turn = true;
+    board.players[player_playing_index].dev_cards_played_this_turn += 1;
     println!("¡{:?} ha jugado Monopolio sobre {:?}!", player_id, material);
     
     let mut total_stolen = 0;
@@ -0,0 +1,217 @@
+// src/dice.rs
+//
+// De dónde salen los dados. Además de los dos dados de siempre hay un
+// mazo de 36 cartas (una por combinación, se vuelve a mezclar cuando
+// quedan pocas), dados equilibrados que bajan el peso de los totales que
+// salieron hace poco para cortar las rachas, y dados físicos: la mesa
+// tira de verdad y se carga lo que salió. El modo se elige en las reglas;
+// lo que hace falta recordar entre tiradas vive en el `Board`.
+
+use crate::rules::RuleSet;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiceMode {
+    /// Dos dados de seis caras.
+    #[default]
+    Standard,
+    /// Mazo de 36 cartas, una por cada par de dados.
+    Deck,
+    /// Dados que recuerdan las últimas tiradas y evitan repetirlas.
+    Balanced,
+    /// Dados físicos: se carga lo que salió en la mesa.
+    Manual,
+}
+
+impl DiceMode {
+    /// El modo por su nombre en la línea de comandos (`--dados mazo`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "normales" => Some(DiceMode::Standard),
+            "mazo" => Some(DiceMode::Deck),
+            "equilibrados" => Some(DiceMode::Balanced),
+            "fisicos" | "físicos" => Some(DiceMode::Manual),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DiceMode::Standard => "dados normales",
+            DiceMode::Deck => "mazo de dados",
+            DiceMode::Balanced => "dados equilibrados",
+            DiceMode::Manual => "dados físicos",
+        }
+    }
+}
+
+/// Con estas cartas en el mazo se vuelve a mezclar entero.
+const DECK_RESHUFFLE_AT: usize = 5;
+/// Tiradas que recuerdan los dados equilibrados.
+const BALANCED_MEMORY: usize = 10;
+
+/// Estado de los dados entre tiradas: el mazo y las últimas tiradas.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dice {
+    /// Cartas que quedan en el mazo (dado blanco, dado rojo).
+    pub deck: Vec<(u8, u8)>,
+    /// Totales de las últimas tiradas, el más reciente al final.
+    pub recent: Vec<u8>,
+    /// Los dos dados de la última tirada, para mostrarlos.
+    #[serde(default)]
+    pub last: Option<(u8, u8)>,
+}
+
+impl Dice {
+    /// Tira según el modo de las reglas; en las rondas sin 7 se vuelve a tirar.
+    /// Devuelve (blanco, rojo). Con dados físicos, si nadie carga nada
+    /// (un bot, o se acabó el tiempo), tira la aplicación.
+    pub fn roll(&mut self, rules: &RuleSet, round: u32) -> (u8, u8) {
+        loop {
+            let dice = match rules.dice {
+                DiceMode::Standard | DiceMode::Manual => roll_two(),
+                DiceMode::Deck => self.draw(),
+                DiceMode::Balanced => self.roll_balanced(),
+            };
+            if dice.0 + dice.1 != 7 || rules.allows_seven(round) {
+                self.remember(dice);
+                return dice;
+            }
+        }
+    }
+
+    /// Lo que salió en los dados de la mesa.
+    pub fn enter(&mut self, rules: &RuleSet, round: u32, first: u8, second: u8) -> Result<(u8, u8), &'static str> {
+        if !(1..=6).contains(&first) || !(1..=6).contains(&second) {
+            return Err("Cada dado va del 1 al 6.");
+        }
+        if first + second == 7 && !rules.allows_seven(round) {
+            return Err("En esta ronda no vale el 7: vuelve a tirar.");
+        }
+        self.remember((first, second));
+        Ok((first, second))
+    }
+
+    fn remember(&mut self, dice: (u8, u8)) {
+        self.last = Some(dice);
+        self.recent.push(dice.0 + dice.1);
+        if self.recent.len() > BALANCED_MEMORY {
+            self.recent.remove(0);
+        }
+    }
+
+    fn draw(&mut self) -> (u8, u8) {
+        if self.deck.len() <= DECK_RESHUFFLE_AT {
+            self.deck = (1..=6).flat_map(|a| (1..=6).map(move |b| (a, b))).collect();
+            self.deck.shuffle(&mut rand::rng());
+        }
+        self.deck.pop().unwrap_or_else(roll_two)
+    }
+
+    /// Elige el total con el peso de `balanced_weights` y después uno de los
+    /// pares que lo suman.
+    fn roll_balanced(&self) -> (u8, u8) {
+        let mut r = rand::rng();
+        let weights = self.balanced_weights();
+        let mut pick = r.random_range(0..weights.iter().map(|&(_, w)| w).sum::<u32>());
+        let mut total = 7;
+        for (t, weight) in weights {
+            if pick < weight {
+                total = t;
+                break;
+            }
+            pick -= weight;
+        }
+        let first = r.random_range(total.saturating_sub(6).max(1)..=(total - 1).min(6));
+        (first, total - first)
+    }
+
+    /// Peso de cada total: el de siempre (6 formas de sacar 7, 1 de sacar
+    /// 2...) dividido por las veces que salió hace poco.
+    fn balanced_weights(&self) -> Vec<(u8, u32)> {
+        (2..=12)
+            .map(|total| {
+                let ways = 6 - (7 - total as i32).unsigned_abs();
+                let seen = self.recent.iter().filter(|&&t| t == total).count() as u32;
+                (total, ways * 60 / (1 + seen))
+            })
+            .collect()
+    }
+}
+
+fn roll_two() -> (u8, u8) {
+    let mut r = rand::rng();
+    (r.random_range(1..=6), r.random_range(1..=6))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Action, Game, GamePhase};
+    use std::collections::HashSet;
+
+    fn rules(dice: DiceMode) -> RuleSet {
+        RuleSet { dice, ..Default::default() }
+    }
+
+    #[test]
+    fn deck_deals_each_pair_once_and_reshuffles_near_the_end() {
+        let rules = rules(DiceMode::Deck);
+        let mut dice = Dice::default();
+        let mut seen = HashSet::new();
+        for _ in 0..36 - DECK_RESHUFFLE_AT {
+            assert!(seen.insert(dice.roll(&rules, 5)), "un par repetido antes de mezclar");
+        }
+        assert_eq!(dice.deck.len(), DECK_RESHUFFLE_AT);
+        dice.roll(&rules, 5);
+        assert_eq!(dice.deck.len(), 35);
+    }
+
+    #[test]
+    fn balanced_dice_lower_the_weight_of_recent_totals() {
+        let fresh = Dice::default().balanced_weights();
+        let weight = |weights: &[(u8, u32)], total: u8| weights.iter().find(|&&(t, _)| t == total).unwrap().1;
+        assert_eq!(weight(&fresh, 7), 6 * weight(&fresh, 2));
+
+        let dice = Dice { recent: vec![7, 7, 8], ..Default::default() };
+        let weights = dice.balanced_weights();
+        assert_eq!(weight(&weights, 7), weight(&fresh, 7) / 3);
+        assert_eq!(weight(&weights, 8), weight(&fresh, 8) / 2);
+        assert_eq!(weight(&weights, 6), weight(&fresh, 6));
+
+        let rules = rules(DiceMode::Balanced);
+        let mut dice = Dice::default();
+        for _ in 0..50 {
+            let (white, red) = dice.roll(&rules, 5);
+            assert!((1..=6).contains(&white) && (1..=6).contains(&red));
+        }
+        assert_eq!(dice.recent.len(), BALANCED_MEMORY);
+    }
+
+    #[test]
+    fn manual_dice_return_what_was_entered() {
+        let rules = rules(DiceMode::Manual);
+        let mut dice = Dice::default();
+        assert_eq!(dice.enter(&rules, 1, 2, 5), Ok((2, 5)));
+        assert_eq!(dice.last, Some((2, 5)));
+        assert_eq!(dice.recent, vec![7]);
+        assert!(dice.enter(&rules, 1, 0, 5).is_err());
+        assert!(dice.enter(&rules, 1, 3, 7).is_err());
+
+        // el motor tira con lo que se cargó, y sólo con dados físicos
+        let mut game = Game::new(2);
+        game.board.rules.dice = DiceMode::Manual;
+        game.phase = GamePhase::Roll;
+        let player = game.current_player();
+        game.apply(player, Action::EnterDice { first: 2, second: 3 }).unwrap();
+        assert_eq!(game.last_roll, Some(5));
+        assert_eq!(game.board.dice.last, Some((2, 3)));
+
+        let mut game = Game::new(2);
+        game.phase = GamePhase::Roll;
+        let player = game.current_player();
+        assert!(game.apply(player, Action::EnterDice { first: 2, second: 3 }).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+// en src/engine/actions.rs
+
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+
+/// Todo lo que un jugador puede pedirle al motor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Action {
+    PlaceSettlement { vertex: VertexId },
+    PlaceCity { vertex: VertexId },
+    PlaceRoad { edge: EdgeId },
+    RollDice,
+    /// Dados físicos: lo que salió en la mesa (blanco, rojo).
+    EnterDice { first: u8, second: u8 },
+    /// Descarte completo al salir un 7 (una entrada por carta).
+    Discard { materials: Vec<MaterialType> },
+    /// `victim` es `None` sólo si no hay nadie a quien robar en la casilla.
+    MoveRobber { tile: TileId, victim: Option<PlayerType> },
+    BuyDevCard,
+    PlayKnight { tile: TileId, victim: Option<PlayerType> },
+    PlayRoadBuilding { first_edge: EdgeId, second_edge: EdgeId },
+    PlayYearOfPlenty { first: MaterialType, second: MaterialType },
+    PlayMonopoly { material: MaterialType },
+    BankTrade { give: MaterialType, get: MaterialType },
+    /// Oferta a otro jugador (o a todos); queda abierta hasta que alguien la acepte.
+    ProposeTrade { offer: TradeOffer },
+    /// Lo único, junto con descartar y el oro, que se hace en turno ajeno.
+    RespondTrade { accept: bool },
+    CancelTrade,
+    /// Navegantes: barco nuevo sobre un borde de mar.
+    PlaceShip { edge: EdgeId },
+    /// Navegantes: mover el último barco de una ruta abierta.
+    MoveShip { from: EdgeId, to: EdgeId },
+    /// Navegantes: recursos elegidos por lo que dio el oro (una entrada por carta).
+    ChooseGold { materials: Vec<MaterialType> },
+    /// Ciudades y Caballeros: caballero básico nuevo junto a un camino propio.
+    BuildKnight { vertex: VertexId },
+    ActivateKnight { vertex: VertexId },
+    PromoteKnight { vertex: VertexId },
+    /// Ciudades y Caballeros: un nivel más de mejora de ciudad.
+    BuildImprovement { track: ImprovementTrack },
+    PlayProgress { play: ProgressPlay },
+    /// Escenarios: canjear fichas (pescado, monedas...) por lo que ofrezca el escenario.
+    ExchangeTokens { token: String, reward: TokenReward },
+    EndTurn,
+}
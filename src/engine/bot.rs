@@ -0,0 +1,262 @@
+// en src/engine/bot.rs
+//
+// Bot sencillo para llenar asientos vacíos. No planifica: mira la
+// partida con su `PlayerView` (como cualquier jugador, sin ver las
+// manos ajenas), prueba acciones en orden de preferencia sobre una
+// copia de su tablero y se queda con la primera que parece legal.
+
+use crate::game_logic::*;
+use crate::rules::RuleSet;
+use crate::types::*;
+use super::actions::Action;
+use super::game::GamePhase;
+use super::view::PlayerView;
+use std::collections::HashMap;
+
+/// Elige una acción legal para quien mira `view`, o `None` si no le toca.
+pub fn choose_action(view: &PlayerView) -> Option<Action> {
+    if !view.needs_decision() {
+        return None;
+    }
+    let me = view.me()?;
+    // los bots no negocian: rechazan las ofertas para no hacer esperar a nadie
+    if view.is_waiting_on(me.id) {
+        return Some(Action::RespondTrade { accept: false });
+    }
+
+    let candidates: Vec<Action> = match &view.phase {
+        GamePhase::Setup { anchor_vertex: None, .. } => best_vertices(&view.board)
+            .into_iter()
+            .map(|vertex| Action::PlaceSettlement { vertex })
+            .collect(),
+        GamePhase::Setup { anchor_vertex: Some(anchor), .. } => view.board.vertices[*anchor]
+            .adjacent_edges
+            .iter()
+            .map(|&edge| Action::PlaceRoad { edge })
+            .collect(),
+        GamePhase::Roll => vec![Action::RollDice],
+        GamePhase::Discard { .. } => vec![discard_action(&view.board.rules, me)],
+        GamePhase::GoldChoice { pending } => pending
+            .iter()
+            .filter(|&&(p, _)| p == me.id)
+            .map(|&(_, amount)| gold_action(me, amount))
+            .collect(),
+        GamePhase::MoveRobber => robber_targets(view, me.id)
+            .into_iter()
+            .map(|(tile, victim)| Action::MoveRobber { tile, victim })
+            .collect(),
+        GamePhase::Main => main_phase_candidates(view, me),
+        GamePhase::Finished { .. } => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .find(|action| looks_legal(view, me, action))
+        .or(match view.phase {
+            GamePhase::Main => Some(Action::EndTurn),
+            _ => None,
+        })
+}
+
+/// Lo que se puede comprobar desde la vista: el propio tablero y la propia mano.
+fn looks_legal(view: &PlayerView, me: &Player, action: &Action) -> bool {
+    let mut board = view.board.clone();
+    let setup_anchor = match view.phase {
+        GamePhase::Setup { anchor_vertex, .. } => Some(anchor_vertex),
+        _ => None,
+    };
+    match *action {
+        Action::PlaceSettlement { vertex } => {
+            place_house(&mut board, me.id, vertex, setup_anchor.is_some()).is_ok()
+        }
+        Action::PlaceCity { vertex } => place_city(&mut board, me.id, vertex).is_ok(),
+        Action::PlaceShip { edge } => place_ship(&mut board, me.id, edge, false).is_ok(),
+        Action::BuildKnight { vertex } => place_knight(&mut board, me.id, vertex).is_ok(),
+        Action::ActivateKnight { vertex } => activate_knight(&mut board, me.id, vertex, false).is_ok(),
+        Action::PromoteKnight { vertex } => promote_knight(&mut board, me.id, vertex, false).is_ok(),
+        Action::BuildImprovement { track } => build_improvement(&mut board, me.id, track).is_ok(),
+        Action::PlayProgress { play } => play_progress_card(&mut board, me.id, play).is_ok(),
+        Action::PlaceRoad { edge } => {
+            let phase = match setup_anchor {
+                Some(Some(anchor_vertex)) => TurnPhase::Setup { anchor_vertex },
+                _ => TurnPhase::Normal,
+            };
+            place_road(&mut board, me.id, edge, phase).is_ok()
+        }
+        Action::BuyDevCard => view.deck_size > 0 && has_resources(me, DEVELOPMENT_CARD_COST),
+        Action::BankTrade { give, get } => {
+            give != get && *me.resources.get(&give).unwrap_or(&0) >= bank_rate(&view.board.rules, me, give)
+        }
+        Action::MoveRobber { tile, victim } => {
+            let candidates = view.robbable_players(tile, me.id);
+            tile < view.board.tiles.len()
+                && !view.board.tiles[tile].has_robber
+                && !view.board.tiles[tile].has_pirate
+                && match victim {
+                    Some(v) => candidates.contains(&v),
+                    None => candidates.is_empty(),
+                }
+        }
+        _ => true,
+    }
+}
+
+fn main_phase_candidates(view: &PlayerView, me: &Player) -> Vec<Action> {
+    let mut actions = Vec::new();
+
+    // ciudades sobre asentamientos propios
+    for (vertex, v) in view.board.vertices.iter().enumerate() {
+        if v.owner == Some(me.id) && v.building == Some(BuildingType::Settlement) {
+            actions.push(Action::PlaceCity { vertex });
+        }
+    }
+    for vertex in best_vertices(&view.board) {
+        actions.push(Action::PlaceSettlement { vertex });
+    }
+    if view.board.rules.cities_and_knights {
+        actions.extend(cities_and_knights_candidates(view, me));
+    }
+    actions.push(Action::BuyDevCard);
+    for edge in 0..view.board.edges.len() {
+        actions.push(Action::PlaceRoad { edge });
+    }
+    for edge in 0..view.board.edges.len() {
+        actions.push(Action::PlaceShip { edge });
+    }
+
+    // cambiamos con el banco lo que más sobra por lo que más falta
+    let counts = |m: MaterialType| *me.resources.get(&m).unwrap_or(&0);
+    let mut materials = RESOURCES.to_vec();
+    materials.sort_by_key(|&m| std::cmp::Reverse(counts(m)));
+    if let (Some(&give), Some(&get)) = (materials.first(), materials.last()) {
+        if counts(give) > counts(get) + 1 {
+            actions.push(Action::BankTrade { give, get });
+        }
+    }
+
+    actions.push(Action::EndTurn);
+    actions
+}
+
+/// Mejoras primero; después caballeros (activos si hay bárbaros cerca) y las
+/// cartas de progreso que no piden elegir nada raro.
+fn cities_and_knights_candidates(view: &PlayerView, me: &Player) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for track in ImprovementTrack::ALL {
+        actions.push(Action::BuildImprovement { track });
+    }
+    for &card in &me.progress_cards {
+        let play = match card {
+            ProgressCard::Irrigation => ProgressPlay::Irrigation,
+            ProgressCard::Mining => ProgressPlay::Mining,
+            ProgressCard::Warlord => ProgressPlay::Warlord,
+            ProgressCard::ResourceMonopoly => ProgressPlay::ResourceMonopoly { material: MaterialType::Wheat },
+            ProgressCard::TradeMonopoly => ProgressPlay::TradeMonopoly { material: MaterialType::Coin },
+            _ => continue,
+        };
+        actions.push(Action::PlayProgress { play });
+    }
+    let barbarians_close = view.board.barbarian_position + 2 >= BARBARIAN_TRACK_LENGTH;
+    for (vertex, v) in view.board.vertices.iter().enumerate() {
+        match v.knight {
+            Some(k) if k.owner == me.id && !k.active && barbarians_close => {
+                actions.push(Action::ActivateKnight { vertex });
+            }
+            Some(k) if k.owner == me.id => actions.push(Action::PromoteKnight { vertex }),
+            Some(_) => {}
+            None => actions.push(Action::BuildKnight { vertex }),
+        }
+    }
+    actions
+}
+
+const RESOURCES: [MaterialType; 5] = [
+    MaterialType::Wood, MaterialType::Brick, MaterialType::Sheep,
+    MaterialType::Wheat, MaterialType::Stone,
+];
+
+/// Puntos de probabilidad de una ficha (6 y 8 valen 5, 2 y 12 valen 1).
+pub fn pips(number: u8) -> u8 {
+    match number {
+        2..=6 => number - 1,
+        8..=12 => 13 - number,
+        _ => 0,
+    }
+}
+
+/// Vértices ordenados por producción esperada, de mayor a menor.
+fn best_vertices(board: &Board) -> Vec<VertexId> {
+    let mut scored: Vec<(VertexId, u32)> = board
+        .vertices
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.owner.is_none())
+        .map(|(id, v)| {
+            let score = v
+                .adjacent_tiles
+                .iter()
+                .map(|&t| pips(board.tiles[t].number) as u32)
+                .sum();
+            (id, score)
+        })
+        .collect();
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(id, _)| id).collect()
+}
+
+/// Descarta siempre de lo que más tiene.
+fn discard_action(rules: &RuleSet, me: &Player) -> Action {
+    let mut materials = Vec::new();
+    let mut hand: HashMap<MaterialType, u8> = me.resources.clone();
+    for _ in 0..cards_to_discard(rules, me) {
+        let Some((&material, _)) = hand.iter().filter(|(_, &c)| c > 0).max_by_key(|(_, &c)| c) else {
+            break;
+        };
+        *hand.get_mut(&material).unwrap() -= 1;
+        materials.push(material);
+    }
+    Action::Discard { materials }
+}
+
+/// El oro va a lo que menos tiene.
+fn gold_action(me: &Player, amount: u8) -> Action {
+    let mut materials = Vec::new();
+    let mut hand: HashMap<MaterialType, u8> = RESOURCES
+        .iter()
+        .map(|&m| (m, *me.resources.get(&m).unwrap_or(&0)))
+        .collect();
+    for _ in 0..amount {
+        let Some((&material, _)) = hand.iter().min_by_key(|(_, &c)| c) else {
+            break;
+        };
+        *hand.get_mut(&material).unwrap() += 1;
+        materials.push(material);
+    }
+    Action::ChooseGold { materials }
+}
+
+/// Casillas para el ladrón: primero las que más rivales tocan y no nos tocan a nosotros.
+/// Entre las víctimas posibles, la que más cartas tiene en la mano.
+fn robber_targets(view: &PlayerView, seat: PlayerType) -> Vec<(TileId, Option<PlayerType>)> {
+    let mut targets: Vec<(TileId, Option<PlayerType>, i32)> = Vec::new();
+    for (tile_id, tile) in view.board.tiles.iter().enumerate() {
+        if tile.has_robber || tile.has_pirate {
+            continue;
+        }
+        let mut score = pips(tile.number) as i32;
+        for &v in &tile.vertices {
+            match view.board.vertices[v].owner {
+                Some(owner) if owner == seat => score -= 10,
+                Some(_) => score += 3,
+                None => {}
+            }
+        }
+        let victim = view
+            .robbable_players(tile_id, seat)
+            .into_iter()
+            .max_by_key(|&p| view.hand_size(p));
+        targets.push((tile_id, victim, score));
+    }
+    targets.sort_by_key(|&(_, _, score)| std::cmp::Reverse(score));
+    targets.into_iter().map(|(tile, victim, _)| (tile, victim)).collect()
+}
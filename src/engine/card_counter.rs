@@ -0,0 +1,449 @@
+// en src/engine/card_counter.rs
+//
+// Conteo de cartas: lo que un jugador atento puede deducir de las manos
+// rivales mirando sólo los eventos públicos (producción, construcciones,
+// comercio con el banco y entre jugadores, monopolios y robos). Para
+// cada jugador se guarda el conjunto de manos posibles; los robos sin
+// material visible abren una rama por cada carta que la víctima podía
+// tener.
+
+use crate::game_logic::{
+    CITY_COST, DEVELOPMENT_CARD_COST, KNIGHT_ACTIVATION_COST, KNIGHT_COST, KNIGHT_PROMOTION_COST, ROAD_COST,
+    SETTLEMENT_COST, SHIP_COST,
+};
+use crate::types::*;
+use super::events::GameEvent;
+use super::game::GamePhase;
+use super::view::PlayerView;
+use rand::seq::IteratorRandom;
+use std::collections::{BTreeSet, HashMap};
+
+const MATERIALS: [MaterialType; 5] = [
+    MaterialType::Wood, MaterialType::Brick, MaterialType::Sheep,
+    MaterialType::Wheat, MaterialType::Stone,
+];
+
+/// Cantidades en el orden de `MATERIALS`.
+type Hand = [u8; 5];
+
+/// Tope de manos posibles por jugador; pasado esto se guarda una muestra al azar.
+const MAX_HANDS: usize = 4096;
+
+/// Cuántas cartas de un material puede tener un jugador, como mínimo y como máximo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandRange {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl HandRange {
+    pub fn is_certain(&self) -> bool {
+        self.min == self.max
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CardCounter {
+    hands: HashMap<PlayerType, BTreeSet<Hand>>,
+    /// En la fundación los asentamientos y caminos no cuestan nada.
+    in_setup: bool,
+    /// Caminos gratis que quedan de una carta de Construcción de Caminos.
+    /// Sus `RoadBuilt` llegan justo después; cualquier otro evento los cancela.
+    free_roads: u8,
+}
+
+impl CardCounter {
+    /// Todos empiezan sin cartas.
+    pub fn new(players: &[PlayerType]) -> Self {
+        CardCounter {
+            hands: players.iter().map(|&p| (p, BTreeSet::from([[0; 5]]))).collect(),
+            in_setup: true,
+            free_roads: 0,
+        }
+    }
+
+    /// Para quien llega con la partida empezada: sólo se sabe cuántas cartas tiene cada uno.
+    pub fn from_view(view: &PlayerView) -> Self {
+        let mut counter = Self::new(&view.turn_order);
+        counter.in_setup = matches!(view.phase, GamePhase::Setup { .. });
+        counter.sync(view);
+        counter
+    }
+
+    /// Incorpora un evento público.
+    pub fn observe(&mut self, event: &GameEvent) {
+        if !matches!(event, GameEvent::RoadBuilt { .. }) {
+            self.free_roads = 0;
+        }
+        match event {
+            GameEvent::GameStarted { turn_order } => *self = Self::new(turn_order),
+            GameEvent::StartingResources { player, materials } | GameEvent::ResourcesFromBank { player, materials } => {
+                for &material in materials {
+                    self.gain(*player, material, 1);
+                }
+            }
+            GameEvent::DiceRolled { .. } => self.in_setup = false,
+            GameEvent::ResourcesProduced { player, material, amount } => self.gain(*player, *material, *amount),
+            GameEvent::SettlementBuilt { player, .. } if !self.in_setup => self.spend(*player, SETTLEMENT_COST),
+            GameEvent::CityBuilt { player, .. } if !self.in_setup => self.spend(*player, CITY_COST),
+            GameEvent::RoadBuilt { player, .. } if !self.in_setup => {
+                if self.free_roads > 0 {
+                    self.free_roads -= 1;
+                } else {
+                    self.spend(*player, ROAD_COST);
+                }
+            }
+            GameEvent::ShipBuilt { player, .. } => self.spend(*player, SHIP_COST),
+            GameEvent::GoldChosen { player, materials } => {
+                for &material in materials {
+                    self.gain(*player, material, 1);
+                }
+            }
+            GameEvent::DevCardBought { player, .. } => self.spend(*player, DEVELOPMENT_CARD_COST),
+            GameEvent::DevCardPlayed { card: DevelopmentCard::RoadBuilding, .. }
+            | GameEvent::ProgressCardPlayed { card: ProgressCard::RoadBuilding, .. } => self.free_roads = 2,
+            GameEvent::ExtraCostPaid { player, cost } => self.spend(*player, cost),
+            GameEvent::TokensExchanged { player, reward, .. } => match reward {
+                TokenReward::Resource(material) => self.gain(*player, *material, 1),
+                TokenReward::Road(_) => self.free_roads = 1,
+                TokenReward::RemoveRobber => {}
+            },
+            GameEvent::KnightBuilt { player, .. } => self.spend(*player, KNIGHT_COST),
+            GameEvent::KnightActivated { player, .. } => self.spend(*player, KNIGHT_ACTIVATION_COST),
+            GameEvent::KnightPromoted { player, .. } => self.spend(*player, KNIGHT_PROMOTION_COST),
+            GameEvent::Discarded { player, materials } => {
+                for &material in materials {
+                    self.spend(*player, &[(material, 1)]);
+                }
+            }
+            GameEvent::ResourceStolen { thief, victim, material: Some(material) } => {
+                self.spend(*victim, &[(*material, 1)]);
+                self.gain(*thief, *material, 1);
+            }
+            GameEvent::ResourceStolen { thief, victim, material: None } => self.unknown_transfer(*thief, *victim),
+            GameEvent::MonopolyPlayed { player, material, stolen } => {
+                for &(victim, amount) in stolen {
+                    self.spend(victim, &[(*material, amount)]);
+                    self.gain(*player, *material, amount);
+                }
+            }
+            GameEvent::BankTrade { player, gave, amount, got } => {
+                self.spend(*player, &[(*gave, *amount)]);
+                self.gain(*player, *got, 1);
+            }
+            GameEvent::PlayersTraded { player, partner, gave, got } => {
+                for &material in gave {
+                    self.spend(*player, &[(material, 1)]);
+                    self.gain(*partner, material, 1);
+                }
+                for &material in got {
+                    self.spend(*partner, &[(material, 1)]);
+                    self.gain(*player, material, 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Ajusta con lo que se ve en la mesa: la mano propia entera y cuántas cartas tiene cada rival.
+    pub fn sync(&mut self, view: &PlayerView) {
+        for player in &view.board.players {
+            let set = self.hands.entry(player.id).or_default();
+            if view.omniscient || Some(player.id) == view.viewer {
+                *set = BTreeSet::from([to_hand(&player.resources)]);
+                continue;
+            }
+            let size = view.hand_size(player.id);
+            set.retain(|h| total(h) == size);
+            if set.is_empty() {
+                // nos perdimos algo (o recién llegamos): cualquier reparto de esa cantidad
+                *set = all_hands(size);
+            }
+        }
+    }
+
+    pub fn range(&self, player: PlayerType, material: MaterialType) -> HandRange {
+        let Some(i) = index(material) else {
+            return HandRange { min: 0, max: 0 };
+        };
+        let set = self.hands.get(&player);
+        let counts = || set.into_iter().flatten().map(|h| h[i]);
+        HandRange {
+            min: counts().min().unwrap_or(0),
+            max: counts().max().unwrap_or(0),
+        }
+    }
+
+    /// Rangos de los cinco recursos, en orden.
+    pub fn ranges(&self, player: PlayerType) -> Vec<(MaterialType, HandRange)> {
+        MATERIALS.iter().map(|&m| (m, self.range(player, m))).collect()
+    }
+
+    pub fn possible_hands(&self, player: PlayerType) -> usize {
+        self.hands.get(&player).map_or(0, |set| set.len())
+    }
+
+    /// La mano exacta, si sólo queda una posibilidad.
+    pub fn certain_hand(&self, player: PlayerType) -> Option<HashMap<MaterialType, u8>> {
+        let set = self.hands.get(&player)?;
+        if set.len() != 1 {
+            return None;
+        }
+        let hand = set.first()?;
+        Some(MATERIALS.iter().zip(hand).filter(|(_, &c)| c > 0).map(|(&m, &c)| (m, c)).collect())
+    }
+
+    /// Proporción de manos posibles con las que `player` puede pagar `cost`.
+    pub fn chance_to_afford(&self, player: PlayerType, cost: &[(MaterialType, u8)]) -> f32 {
+        let Some(set) = self.hands.get(&player).filter(|s| !s.is_empty()) else {
+            return 0.0;
+        };
+        let affordable = set.iter().filter(|h| can_pay(h, cost)).count();
+        affordable as f32 / set.len() as f32
+    }
+
+    // -------------------------------------------------------------------------
+    // ACTUALIZACIONES
+    // -------------------------------------------------------------------------
+
+    fn gain(&mut self, player: PlayerType, material: MaterialType, amount: u8) {
+        let Some(i) = index(material) else {
+            return;
+        };
+        let set = self.hands.entry(player).or_default();
+        *set = set
+            .iter()
+            .map(|h| {
+                let mut h = *h;
+                h[i] += amount;
+                h
+            })
+            .collect();
+    }
+
+    /// Quita `cost` y descarta las manos que no podían pagarlo.
+    fn spend(&mut self, player: PlayerType, cost: &[(MaterialType, u8)]) {
+        let set = self.hands.entry(player).or_default();
+        let paid: BTreeSet<Hand> = set.iter().filter(|h| can_pay(h, cost)).map(|h| pay(h, cost)).collect();
+        *set = if paid.is_empty() {
+            // ninguna cuadra (nos perdimos algo): restamos lo que se pueda
+            set.iter().map(|h| pay(h, cost)).collect()
+        } else {
+            paid
+        };
+    }
+
+    /// Robo sin material visible: la víctima pierde cualquiera de sus cartas y el ladrón la gana.
+    fn unknown_transfer(&mut self, thief: PlayerType, victim: PlayerType) {
+        let victim_hands = self.hands.remove(&victim).unwrap_or_default();
+        let mut new_victim = BTreeSet::new();
+        let mut could_be = [false; 5];
+        for hand in &victim_hands {
+            for i in 0..MATERIALS.len() {
+                if hand[i] > 0 {
+                    let mut h = *hand;
+                    h[i] -= 1;
+                    new_victim.insert(h);
+                    could_be[i] = true;
+                }
+            }
+        }
+        if new_victim.is_empty() {
+            new_victim = victim_hands;
+        }
+        self.hands.insert(victim, cap(new_victim));
+
+        let thief_hands = self.hands.entry(thief).or_default();
+        let mut new_thief = BTreeSet::new();
+        for hand in thief_hands.iter() {
+            for i in (0..MATERIALS.len()).filter(|&i| could_be[i]) {
+                let mut h = *hand;
+                h[i] += 1;
+                new_thief.insert(h);
+            }
+        }
+        if !new_thief.is_empty() {
+            *thief_hands = cap(new_thief);
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// HELPERS
+// -----------------------------------------------------------------------------
+
+fn index(material: MaterialType) -> Option<usize> {
+    MATERIALS.iter().position(|&m| m == material)
+}
+
+fn to_hand(resources: &HashMap<MaterialType, u8>) -> Hand {
+    let mut hand = [0; 5];
+    for (i, m) in MATERIALS.iter().enumerate() {
+        hand[i] = *resources.get(m).unwrap_or(&0);
+    }
+    hand
+}
+
+fn total(hand: &Hand) -> u8 {
+    hand.iter().sum()
+}
+
+fn can_pay(hand: &Hand, cost: &[(MaterialType, u8)]) -> bool {
+    cost.iter().all(|&(m, amount)| index(m).is_some_and(|i| hand[i] >= amount))
+}
+
+fn pay(hand: &Hand, cost: &[(MaterialType, u8)]) -> Hand {
+    let mut h = *hand;
+    for &(m, amount) in cost {
+        if let Some(i) = index(m) {
+            h[i] = h[i].saturating_sub(amount);
+        }
+    }
+    h
+}
+
+/// Todas las formas de repartir `size` cartas entre los cinco recursos.
+fn all_hands(size: u8) -> BTreeSet<Hand> {
+    fn fill(hand: &mut Hand, i: usize, left: u8, out: &mut BTreeSet<Hand>) {
+        if i == hand.len() - 1 {
+            hand[i] = left;
+            out.insert(*hand);
+            return;
+        }
+        for n in 0..=left {
+            hand[i] = n;
+            fill(hand, i + 1, left - n, out);
+        }
+    }
+    let mut out = BTreeSet::new();
+    fill(&mut [0; 5], 0, size, &mut out);
+    cap(out)
+}
+
+/// Deja `MAX_HANDS` manos: los extremos de cada material, para que `range`
+/// siga siendo exacto, y el resto elegido al azar, para no torcer
+/// `chance_to_afford` hacia ningún material.
+fn cap(set: BTreeSet<Hand>) -> BTreeSet<Hand> {
+    if set.len() <= MAX_HANDS {
+        return set;
+    }
+    let mut kept = BTreeSet::new();
+    for i in 0..MATERIALS.len() {
+        kept.extend(set.iter().min_by_key(|h| h[i]).copied());
+        kept.extend(set.iter().max_by_key(|h| h[i]).copied());
+    }
+    let sample = set
+        .iter()
+        .filter(|h| !kept.contains(*h))
+        .copied()
+        .choose_multiple(&mut rand::rng(), MAX_HANDS - kept.len());
+    kept.extend(sample);
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Game;
+    use PlayerType::{Player1, Player2, Player3};
+
+    fn counter_with(hands: &[(PlayerType, &[(MaterialType, u8)])]) -> CardCounter {
+        let players: Vec<_> = hands.iter().map(|&(p, _)| p).collect();
+        let mut counter = CardCounter::new(&players);
+        counter.in_setup = false;
+        for &(player, hand) in hands {
+            for &(material, amount) in hand {
+                counter.observe(&GameEvent::ResourcesProduced { player, material, amount });
+            }
+        }
+        counter
+    }
+
+    #[test]
+    fn known_steal_moves_the_card() {
+        let mut counter = counter_with(&[(Player1, &[]), (Player2, &[(MaterialType::Wood, 2)])]);
+        counter.observe(&GameEvent::ResourceStolen {
+            thief: Player1,
+            victim: Player2,
+            material: Some(MaterialType::Wood),
+        });
+        let wood = HashMap::from([(MaterialType::Wood, 1)]);
+        assert_eq!(counter.certain_hand(Player1), Some(wood.clone()));
+        assert_eq!(counter.certain_hand(Player2), Some(wood));
+    }
+
+    #[test]
+    fn unknown_steal_branches_on_the_victim_hand() {
+        let mut counter = counter_with(&[
+            (Player1, &[]),
+            (Player2, &[(MaterialType::Wood, 1), (MaterialType::Brick, 1)]),
+        ]);
+        counter.observe(&GameEvent::ResourceStolen { thief: Player1, victim: Player2, material: None });
+
+        assert_eq!(counter.possible_hands(Player1), 2);
+        assert_eq!(counter.possible_hands(Player2), 2);
+        assert_eq!(counter.range(Player1, MaterialType::Wood), HandRange { min: 0, max: 1 });
+        assert_eq!(counter.range(Player1, MaterialType::Sheep), HandRange { min: 0, max: 0 });
+        assert_eq!(counter.chance_to_afford(Player1, &[(MaterialType::Brick, 1)]), 0.5);
+        assert_eq!(counter.chance_to_afford(Player2, &[(MaterialType::Wood, 1), (MaterialType::Brick, 1)]), 0.0);
+    }
+
+    #[test]
+    fn monopoly_takes_everything_announced() {
+        let mut counter = counter_with(&[
+            (Player1, &[]),
+            (Player2, &[(MaterialType::Wheat, 2), (MaterialType::Stone, 1)]),
+            (Player3, &[(MaterialType::Wheat, 1)]),
+        ]);
+        counter.observe(&GameEvent::MonopolyPlayed {
+            player: Player1,
+            material: MaterialType::Wheat,
+            stolen: vec![(Player2, 2), (Player3, 1)],
+        });
+        assert_eq!(counter.certain_hand(Player1), Some(HashMap::from([(MaterialType::Wheat, 3)])));
+        assert_eq!(counter.certain_hand(Player2), Some(HashMap::from([(MaterialType::Stone, 1)])));
+        assert_eq!(counter.certain_hand(Player3), Some(HashMap::new()));
+    }
+
+    #[test]
+    fn sync_keeps_own_hand_and_matching_rival_hands() {
+        let mut game = Game::new(2);
+        game.board.players[0].resources = HashMap::from([(MaterialType::Sheep, 2)]);
+        game.board.players[1].resources = HashMap::from([(MaterialType::Wood, 1), (MaterialType::Brick, 1)]);
+        let view = PlayerView::for_viewer(&game, Some(Player1));
+
+        // lo que sabíamos de Player2 cuadra con sus dos cartas y se conserva
+        let mut counter = counter_with(&[(Player1, &[]), (Player2, &[(MaterialType::Wood, 1)])]);
+        counter.observe(&GameEvent::ResourcesProduced { player: Player2, material: MaterialType::Brick, amount: 1 });
+        counter.sync(&view);
+        assert_eq!(counter.certain_hand(Player1), Some(HashMap::from([(MaterialType::Sheep, 2)])));
+        assert_eq!(counter.possible_hands(Player2), 1);
+
+        // sin historia, cualquier reparto de dos cartas entre cinco recursos
+        let counter = CardCounter::from_view(&view);
+        assert_eq!(counter.possible_hands(Player2), 15);
+        assert_eq!(counter.range(Player2, MaterialType::Stone), HandRange { min: 0, max: 2 });
+    }
+
+    #[test]
+    fn cap_keeps_the_true_range_of_every_material() {
+        let hands = all_hands(30);
+        assert_eq!(hands.len(), MAX_HANDS);
+        let mut counter = CardCounter::new(&[Player1]);
+        counter.hands.insert(Player1, hands);
+        for material in MATERIALS {
+            assert_eq!(counter.range(Player1, material), HandRange { min: 0, max: 30 });
+        }
+    }
+
+    #[test]
+    fn unused_free_roads_do_not_carry_over() {
+        let mut counter = counter_with(&[(Player1, &[(MaterialType::Wood, 1), (MaterialType::Brick, 1)])]);
+        // Construcción de Caminos de progreso con lugar para un solo camino
+        counter.observe(&GameEvent::ProgressCardPlayed { player: Player1, card: ProgressCard::RoadBuilding });
+        counter.observe(&GameEvent::RoadBuilt { player: Player1, edge: 3 });
+        counter.observe(&GameEvent::TurnEnded { player: Player1, next: Player1 });
+        counter.observe(&GameEvent::RoadBuilt { player: Player1, edge: 4 });
+        assert_eq!(counter.certain_hand(Player1), Some(HashMap::new()));
+    }
+}
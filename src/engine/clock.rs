@@ -0,0 +1,154 @@
+// en src/engine/clock.rs
+//
+// Relojes de la partida. El motor no mira la hora: quien lo hospeda
+// llama a `Game::advance_clock` con el tiempo que pasó, y acá se
+// descuenta del reloj que corresponda.
+
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Límites de tiempo, en segundos. `None` = sin límite.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Tiempo para cada turno completo.
+    pub turn_secs: Option<u32>,
+    /// Reserva de cada jugador para toda la partida, como en el ajedrez.
+    pub game_secs: Option<u32>,
+    /// Para decidir fuera del turno propio (descartes, oro y responder ofertas).
+    pub decision_secs: Option<u32>,
+}
+
+impl ClockConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.turn_secs.is_some() || self.game_secs.is_some() || self.decision_secs.is_some()
+    }
+}
+
+/// Lo que queda en cada reloj, en milisegundos.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Clocks {
+    pub config: ClockConfig,
+    pub turn_left_ms: Option<u64>,
+    pub decision_left_ms: Option<u64>,
+    pub bank_ms: HashMap<PlayerType, u64>,
+}
+
+impl Clocks {
+    pub fn new(config: ClockConfig, players: &[PlayerType]) -> Self {
+        let bank_ms = match config.game_secs {
+            Some(secs) => players.iter().map(|&p| (p, secs as u64 * 1000)).collect(),
+            None => HashMap::new(),
+        };
+        let mut clocks = Clocks { config, turn_left_ms: None, decision_left_ms: None, bank_ms };
+        clocks.start_turn();
+        clocks
+    }
+
+    pub fn start_turn(&mut self) {
+        self.turn_left_ms = self.config.turn_secs.map(|s| s as u64 * 1000);
+    }
+
+    pub fn start_decision(&mut self) {
+        self.decision_left_ms = self.config.decision_secs.map(|s| s as u64 * 1000);
+    }
+
+    /// Descuenta `elapsed` del turno y de la reserva de `player`.
+    /// Devuelve `true` si se le acabó el tiempo.
+    pub fn charge_turn(&mut self, player: PlayerType, elapsed: Duration) -> bool {
+        let ms = elapsed.as_millis() as u64;
+        let mut expired = false;
+        if let Some(left) = self.turn_left_ms.as_mut() {
+            *left = left.saturating_sub(ms);
+            expired |= *left == 0;
+        }
+        if let Some(bank) = self.bank_ms.get_mut(&player) {
+            *bank = bank.saturating_sub(ms);
+            expired |= *bank == 0;
+        }
+        expired
+    }
+
+    /// Descuenta `elapsed` de la decisión en curso. Devuelve `true` si venció.
+    pub fn charge_decision(&mut self, elapsed: Duration) -> bool {
+        match self.decision_left_ms.as_mut() {
+            Some(left) => {
+                *left = left.saturating_sub(elapsed.as_millis() as u64);
+                *left == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Lo que le queda a `player` en su turno: el menor entre el turno y su reserva.
+    pub fn time_left(&self, player: PlayerType) -> Option<Duration> {
+        let bank = self.bank_ms.get(&player).copied();
+        let left = match (self.turn_left_ms, bank) {
+            (Some(turn), Some(bank)) => Some(turn.min(bank)),
+            (turn, bank) => turn.or(bank),
+        };
+        left.map(Duration::from_millis)
+    }
+
+    pub fn decision_left(&self) -> Option<Duration> {
+        self.decision_left_ms.map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PlayerType::{Player1, Player2};
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn turn_clock_runs_out_and_restarts_each_turn() {
+        let config = ClockConfig { turn_secs: Some(10), ..Default::default() };
+        let mut clocks = Clocks::new(config, &[Player1, Player2]);
+        assert!(!clocks.charge_turn(Player1, 9 * SEC));
+        assert_eq!(clocks.time_left(Player1), Some(SEC));
+        assert!(clocks.charge_turn(Player1, 2 * SEC));
+        assert_eq!(clocks.time_left(Player1), Some(Duration::ZERO));
+
+        clocks.start_turn();
+        assert_eq!(clocks.time_left(Player2), Some(10 * SEC));
+    }
+
+    #[test]
+    fn game_clock_is_a_reserve_per_player() {
+        let config = ClockConfig { turn_secs: Some(60), game_secs: Some(30), ..Default::default() };
+        let mut clocks = Clocks::new(config, &[Player1, Player2]);
+        assert!(!clocks.charge_turn(Player1, 20 * SEC));
+        clocks.start_turn();
+        // le queda menos de la reserva que del turno
+        assert_eq!(clocks.time_left(Player1), Some(10 * SEC));
+        assert_eq!(clocks.time_left(Player2), Some(30 * SEC));
+        assert!(clocks.charge_turn(Player1, 10 * SEC));
+        assert!(!clocks.charge_turn(Player2, 10 * SEC));
+    }
+
+    #[test]
+    fn decision_clock_is_separate_from_the_turn() {
+        let config = ClockConfig { turn_secs: Some(60), decision_secs: Some(5), ..Default::default() };
+        let mut clocks = Clocks::new(config, &[Player1, Player2]);
+        assert_eq!(clocks.decision_left(), None);
+        clocks.start_decision();
+        assert!(!clocks.charge_decision(4 * SEC));
+        assert!(clocks.charge_decision(SEC));
+        assert_eq!(clocks.decision_left(), Some(Duration::ZERO));
+        assert_eq!(clocks.time_left(Player1), Some(60 * SEC));
+    }
+
+    #[test]
+    fn without_limits_nothing_runs_out() {
+        let mut clocks = Clocks::new(ClockConfig::default(), &[Player1, Player2]);
+        assert!(!clocks.config.is_enabled());
+        clocks.start_decision();
+        assert!(!clocks.charge_turn(Player1, 3600 * SEC));
+        assert!(!clocks.charge_decision(3600 * SEC));
+        assert_eq!(clocks.time_left(Player1), None);
+    }
+}
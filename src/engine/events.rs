@@ -0,0 +1,189 @@
+// en src/engine/events.rs
+
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Lo que pasó en la partida como consecuencia de una acción.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    GameStarted { turn_order: Vec<PlayerType> },
+    SettlementBuilt { player: PlayerType, vertex: VertexId },
+    CityBuilt { player: PlayerType, vertex: VertexId },
+    RoadBuilt { player: PlayerType, edge: EdgeId },
+    StartingResources { player: PlayerType, materials: Vec<MaterialType> },
+    DiceRolled { player: PlayerType, roll: u8 },
+    ResourcesProduced { player: PlayerType, material: MaterialType, amount: u8 },
+    Discarded { player: PlayerType, materials: Vec<MaterialType> },
+    RobberMoved { player: PlayerType, tile: TileId },
+    /// `material` sólo lo ven el ladrón y la víctima.
+    ResourceStolen { thief: PlayerType, victim: PlayerType, material: Option<MaterialType> },
+    /// `card` sólo la ve quien la compró.
+    DevCardBought { player: PlayerType, card: Option<DevelopmentCard> },
+    DevCardPlayed { player: PlayerType, card: DevelopmentCard },
+    ResourcesFromBank { player: PlayerType, materials: Vec<MaterialType> },
+    MonopolyPlayed { player: PlayerType, material: MaterialType, stolen: Vec<(PlayerType, u8)> },
+    BankTrade { player: PlayerType, gave: MaterialType, amount: u8, got: MaterialType },
+    TradeProposed { player: PlayerType, offer: TradeOffer },
+    TradeDeclined { player: PlayerType },
+    TradeCancelled { player: PlayerType },
+    PlayersTraded { player: PlayerType, partner: PlayerType, gave: Vec<MaterialType>, got: Vec<MaterialType> },
+    LargestArmyChanged { player: PlayerType, knights: u8 },
+    LongestRoadChanged { player: PlayerType, length: u8 },
+    ShipBuilt { player: PlayerType, edge: EdgeId },
+    ShipMoved { player: PlayerType, from: EdgeId, to: EdgeId },
+    PirateMoved { player: PlayerType, tile: TileId },
+    GoldChosen { player: PlayerType, materials: Vec<MaterialType> },
+    /// Primera casa en una isla nueva.
+    IslandBonus { player: PlayerType, points: u8 },
+    /// Ciudades y Caballeros: dado de eventos y dado rojo de la tirada.
+    EventDieRolled { player: PlayerType, face: EventDie, red: u8 },
+    BarbariansAdvanced { position: u8 },
+    BarbarianAttack { strength: u8, defense: u8 },
+    DefenderOfCatan { player: PlayerType },
+    CityPillaged { player: PlayerType, vertex: VertexId },
+    /// `card` sólo la ve quien la saca, salvo las de punto, que se juegan a la vista.
+    ProgressCardDrawn { player: PlayerType, card: Option<ProgressCard> },
+    ProgressCardPlayed { player: PlayerType, card: ProgressCard },
+    KnightBuilt { player: PlayerType, vertex: VertexId },
+    KnightActivated { player: PlayerType, vertex: VertexId },
+    KnightPromoted { player: PlayerType, vertex: VertexId, level: u8 },
+    ImprovementBuilt { player: PlayerType, track: ImprovementTrack, level: u8 },
+    MetropolisGained { player: PlayerType, track: ImprovementTrack, vertex: VertexId },
+    /// Escenarios: lo que se cobró de más por construir (un puente, por ejemplo).
+    ExtraCostPaid { player: PlayerType, cost: Vec<(MaterialType, u8)> },
+    /// Escenarios: cuántas fichas de `token` tiene ahora el jugador.
+    TokensChanged { player: PlayerType, token: String, count: u8 },
+    TokensExchanged { player: PlayerType, token: String, cost: u8, reward: TokenReward },
+    TurnEnded { player: PlayerType, next: PlayerType },
+    /// Se le acabó el reloj; lo que sigue lo jugó el motor por él.
+    TimedOut { player: PlayerType },
+    GameOver { winner: PlayerType },
+}
+
+impl GameEvent {
+    /// Versión del evento que puede ver `viewer` (`None` = alguien sin asiento).
+    pub fn visible_to(&self, viewer: Option<PlayerType>) -> GameEvent {
+        match self {
+            GameEvent::ResourceStolen { thief, victim, material } => {
+                let can_see = viewer == Some(*thief) || viewer == Some(*victim);
+                GameEvent::ResourceStolen {
+                    thief: *thief,
+                    victim: *victim,
+                    material: if can_see { *material } else { None },
+                }
+            }
+            GameEvent::DevCardBought { player, card } => GameEvent::DevCardBought {
+                player: *player,
+                card: if viewer == Some(*player) { *card } else { None },
+            },
+            GameEvent::ProgressCardDrawn { player, card } => GameEvent::ProgressCardDrawn {
+                player: *player,
+                card: card.filter(|c| viewer == Some(*player) || c.is_victory_point()),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameEvent::GameStarted { turn_order } => write!(f, "¡Empieza la partida! Orden: {:?}", turn_order),
+            GameEvent::SettlementBuilt { player, vertex } => write!(f, "{:?} construye un asentamiento en {}.", player, vertex),
+            GameEvent::CityBuilt { player, vertex } => write!(f, "{:?} construye una ciudad en {}.", player, vertex),
+            GameEvent::RoadBuilt { player, edge } => write!(f, "{:?} construye un camino en {}.", player, edge),
+            GameEvent::StartingResources { player, materials } => {
+                write!(f, "{:?} recibe sus recursos iniciales: {:?}", player, materials)
+            }
+            GameEvent::DiceRolled { player, roll } => write!(f, "{:?} saca un {}.", player, roll),
+            GameEvent::ResourcesProduced { player, material, amount } => {
+                write!(f, "- {:?} recibe {} de {:?}", player, amount, material)
+            }
+            GameEvent::Discarded { player, materials } => write!(f, "{:?} descarta {:?}", player, materials),
+            GameEvent::RobberMoved { player, tile } => write!(f, "{:?} mueve el ladrón a la casilla {}.", player, tile),
+            GameEvent::PirateMoved { player, tile } => write!(f, "{:?} mueve el pirata a la casilla {}.", player, tile),
+            GameEvent::ShipBuilt { player, edge } => write!(f, "{:?} construye un barco en {}.", player, edge),
+            GameEvent::ShipMoved { player, from, to } => write!(f, "{:?} mueve su barco de {} a {}.", player, from, to),
+            GameEvent::GoldChosen { player, materials } => write!(f, "{:?} cambia su oro por {:?}", player, materials),
+            GameEvent::IslandBonus { player, points } => {
+                write!(f, "¡{:?} llega a una isla nueva y gana {} puntos!", player, points)
+            }
+            GameEvent::EventDieRolled { face, red, .. } => match face {
+                EventDie::Barbarians => write!(f, "Dado de eventos: barco bárbaro (rojo {}).", red),
+                EventDie::Gate(track) => write!(f, "Dado de eventos: puerta de {:?} (rojo {}).", track, red),
+            },
+            GameEvent::BarbariansAdvanced { position } => {
+                write!(f, "Los bárbaros avanzan ({}/{}).", position, crate::game_logic::BARBARIAN_TRACK_LENGTH)
+            }
+            GameEvent::BarbarianAttack { strength, defense } => {
+                write!(f, "¡Atacan los bárbaros! Fuerza {} contra {} de los caballeros.", strength, defense)
+            }
+            GameEvent::DefenderOfCatan { player } => write!(f, "¡{:?} es Defensor de Catán!", player),
+            GameEvent::CityPillaged { player, vertex } => {
+                write!(f, "Los bárbaros saquean la ciudad de {:?} en {}.", player, vertex)
+            }
+            GameEvent::ProgressCardDrawn { player, card } => match card {
+                Some(c) => write!(f, "{:?} saca una carta de progreso: {:?}.", player, c),
+                None => write!(f, "{:?} saca una carta de progreso.", player),
+            },
+            GameEvent::ProgressCardPlayed { player, card } => write!(f, "{:?} juega {:?}.", player, card),
+            GameEvent::KnightBuilt { player, vertex } => write!(f, "{:?} pone un caballero en {}.", player, vertex),
+            GameEvent::KnightActivated { player, vertex } => {
+                write!(f, "{:?} activa su caballero en {}.", player, vertex)
+            }
+            GameEvent::KnightPromoted { player, vertex, level } => {
+                write!(f, "{:?} asciende su caballero en {} a nivel {}.", player, vertex, level)
+            }
+            GameEvent::ImprovementBuilt { player, track, level } => {
+                write!(f, "{:?} mejora {:?} a nivel {}.", player, track, level)
+            }
+            GameEvent::MetropolisGained { player, track, vertex } => {
+                write!(f, "¡{:?} tiene la metrópolis de {:?} en {}!", player, track, vertex)
+            }
+            GameEvent::ExtraCostPaid { player, cost } => write!(f, "{:?} paga de más {:?}.", player, cost),
+            GameEvent::TokensChanged { player, token, count } => write!(f, "{:?} tiene {} de {}.", player, count, token),
+            GameEvent::TokensExchanged { player, token, cost, reward } => {
+                write!(f, "{:?} canjea {} de {} por {:?}.", player, cost, token, reward)
+            }
+            GameEvent::ResourceStolen { thief, victim, material } => match material {
+                Some(m) => write!(f, "{:?} le roba 1 de {:?} a {:?}.", thief, m, victim),
+                None => write!(f, "{:?} le roba una carta a {:?}.", thief, victim),
+            },
+            GameEvent::DevCardBought { player, card } => match card {
+                Some(c) => write!(f, "{:?} compra una carta de desarrollo: {:?}.", player, c),
+                None => write!(f, "{:?} compra una carta de desarrollo.", player),
+            },
+            GameEvent::DevCardPlayed { player, card } => write!(f, "{:?} juega {:?}.", player, card),
+            GameEvent::ResourcesFromBank { player, materials } => {
+                write!(f, "{:?} toma del banco {:?}.", player, materials)
+            }
+            GameEvent::MonopolyPlayed { player, material, stolen } => {
+                let total: u32 = stolen.iter().map(|&(_, n)| n as u32).sum();
+                write!(f, "{:?} monopoliza {:?} y se lleva {}.", player, material, total)
+            }
+            GameEvent::BankTrade { player, gave, amount, got } => {
+                write!(f, "{:?} cambia {} de {:?} por 1 de {:?}.", player, amount, gave, got)
+            }
+            GameEvent::TradeProposed { player, offer } => match offer.to {
+                Some(to) => write!(f, "{:?} le ofrece a {:?} {:?} por {:?}.", player, to, offer.give, offer.get),
+                None => write!(f, "{:?} ofrece {:?} por {:?}.", player, offer.give, offer.get),
+            },
+            GameEvent::TradeDeclined { player } => write!(f, "{:?} rechaza la oferta.", player),
+            GameEvent::TradeCancelled { player } => write!(f, "{:?} retira su oferta.", player),
+            GameEvent::PlayersTraded { player, partner, gave, got } => {
+                write!(f, "{:?} le da {:?} a {:?} a cambio de {:?}.", player, gave, partner, got)
+            }
+            GameEvent::LargestArmyChanged { player, knights } => {
+                write!(f, "¡{:?} tiene el Mayor Ejército ({} caballeros)!", player, knights)
+            }
+            GameEvent::LongestRoadChanged { player, length } => {
+                write!(f, "¡{:?} tiene el Camino Más Largo ({} segmentos)!", player, length)
+            }
+            GameEvent::TurnEnded { player, next } => write!(f, "{:?} termina su turno. Le toca a {:?}.", player, next),
+            GameEvent::TimedOut { player } => write!(f, "{:?} se quedó sin tiempo.", player),
+            GameEvent::GameOver { winner } => write!(f, "¡JUEGO TERMINADO! ¡El ganador es {:?}!", winner),
+        }
+    }
+}
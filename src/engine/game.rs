@@ -0,0 +1,1137 @@
+// en src/engine/game.rs
+
+use crate::development_cards::*;
+use crate::dice::DiceMode;
+use crate::game_logic::*;
+use crate::rules::RuleSet;
+use crate::setup::{add_player, set_rules, setup_board_with_layout, MapLayout};
+use crate::types::*;
+use super::actions::Action;
+use super::bot;
+use super::clock::{ClockConfig, Clocks};
+use super::events::GameEvent;
+use super::view::{KnownSteal, PlayerView};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Cota de acciones automáticas seguidas cuando a alguien se le acaba el tiempo.
+const MAX_AUTO_ACTIONS: usize = 10;
+
+/// En qué punto del turno está la partida.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamePhase {
+    /// Fundación: la ronda 1 va en orden y la 2 en orden inverso.
+    /// `anchor_vertex` es la casa recién puesta, a la que debe tocar el camino.
+    Setup { round: u8, anchor_vertex: Option<VertexId> },
+    /// El jugador actual tiene que tirar los dados.
+    Roll,
+    /// Salió un 7: estos jugadores todavía tienen que descartar.
+    Discard { pending: Vec<PlayerType> },
+    /// Navegantes: produjeron campos de oro y estos jugadores eligen sus cartas.
+    GoldChoice { pending: Vec<(PlayerType, u8)> },
+    /// El jugador actual tiene que mover el ladrón.
+    MoveRobber,
+    /// Construir, comerciar, jugar cartas o terminar el turno.
+    Main,
+    Finished { winner: PlayerType },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Game {
+    pub board: Board,
+    pub turn_order: Vec<PlayerType>,
+    /// Índice en `turn_order` del jugador que tiene el turno.
+    pub current: usize,
+    pub phase: GamePhase,
+    pub turn_number: u32,
+    pub last_roll: Option<u8>,
+    /// Todos los robos, con el material; `PlayerView` decide quién lo ve.
+    pub steals: Vec<KnownSteal>,
+    /// Relojes; sin límites salvo que se configuren con `with_clocks`.
+    #[serde(default)]
+    pub clocks: Clocks,
+    /// Oferta de comercio abierta del jugador de turno.
+    #[serde(default)]
+    pub trade: Option<PendingTrade>,
+}
+
+/// Oferta de comercio esperando respuesta.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTrade {
+    pub from: PlayerType,
+    pub offer: TradeOffer,
+    /// Quienes ya la rechazaron.
+    pub declined: Vec<PlayerType>,
+}
+
+impl PendingTrade {
+    /// Quienes todavía pueden aceptarla o rechazarla, en orden de turno.
+    pub fn waiting_on(&self, turn_order: &[PlayerType]) -> Vec<PlayerType> {
+        turn_order
+            .iter()
+            .copied()
+            .filter(|&p| p != self.from && self.offer.to.is_none_or(|to| to == p) && !self.declined.contains(&p))
+            .collect()
+    }
+}
+
+impl Game {
+    /// Tablero nuevo con `num_players` jugadores (2-4), listo para la fundación.
+    pub fn new(num_players: usize) -> Self {
+        Self::with_options(num_players, MapLayout::Random, RuleSet::default())
+    }
+
+    pub fn with_options(num_players: usize, layout: MapLayout, rules: RuleSet) -> Self {
+        let mut board = setup_board_with_layout(layout);
+        set_rules(&mut board, layout.adjust_rules(rules));
+        let mut turn_order = Vec::new();
+        for _ in 0..num_players.clamp(2, 4) {
+            if let Some(id) = add_player(&mut board) {
+                turn_order.push(id);
+            }
+        }
+
+        Game {
+            board,
+            turn_order,
+            current: 0,
+            phase: GamePhase::Setup { round: 1, anchor_vertex: None },
+            turn_number: 0,
+            last_roll: None,
+            steals: Vec::new(),
+            clocks: Clocks::default(),
+            trade: None,
+        }
+    }
+
+    pub fn with_clocks(mut self, config: ClockConfig) -> Self {
+        self.clocks = Clocks::new(config, &self.turn_order);
+        self
+    }
+
+    pub fn current_player(&self) -> PlayerType {
+        self.turn_order[self.current]
+    }
+
+    pub fn winner(&self) -> Option<PlayerType> {
+        match self.phase {
+            GamePhase::Finished { winner } => Some(winner),
+            _ => None,
+        }
+    }
+
+    pub fn player(&self, player_id: PlayerType) -> Option<&Player> {
+        self.board.players.iter().find(|p| p.id == player_id)
+    }
+
+    /// Valida y ejecuta la acción de `player_id`. Si falla, el estado no cambia.
+    pub fn apply(&mut self, player_id: PlayerType, action: Action) -> Result<Vec<GameEvent>, &'static str> {
+        let turn_before = (self.current, self.turn_number);
+        let was_deciding = matches!(self.phase, GamePhase::Discard { .. } | GamePhase::GoldChoice { .. });
+
+        let events = self.apply_action(player_id, action)?;
+
+        // los relojes arrancan de nuevo con cada turno y con cada ronda de descartes u oro
+        if (self.current, self.turn_number) != turn_before {
+            self.clocks.start_turn();
+        }
+        if !was_deciding && matches!(self.phase, GamePhase::Discard { .. } | GamePhase::GoldChoice { .. }) {
+            self.clocks.start_decision();
+        }
+        Ok(events)
+    }
+
+    fn apply_action(&mut self, player_id: PlayerType, action: Action) -> Result<Vec<GameEvent>, &'static str> {
+        if self.winner().is_some() {
+            return Err("La partida ya terminó.");
+        }
+        if self.player(player_id).is_none() {
+            return Err("Error: No se encontró al jugador.");
+        }
+
+        // descartar y elegir el oro es lo único que se hace fuera del propio turno
+        if let Action::Discard { materials } = &action {
+            return self.apply_discard(player_id, materials);
+        }
+        if let Action::ChooseGold { materials } = &action {
+            return self.apply_gold_choice(player_id, materials);
+        }
+        if let Action::RespondTrade { accept } = action {
+            return self.respond_trade(player_id, accept);
+        }
+        if player_id != self.current_player() {
+            return Err("No es tu turno.");
+        }
+
+        let largest_army_before = self.board.largest_army;
+        let longest_road_before = self.board.longest_road;
+        let tokens_before = self.tokens();
+
+        let mut events = match self.phase.clone() {
+            GamePhase::Setup { round, anchor_vertex } => {
+                self.apply_setup(player_id, round, anchor_vertex, action)?
+            }
+            GamePhase::Roll => match action {
+                Action::RollDice => self.roll_dice(player_id, None)?,
+                Action::EnterDice { first, second } => {
+                    if self.board.rules.dice != DiceMode::Manual {
+                        return Err("En esta partida los dados los tira la aplicación.");
+                    }
+                    self.roll_dice(player_id, Some((first, second)))?
+                }
+                Action::PlayKnight { tile, victim } => self.play_knight(player_id, tile, victim)?,
+                _ => return Err("Primero tienes que tirar los dados."),
+            },
+            GamePhase::Discard { .. } => {
+                return Err("Esperando a que los jugadores descarten.");
+            }
+            GamePhase::GoldChoice { .. } => {
+                return Err("Esperando a que los jugadores elijan su oro.");
+            }
+            GamePhase::MoveRobber => match action {
+                Action::MoveRobber { tile, victim } => {
+                    let events = self.move_robber_and_steal(player_id, tile, victim)?;
+                    self.phase = GamePhase::Main;
+                    events
+                }
+                _ => return Err("Primero tienes que mover el ladrón."),
+            },
+            GamePhase::Main => self.apply_main(player_id, action)?,
+            GamePhase::Finished { .. } => unreachable!("ya comprobado arriba"),
+        };
+
+        if self.board.largest_army != largest_army_before {
+            if let Some(holder) = self.board.largest_army {
+                events.push(GameEvent::LargestArmyChanged {
+                    player: holder,
+                    knights: self.board.largest_army_size,
+                });
+            }
+        }
+        if self.board.longest_road != longest_road_before {
+            if let Some(holder) = self.board.longest_road {
+                events.push(GameEvent::LongestRoadChanged {
+                    player: holder,
+                    length: self.board.longest_road_size,
+                });
+            }
+        }
+        events.extend(self.token_changes(&tokens_before));
+        for event in &events {
+            if let GameEvent::ResourceStolen { thief, victim, material } = *event {
+                self.steals.push(KnownSteal { thief, victim, material });
+            }
+        }
+        if let Some(winner) = check_for_winner(&self.board) {
+            self.phase = GamePhase::Finished { winner };
+            events.push(GameEvent::GameOver { winner });
+        }
+
+        Ok(events)
+    }
+
+    // -------------------------------------------------------------------------
+    // RELOJES
+    // -------------------------------------------------------------------------
+
+    /// Descuenta `elapsed` del reloj de quien está decidiendo. Si se le acabó,
+    /// el motor juega por él: tira los dados, termina el turno, descarta al
+    /// azar o, en la fundación y con el ladrón, hace lo que haría un bot.
+    pub fn advance_clock(&mut self, elapsed: Duration) -> Vec<GameEvent> {
+        if !self.clocks.config.is_enabled() || self.winner().is_some() {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        if let GamePhase::Discard { pending } = self.phase.clone() {
+            if self.clocks.charge_decision(elapsed) {
+                for player in pending {
+                    events.push(GameEvent::TimedOut { player });
+                    let materials = self.random_discard(player);
+                    if let Ok(mut discarded) = self.apply(player, Action::Discard { materials }) {
+                        events.append(&mut discarded);
+                    }
+                }
+            }
+            return events;
+        }
+        if let GamePhase::GoldChoice { pending } = self.phase.clone() {
+            if self.clocks.charge_decision(elapsed) {
+                for (player, amount) in pending {
+                    events.push(GameEvent::TimedOut { player });
+                    let materials = random_resources(amount);
+                    if let Ok(mut chosen) = self.apply(player, Action::ChooseGold { materials }) {
+                        events.append(&mut chosen);
+                    }
+                }
+            }
+            return events;
+        }
+        // a quien no respondió la oferta a tiempo se la da por rechazada
+        if let Some(trade) = &self.trade {
+            if self.clocks.charge_decision(elapsed) {
+                for player in trade.waiting_on(&self.turn_order) {
+                    events.push(GameEvent::TimedOut { player });
+                    if let Ok(mut declined) = self.apply(player, Action::RespondTrade { accept: false }) {
+                        events.append(&mut declined);
+                    }
+                }
+            }
+        }
+
+        let player = self.current_player();
+        if !self.clocks.charge_turn(player, elapsed) {
+            return events;
+        }
+        events.push(GameEvent::TimedOut { player });
+        // se juega solo hasta que pase el turno o haga falta que otros decidan
+        for _ in 0..MAX_AUTO_ACTIONS {
+            if self.current_player() != player
+                || self.winner().is_some()
+                || matches!(self.phase, GamePhase::Discard { .. } | GamePhase::GoldChoice { .. })
+            {
+                break;
+            }
+            let action = self.timeout_action(player);
+            match self.apply(player, action) {
+                Ok(mut more) => events.append(&mut more),
+                Err(_) => break,
+            }
+        }
+        events
+    }
+
+    fn timeout_action(&self, player_id: PlayerType) -> Action {
+        match self.phase {
+            GamePhase::Roll => Action::RollDice,
+            GamePhase::Main => Action::EndTurn,
+            _ => bot::choose_action(&PlayerView::for_viewer(self, Some(player_id))).unwrap_or(Action::EndTurn),
+        }
+    }
+
+    /// Alguna jugada válida para `player_id` en esta fase, para cuando un bot
+    /// se equivoca: descartar o elegir el oro al azar, rechazar la oferta
+    /// abierta, la primera casilla válida para el ladrón... `None` si ahora
+    /// no tiene nada que hacer.
+    pub fn fallback_action(&self, player_id: PlayerType) -> Option<Action> {
+        let waiting = self.trade.as_ref().is_some_and(|t| t.waiting_on(&self.turn_order).contains(&player_id));
+        let candidates: Vec<Action> = match &self.phase {
+            _ if waiting => vec![Action::RespondTrade { accept: false }],
+            GamePhase::Discard { pending } if pending.contains(&player_id) => {
+                vec![Action::Discard { materials: self.random_discard(player_id) }]
+            }
+            GamePhase::GoldChoice { pending } => pending
+                .iter()
+                .filter(|&&(p, _)| p == player_id)
+                .map(|&(_, amount)| Action::ChooseGold { materials: random_resources(amount) })
+                .collect(),
+            _ if player_id != self.current_player() => Vec::new(),
+            GamePhase::Setup { anchor_vertex: None, .. } => {
+                (0..self.board.vertices.len()).map(|vertex| Action::PlaceSettlement { vertex }).collect()
+            }
+            GamePhase::Setup { anchor_vertex: Some(_), .. } => {
+                (0..self.board.edges.len()).map(|edge| Action::PlaceRoad { edge }).collect()
+            }
+            GamePhase::Roll => vec![Action::RollDice],
+            GamePhase::MoveRobber => (0..self.board.tiles.len())
+                .flat_map(|tile| {
+                    let victims = robbable_players(&self.board, tile, player_id);
+                    let victims: Vec<Option<PlayerType>> = if victims.is_empty() {
+                        vec![None]
+                    } else {
+                        victims.into_iter().map(Some).collect()
+                    };
+                    victims.into_iter().map(move |victim| Action::MoveRobber { tile, victim })
+                })
+                .collect(),
+            GamePhase::Main => vec![Action::EndTurn],
+            _ => Vec::new(),
+        };
+        // se prueba sobre una copia para no devolver algo que el motor rechace
+        candidates.into_iter().find(|action| self.clone().apply(player_id, action.clone()).is_ok())
+    }
+
+    /// Las cartas que hay que descartar, elegidas al azar de la mano.
+    fn random_discard(&self, player_id: PlayerType) -> Vec<MaterialType> {
+        let Some(player) = self.player(player_id) else {
+            return Vec::new();
+        };
+        let mut cards: Vec<MaterialType> = player
+            .resources
+            .iter()
+            .flat_map(|(&m, &count)| std::iter::repeat_n(m, count as usize))
+            .collect();
+        let mut rng = rand::rng();
+        let mut discarded = Vec::new();
+        for _ in 0..cards_to_discard(&self.board.rules, player) {
+            if cards.is_empty() {
+                break;
+            }
+            discarded.push(cards.swap_remove(rng.random_range(0..cards.len())));
+        }
+        discarded
+    }
+
+    // -------------------------------------------------------------------------
+    // FUNDACIÓN
+    // -------------------------------------------------------------------------
+
+    fn apply_setup(
+        &mut self,
+        player_id: PlayerType,
+        round: u8,
+        anchor_vertex: Option<VertexId>,
+        action: Action,
+    ) -> Result<Vec<GameEvent>, &'static str> {
+        match (anchor_vertex, action) {
+            (None, Action::PlaceSettlement { vertex }) => {
+                place_house(&mut self.board, player_id, vertex, true)?;
+                let mut events = vec![GameEvent::SettlementBuilt { player: player_id, vertex }];
+                // en Ciudades y Caballeros la segunda pieza es una ciudad
+                if round == 2 && self.board.rules.cities_and_knights {
+                    found_city(&mut self.board, player_id, vertex)?;
+                    events.push(GameEvent::CityBuilt { player: player_id, vertex });
+                }
+
+                // en la segunda ronda la casa da recursos (en las dos, si las reglas lo dicen)
+                if round == 2 || self.board.rules.starting_resources_from_both {
+                    let before = self.hand(player_id);
+                    give_starting_resources(&mut self.board, player_id, vertex);
+                    events.push(GameEvent::StartingResources {
+                        player: player_id,
+                        materials: self.gained_since(player_id, &before),
+                    });
+                }
+
+                self.phase = GamePhase::Setup { round, anchor_vertex: Some(vertex) };
+                Ok(events)
+            }
+            (Some(anchor), Action::PlaceRoad { edge }) => {
+                let phase = TurnPhase::Setup { anchor_vertex: anchor };
+                place_road(&mut self.board, player_id, edge, phase)?;
+                self.advance_setup(round);
+                Ok(vec![GameEvent::RoadBuilt { player: player_id, edge }])
+            }
+            (None, _) => Err("Fundación: coloca primero tu asentamiento."),
+            (Some(_), _) => Err("Fundación: coloca un camino junto a tu asentamiento."),
+        }
+    }
+
+    /// Orden de serpiente: 1, 2, ..., N, N, ..., 2, 1.
+    fn advance_setup(&mut self, round: u8) {
+        let last = self.turn_order.len() - 1;
+        self.phase = match (round, self.current) {
+            (1, c) if c == last => GamePhase::Setup { round: 2, anchor_vertex: None },
+            (1, c) => {
+                self.current = c + 1;
+                GamePhase::Setup { round: 1, anchor_vertex: None }
+            }
+            (_, 0) => {
+                self.turn_number = 1;
+                GamePhase::Roll
+            }
+            (_, c) => {
+                self.current = c - 1;
+                GamePhase::Setup { round: 2, anchor_vertex: None }
+            }
+        };
+    }
+
+    // -------------------------------------------------------------------------
+    // DADOS Y LADRÓN
+    // -------------------------------------------------------------------------
+
+    /// Tira los dados, o usa los de la mesa (`entered`) si se juega con dados físicos.
+    fn roll_dice(&mut self, player_id: PlayerType, entered: Option<(u8, u8)>) -> Result<Vec<GameEvent>, &'static str> {
+        let players = self.turn_order.len().max(1) as u32;
+        let round = self.turn_number.saturating_sub(1) / players + 1;
+        let (die1, die2) = match entered {
+            Some((first, second)) => self.board.dice.enter(&self.board.rules, round, first, second)?,
+            None => self.board.dice.roll(&self.board.rules, round),
+        };
+        let (roll, red) = (die1 + die2, die2);
+        self.last_roll = Some(roll);
+
+        let mut events = vec![GameEvent::DiceRolled { player: player_id, roll }];
+        // Ciudades y Caballeros: los bárbaros se mueven antes de repartir
+        let face = self.board.rules.cities_and_knights.then(roll_event_die);
+        if let Some(face) = face {
+            events.push(GameEvent::EventDieRolled { player: player_id, face, red });
+            if face == EventDie::Barbarians {
+                events.extend(self.advance_barbarians());
+            }
+        }
+
+        if roll == 7 {
+            let pending: Vec<PlayerType> = self
+                .board
+                .players
+                .iter()
+                .filter(|p| cards_to_discard(&self.board.rules, p) > 0)
+                .map(|p| p.id)
+                .collect();
+            self.phase = if pending.is_empty() {
+                self.robber_phase()
+            } else {
+                GamePhase::Discard { pending }
+            };
+        } else {
+            let payouts = give_materials_on_roll(&mut self.board, roll);
+            for &id in &self.turn_order {
+                if let Some(gains) = payouts.get(&id) {
+                    for (&material, &amount) in gains.iter().filter(|(m, _)| m.is_resource() || m.is_commodity()) {
+                        events.push(GameEvent::ResourcesProduced { player: id, material, amount });
+                    }
+                }
+            }
+            // el acueducto se elige igual que el oro
+            let owed = choices_owed(&self.board, &payouts);
+            self.phase = if owed.is_empty() {
+                GamePhase::Main
+            } else {
+                GamePhase::GoldChoice { pending: owed }
+            };
+        }
+        if let Some(EventDie::Gate(track)) = face {
+            for (player, card) in draw_progress_cards(&mut self.board, track, red) {
+                events.push(GameEvent::ProgressCardDrawn { player, card: Some(card) });
+            }
+        }
+        Ok(events)
+    }
+
+    /// Después de un 7, el ladrón. En Ciudades y Caballeros no se mueve hasta el primer ataque bárbaro.
+    fn robber_phase(&self) -> GamePhase {
+        if self.board.rules.cities_and_knights && self.board.barbarian_attacks == 0 {
+            GamePhase::Main
+        } else {
+            GamePhase::MoveRobber
+        }
+    }
+
+    fn advance_barbarians(&mut self) -> Vec<GameEvent> {
+        let Some(attack) = advance_barbarians(&mut self.board) else {
+            return vec![GameEvent::BarbariansAdvanced { position: self.board.barbarian_position }];
+        };
+        let mut events = vec![GameEvent::BarbarianAttack { strength: attack.strength, defense: attack.defense }];
+        if let Some(player) = attack.defender {
+            events.push(GameEvent::DefenderOfCatan { player });
+        }
+        for (player, card) in attack.rewarded {
+            events.push(GameEvent::ProgressCardDrawn { player, card });
+        }
+        for (player, vertex) in attack.pillaged {
+            events.push(GameEvent::CityPillaged { player, vertex });
+        }
+        events
+    }
+
+    fn apply_discard(
+        &mut self,
+        player_id: PlayerType,
+        materials: &[MaterialType],
+    ) -> Result<Vec<GameEvent>, &'static str> {
+        let GamePhase::Discard { pending } = &self.phase else {
+            return Err("No hay que descartar ahora.");
+        };
+        if !pending.contains(&player_id) {
+            return Err("No tienes que descartar.");
+        }
+
+        let player = self.player(player_id).ok_or("Error: No se encontró al jugador.")?;
+        if materials.len() != cards_to_discard(&self.board.rules, player) as usize {
+            return Err("Cantidad de cartas a descartar incorrecta.");
+        }
+        let mut needed: HashMap<MaterialType, u8> = HashMap::new();
+        for &m in materials {
+            *needed.entry(m).or_insert(0) += 1;
+        }
+        for (m, &count) in &needed {
+            if *player.resources.get(m).unwrap_or(&0) < count {
+                return Err("No tienes esas cartas para descartar.");
+            }
+        }
+
+        for &m in materials {
+            discard_resource(&mut self.board, player_id, m)?;
+        }
+
+        let mut remaining = pending.clone();
+        remaining.retain(|&p| p != player_id);
+        self.phase = if remaining.is_empty() {
+            self.robber_phase()
+        } else {
+            GamePhase::Discard { pending: remaining }
+        };
+
+        Ok(vec![GameEvent::Discarded { player: player_id, materials: materials.to_vec() }])
+    }
+
+    fn apply_gold_choice(
+        &mut self,
+        player_id: PlayerType,
+        materials: &[MaterialType],
+    ) -> Result<Vec<GameEvent>, &'static str> {
+        let GamePhase::GoldChoice { pending } = &self.phase else {
+            return Err("No hay oro para elegir ahora.");
+        };
+        let Some(&(_, amount)) = pending.iter().find(|&&(p, _)| p == player_id) else {
+            return Err("No tienes oro para elegir.");
+        };
+        if materials.len() != amount as usize {
+            return Err("Cantidad de cartas de oro incorrecta.");
+        }
+        take_gold(&mut self.board, player_id, materials)?;
+
+        let mut remaining = pending.clone();
+        remaining.retain(|&(p, _)| p != player_id);
+        self.phase = if remaining.is_empty() {
+            GamePhase::Main
+        } else {
+            GamePhase::GoldChoice { pending: remaining }
+        };
+        Ok(vec![GameEvent::GoldChosen { player: player_id, materials: materials.to_vec() }])
+    }
+
+    /// Jugadores a los que se puede robar desde `tile`.
+    pub fn robbable_players(&self, tile: TileId, thief: PlayerType) -> Vec<PlayerType> {
+        robbable_players(&self.board, tile, thief)
+    }
+
+    fn check_robber_target(
+        &self,
+        player_id: PlayerType,
+        tile: TileId,
+        victim: Option<PlayerType>,
+    ) -> Result<(), &'static str> {
+        if tile >= self.board.tiles.len() {
+            return Err("Posición inválida: La casilla no existe.");
+        }
+        if !is_new_robber_tile(&self.board, tile) {
+            return Err("No se puede mover: Debes mover el ladrón a una *nueva* casilla.");
+        }
+        let candidates = self.robbable_players(tile, player_id);
+        match victim {
+            Some(v) if !candidates.contains(&v) => {
+                Err("No se puede robar: Ese jugador no tiene edificios en la casilla o lo protege el ladrón amistoso.")
+            }
+            None if !candidates.is_empty() => Err("Tienes que elegir a quién robar."),
+            _ => Ok(()),
+        }
+    }
+
+    fn move_robber_and_steal(
+        &mut self,
+        player_id: PlayerType,
+        tile: TileId,
+        victim: Option<PlayerType>,
+    ) -> Result<Vec<GameEvent>, &'static str> {
+        self.check_robber_target(player_id, tile, victim)?;
+        move_robber(&mut self.board, tile)?;
+
+        let mut events = vec![self.robber_event(player_id, tile)];
+        if let Some(victim) = victim {
+            if let Some(material) = steal_random_resource(&mut self.board, player_id, victim) {
+                events.push(GameEvent::ResourceStolen {
+                    thief: player_id,
+                    victim,
+                    material: Some(material),
+                });
+            }
+        }
+        Ok(events)
+    }
+
+    // -------------------------------------------------------------------------
+    // FASE PRINCIPAL
+    // -------------------------------------------------------------------------
+
+    fn apply_main(&mut self, player_id: PlayerType, action: Action) -> Result<Vec<GameEvent>, &'static str> {
+        match action {
+            Action::PlaceSettlement { vertex } => {
+                let points_before = self.player(player_id).map_or(0, |p| p.victory_points);
+                let extra = self.extra_cost(player_id, Construction::Settlement(vertex));
+                place_house(&mut self.board, player_id, vertex, false)?;
+                let mut events = vec![GameEvent::SettlementBuilt { player: player_id, vertex }];
+                events.extend(extra);
+                // la casa da 1 punto; lo que sobre es el bonus por isla nueva
+                let gained = self.player(player_id).map_or(0, |p| p.victory_points) - points_before;
+                if gained > 1 {
+                    events.push(GameEvent::IslandBonus { player: player_id, points: gained - 1 });
+                }
+                Ok(events)
+            }
+            Action::PlaceShip { edge } => {
+                place_ship(&mut self.board, player_id, edge, false)?;
+                Ok(vec![GameEvent::ShipBuilt { player: player_id, edge }])
+            }
+            Action::MoveShip { from, to } => {
+                move_ship(&mut self.board, player_id, from, to)?;
+                Ok(vec![GameEvent::ShipMoved { player: player_id, from, to }])
+            }
+            Action::PlaceCity { vertex } => {
+                let extra = self.extra_cost(player_id, Construction::City(vertex));
+                place_city(&mut self.board, player_id, vertex)?;
+                Ok([GameEvent::CityBuilt { player: player_id, vertex }].into_iter().chain(extra).collect())
+            }
+            Action::PlaceRoad { edge } => {
+                let extra = self.extra_cost(player_id, Construction::Road(edge));
+                place_road(&mut self.board, player_id, edge, TurnPhase::Normal)?;
+                Ok([GameEvent::RoadBuilt { player: player_id, edge }].into_iter().chain(extra).collect())
+            }
+            Action::BuyDevCard => {
+                buy_development_card(&mut self.board, player_id)?;
+                let card = self.player(player_id).and_then(|p| p.dev_cards.last().copied());
+                Ok(vec![GameEvent::DevCardBought { player: player_id, card }])
+            }
+            Action::PlayKnight { tile, victim } => self.play_knight(player_id, tile, victim),
+            Action::PlayRoadBuilding { first_edge, second_edge } => {
+                // se prueba sobre una copia: la carta no debe gastarse si falla un camino
+                let mut trial = self.board.clone();
+                play_road_building_card(&mut trial, player_id, first_edge, second_edge)?;
+                self.board = trial;
+                Ok(vec![
+                    GameEvent::DevCardPlayed { player: player_id, card: DevelopmentCard::RoadBuilding },
+                    GameEvent::RoadBuilt { player: player_id, edge: first_edge },
+                    GameEvent::RoadBuilt { player: player_id, edge: second_edge },
+                ])
+            }
+            Action::PlayYearOfPlenty { first, second } => {
+                if !first.is_resource() || !second.is_resource() {
+                    return Err("Material no válido.");
+                }
+                if !play_year_of_plenty_card(&mut self.board, player_id, first, second) {
+                    return Err("No puedes jugar Año de la Abundancia.");
+                }
+                Ok(vec![
+                    GameEvent::DevCardPlayed { player: player_id, card: DevelopmentCard::YearOfPlenty },
+                    GameEvent::ResourcesFromBank { player: player_id, materials: vec![first, second] },
+                ])
+            }
+            Action::PlayMonopoly { material } => {
+                if !material.is_resource() {
+                    return Err("Material no válido.");
+                }
+                let stolen: Vec<(PlayerType, u8)> = self
+                    .board
+                    .players
+                    .iter()
+                    .filter(|p| p.id != player_id)
+                    .map(|p| (p.id, *p.resources.get(&material).unwrap_or(&0)))
+                    .filter(|&(_, amount)| amount > 0)
+                    .collect();
+                if !play_monopoly_card(&mut self.board, player_id, material) {
+                    return Err("No puedes jugar Monopolio.");
+                }
+                Ok(vec![
+                    GameEvent::DevCardPlayed { player: player_id, card: DevelopmentCard::Monopoly },
+                    GameEvent::MonopolyPlayed { player: player_id, material, stolen },
+                ])
+            }
+            Action::BankTrade { give, get } => {
+                let amount = self
+                    .player(player_id)
+                    .map(|p| bank_rate(&self.board.rules, p, give))
+                    .unwrap_or(self.board.rules.bank_rate);
+                if !trade_with_bank(&mut self.board, player_id, give, get) {
+                    return Err("Intercambio con el banco rechazado.");
+                }
+                Ok(vec![GameEvent::BankTrade { player: player_id, gave: give, amount, got: get }])
+            }
+            Action::ProposeTrade { offer } => self.propose_trade(player_id, offer),
+            Action::CancelTrade => match self.trade.take() {
+                Some(_) => Ok(vec![GameEvent::TradeCancelled { player: player_id }]),
+                None => Err("No tienes ninguna oferta abierta."),
+            },
+            Action::RespondTrade { .. } => unreachable!("ya resuelto arriba"),
+            Action::EndTurn => Ok(self.end_turn(player_id)),
+            Action::RollDice | Action::EnterDice { .. } => Err("Ya tiraste los dados este turno."),
+            Action::MoveRobber { .. } => Err("Ahora no puedes mover el ladrón."),
+            Action::Discard { .. } => Err("No hay que descartar ahora."),
+            Action::ChooseGold { .. } => Err("No hay oro para elegir ahora."),
+            Action::BuildKnight { vertex } => {
+                place_knight(&mut self.board, player_id, vertex)?;
+                Ok(vec![GameEvent::KnightBuilt { player: player_id, vertex }])
+            }
+            Action::ActivateKnight { vertex } => {
+                activate_knight(&mut self.board, player_id, vertex, false)?;
+                Ok(vec![GameEvent::KnightActivated { player: player_id, vertex }])
+            }
+            Action::PromoteKnight { vertex } => {
+                let level = promote_knight(&mut self.board, player_id, vertex, false)?;
+                Ok(vec![GameEvent::KnightPromoted { player: player_id, vertex, level }])
+            }
+            Action::BuildImprovement { track } => {
+                let metropolis = build_improvement(&mut self.board, player_id, track)?;
+                let level = self.player(player_id).map_or(0, |p| improvement_level(p, track));
+                let mut events = vec![GameEvent::ImprovementBuilt { player: player_id, track, level }];
+                if let Some(vertex) = metropolis {
+                    events.push(GameEvent::MetropolisGained { player: player_id, track, vertex });
+                }
+                Ok(events)
+            }
+            Action::PlayProgress { play } => self.play_progress(player_id, play),
+            Action::ExchangeTokens { token, reward } => {
+                let cost = available_exchanges(&self.board)
+                    .iter()
+                    .find(|e| e.token == token && e.reward == reward.kind())
+                    .map_or(0, |e| e.cost);
+                exchange_tokens(&mut self.board, player_id, &token, reward)?;
+                let mut events = vec![GameEvent::TokensExchanged { player: player_id, token, cost, reward }];
+                if let TokenReward::Road(edge) = reward {
+                    events.push(GameEvent::RoadBuilt { player: player_id, edge });
+                }
+                Ok(events)
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // COMERCIO ENTRE JUGADORES
+    // -------------------------------------------------------------------------
+
+    fn propose_trade(&mut self, player_id: PlayerType, offer: TradeOffer) -> Result<Vec<GameEvent>, &'static str> {
+        if offer.give.is_empty() || offer.get.is_empty() {
+            return Err("La oferta tiene que dar y pedir al menos una carta.");
+        }
+        if !offer.give.iter().chain(&offer.get).all(|m| m.is_resource()) {
+            return Err("Sólo se comercia con recursos.");
+        }
+        if let Some(to) = offer.to {
+            if to == player_id || !self.turn_order.contains(&to) {
+                return Err("No puedes ofrecerle a ese jugador.");
+            }
+        }
+        let player = self.player(player_id).ok_or("Error: No se encontró al jugador.")?;
+        if !has_cards(player, &offer.give) {
+            return Err("No tienes las cartas que ofreces.");
+        }
+
+        // una oferta nueva reemplaza a la anterior, y con ella el tiempo para responder
+        self.trade = Some(PendingTrade { from: player_id, offer: offer.clone(), declined: Vec::new() });
+        self.clocks.start_decision();
+        Ok(vec![GameEvent::TradeProposed { player: player_id, offer }])
+    }
+
+    fn respond_trade(&mut self, player_id: PlayerType, accept: bool) -> Result<Vec<GameEvent>, &'static str> {
+        let Some(trade) = self.trade.as_mut() else {
+            return Err("No hay ninguna oferta abierta.");
+        };
+        if !trade.waiting_on(&self.turn_order).contains(&player_id) {
+            return Err("Esa oferta no es para ti.");
+        }
+        if !accept {
+            trade.declined.push(player_id);
+            // si ya la rechazaron todos, se cierra
+            if trade.waiting_on(&self.turn_order).is_empty() {
+                self.trade = None;
+            }
+            return Ok(vec![GameEvent::TradeDeclined { player: player_id }]);
+        }
+
+        let PendingTrade { from, offer, .. } = trade.clone();
+        trade_between_players(&mut self.board, from, player_id, &offer.give, &offer.get)?;
+        self.trade = None;
+        Ok(vec![GameEvent::PlayersTraded { player: from, partner: player_id, gave: offer.give, got: offer.get }])
+    }
+
+    fn play_progress(&mut self, player_id: PlayerType, play: ProgressPlay) -> Result<Vec<GameEvent>, &'static str> {
+        if let ProgressPlay::Bishop { tile } = play {
+            if tile < self.board.tiles.len() && is_sea(&self.board, tile) {
+                return Err("El obispo lleva al ladrón por tierra.");
+            }
+        }
+        let outcome = play_progress_card(&mut self.board, player_id, play)?;
+        let mut events = vec![GameEvent::ProgressCardPlayed { player: player_id, card: play.card() }];
+        match play {
+            ProgressPlay::Irrigation | ProgressPlay::Mining => {
+                events.push(GameEvent::ResourcesFromBank { player: player_id, materials: outcome.gained });
+            }
+            ProgressPlay::RoadBuilding { .. } => {
+                for edge in outcome.roads {
+                    events.push(GameEvent::RoadBuilt { player: player_id, edge });
+                }
+            }
+            ProgressPlay::ResourceMonopoly { material } | ProgressPlay::TradeMonopoly { material } => {
+                let stolen = outcome.taken.iter().map(|&(victim, _, amount)| (victim, amount)).collect();
+                events.push(GameEvent::MonopolyPlayed { player: player_id, material, stolen });
+            }
+            ProgressPlay::Bishop { tile } => {
+                events.push(GameEvent::RobberMoved { player: player_id, tile });
+                for (victim, material, _) in outcome.taken {
+                    events.push(GameEvent::ResourceStolen { thief: player_id, victim, material: Some(material) });
+                }
+            }
+            ProgressPlay::Smith { .. } | ProgressPlay::Warlord => {}
+        }
+        Ok(events)
+    }
+
+    fn play_knight(
+        &mut self,
+        player_id: PlayerType,
+        tile: TileId,
+        victim: Option<PlayerType>,
+    ) -> Result<Vec<GameEvent>, &'static str> {
+        let index = self
+            .board
+            .players
+            .iter()
+            .position(|p| p.id == player_id)
+            .ok_or("Error: No se encontró al jugador.")?;
+        if dev_card_limit_reached(&self.board, index) {
+            return Err("Error: Ya jugaste las cartas de desarrollo permitidas este turno.");
+        }
+        let player = &self.board.players[index];
+        if !player.dev_cards.contains(&DevelopmentCard::Knight) {
+            return Err("Error: No tienes una carta de Caballero.");
+        }
+        self.check_robber_target(player_id, tile, victim)?;
+
+        let before = self.hand(player_id);
+        play_knight_card(&mut self.board, player_id, tile, victim);
+
+        let mut events = vec![
+            GameEvent::DevCardPlayed { player: player_id, card: DevelopmentCard::Knight },
+            self.robber_event(player_id, tile),
+        ];
+        if let (Some(victim), Some(&material)) = (victim, self.gained_since(player_id, &before).first()) {
+            events.push(GameEvent::ResourceStolen { thief: player_id, victim, material: Some(material) });
+        }
+        Ok(events)
+    }
+
+    fn end_turn(&mut self, player_id: PlayerType) -> Vec<GameEvent> {
+        run_turn_end_hooks(&mut self.board, player_id);
+        self.trade = None;
+        self.current = (self.current + 1) % self.turn_order.len();
+        self.turn_number += 1;
+        self.last_roll = None;
+        self.phase = GamePhase::Roll;
+
+        let next = self.current_player();
+        if let Some(p) = self.board.players.iter_mut().find(|p| p.id == next) {
+            p.dev_cards_played_this_turn = 0;
+            reset_ship_turn(p);
+        }
+        vec![GameEvent::TurnEnded { player: player_id, next }]
+    }
+
+    // -------------------------------------------------------------------------
+    // HELPERS
+    // -------------------------------------------------------------------------
+
+    /// El ladrón en tierra, el pirata en el mar.
+    fn robber_event(&self, player_id: PlayerType, tile: TileId) -> GameEvent {
+        if is_sea(&self.board, tile) {
+            GameEvent::PirateMoved { player: player_id, tile }
+        } else {
+            GameEvent::RobberMoved { player: player_id, tile }
+        }
+    }
+
+    /// Lo que cobran de más los escenarios por construir, como evento.
+    fn extra_cost(&self, player_id: PlayerType, construction: Construction) -> Option<GameEvent> {
+        let cost = extra_build_cost(&self.board, construction);
+        (!cost.is_empty()).then_some(GameEvent::ExtraCostPaid { player: player_id, cost })
+    }
+
+    fn tokens(&self) -> Vec<(PlayerType, HashMap<String, u8>)> {
+        self.board.players.iter().map(|p| (p.id, p.tokens.clone())).collect()
+    }
+
+    /// Fichas de escenario que cambiaron respecto de `before`.
+    fn token_changes(&self, before: &[(PlayerType, HashMap<String, u8>)]) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        for (player, was) in before {
+            let Some(now) = self.player(*player).map(|p| &p.tokens) else {
+                continue;
+            };
+            let mut names: Vec<&String> = now.keys().chain(was.keys()).collect();
+            names.sort();
+            names.dedup();
+            for token in names {
+                let count = now.get(token).copied().unwrap_or(0);
+                if count != was.get(token).copied().unwrap_or(0) {
+                    events.push(GameEvent::TokensChanged { player: *player, token: token.clone(), count });
+                }
+            }
+        }
+        events
+    }
+
+    fn hand(&self, player_id: PlayerType) -> HashMap<MaterialType, u8> {
+        self.player(player_id).map(|p| p.resources.clone()).unwrap_or_default()
+    }
+
+    /// Cartas que ganó el jugador respecto de `before` (una entrada por carta).
+    fn gained_since(&self, player_id: PlayerType, before: &HashMap<MaterialType, u8>) -> Vec<MaterialType> {
+        let mut gained = Vec::new();
+        if let Some(player) = self.player(player_id) {
+            for (&material, &now) in &player.resources {
+                let was = *before.get(&material).unwrap_or(&0);
+                for _ in was..now {
+                    gained.push(material);
+                }
+            }
+        }
+        gained
+    }
+}
+
+/// Recursos al azar, para quien se quedó sin tiempo eligiendo el oro.
+fn random_resources(amount: u8) -> Vec<MaterialType> {
+    let resources = MaterialType::RESOURCES;
+    let mut rng = rand::rng();
+    (0..amount).map(|_| resources[rng.random_range(0..resources.len())]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_action_plays_through_setup_and_robber() {
+        let mut game = Game::new(3);
+        while matches!(game.phase, GamePhase::Setup { .. }) {
+            let player = game.current_player();
+            let action = game.fallback_action(player).expect("siempre hay lugar en la fundación");
+            game.apply(player, action).unwrap();
+        }
+        assert_eq!(game.phase, GamePhase::Roll);
+
+        game.phase = GamePhase::MoveRobber;
+        let player = game.current_player();
+        let action = game.fallback_action(player).unwrap();
+        assert!(matches!(action, Action::MoveRobber { .. }));
+        game.apply(player, action).unwrap();
+        assert_eq!(game.phase, GamePhase::Main);
+
+        // fuera de turno y sin nada pendiente no hay jugada
+        let other = game.turn_order[(game.current + 1) % game.turn_order.len()];
+        assert_eq!(game.fallback_action(other), None);
+    }
+
+    /// Termina la fundación con jugadas cualquiera y deja al primero en la fase principal.
+    fn game_in_main(config: ClockConfig) -> Game {
+        let mut game = Game::new(3).with_clocks(config);
+        while matches!(game.phase, GamePhase::Setup { .. }) {
+            let player = game.current_player();
+            let action = game.fallback_action(player).unwrap();
+            game.apply(player, action).unwrap();
+        }
+        game.phase = GamePhase::Main;
+        game
+    }
+
+    #[test]
+    fn unanswered_trade_is_declined_when_the_decision_clock_runs_out() {
+        let mut game = game_in_main(ClockConfig { decision_secs: Some(10), ..Default::default() });
+        let me = game.current_player();
+        game.board.players.iter_mut().find(|p| p.id == me).unwrap().resources.insert(MaterialType::Wood, 1);
+        let offer = TradeOffer { to: None, give: vec![MaterialType::Wood], get: vec![MaterialType::Stone] };
+        game.apply(me, Action::ProposeTrade { offer }).unwrap();
+
+        let first = game.trade.as_ref().unwrap().waiting_on(&game.turn_order)[0];
+        game.apply(first, Action::RespondTrade { accept: false }).unwrap();
+        assert!(game.advance_clock(Duration::from_secs(5)).is_empty());
+
+        let events = game.advance_clock(Duration::from_secs(5));
+        let timed_out: Vec<_> = events.iter().filter(|e| matches!(e, GameEvent::TimedOut { .. })).collect();
+        assert_eq!(timed_out.len(), 1, "sólo al que faltaba responder");
+        assert!(events.iter().any(|e| matches!(e, GameEvent::TradeDeclined { player } if *player != first)));
+        assert_eq!(game.trade, None);
+        // la oferta vencida no le termina el turno a quien la hizo
+        assert_eq!(game.current_player(), me);
+        assert_eq!(game.phase, GamePhase::Main);
+    }
+
+    /// Deja a `player` con `amount` cartas de `material` y nada más.
+    fn give_only(game: &mut Game, player: PlayerType, material: MaterialType, amount: u8) {
+        let hand = &mut game.board.players.iter_mut().find(|p| p.id == player).unwrap().resources;
+        hand.clear();
+        hand.insert(material, amount);
+    }
+
+    #[test]
+    fn setup_goes_in_snake_order() {
+        let mut game = Game::new(3);
+        let mut order = Vec::new();
+        while let GamePhase::Setup { anchor_vertex, .. } = game.phase {
+            let player = game.current_player();
+            if anchor_vertex.is_none() {
+                order.push(player);
+            }
+            let action = game.fallback_action(player).unwrap();
+            game.apply(player, action).unwrap();
+        }
+        let (p1, p2, p3) = (game.turn_order[0], game.turn_order[1], game.turn_order[2]);
+        assert_eq!(order, vec![p1, p2, p3, p3, p2, p1]);
+        assert_eq!(game.phase, GamePhase::Roll);
+        assert_eq!(game.current_player(), p1);
+    }
+
+    #[test]
+    fn robber_moves_only_after_everyone_discards() {
+        let mut game = game_in_main(ClockConfig::default());
+        let (a, b) = (game.turn_order[1], game.turn_order[2]);
+        give_only(&mut game, a, MaterialType::Wood, 8);
+        give_only(&mut game, b, MaterialType::Stone, 9);
+        game.phase = GamePhase::Discard { pending: vec![a, b] };
+
+        assert!(game.apply(a, Action::Discard { materials: vec![MaterialType::Wood; 3] }).is_err());
+        game.apply(a, Action::Discard { materials: vec![MaterialType::Wood; 4] }).unwrap();
+        assert_eq!(game.phase, GamePhase::Discard { pending: vec![b] });
+        assert!(game.apply(a, Action::Discard { materials: vec![MaterialType::Wood; 4] }).is_err());
+
+        game.apply(b, Action::Discard { materials: vec![MaterialType::Stone; 4] }).unwrap();
+        assert_eq!(game.phase, GamePhase::MoveRobber);
+        assert_eq!(game.hand(a).get(&MaterialType::Wood), Some(&4));
+    }
+
+    #[test]
+    fn gold_choice_waits_for_everyone_owed() {
+        let mut game = game_in_main(ClockConfig::default());
+        let (a, b) = (game.turn_order[0], game.turn_order[1]);
+        give_only(&mut game, a, MaterialType::Wood, 0);
+        give_only(&mut game, b, MaterialType::Wood, 0);
+        game.phase = GamePhase::GoldChoice { pending: vec![(a, 1), (b, 2)] };
+
+        assert!(game.apply(a, Action::ChooseGold { materials: vec![] }).is_err());
+        game.apply(a, Action::ChooseGold { materials: vec![MaterialType::Wheat] }).unwrap();
+        assert_eq!(game.phase, GamePhase::GoldChoice { pending: vec![(b, 2)] });
+
+        game.apply(b, Action::ChooseGold { materials: vec![MaterialType::Sheep, MaterialType::Brick] }).unwrap();
+        assert_eq!(game.phase, GamePhase::Main);
+        assert_eq!(game.hand(a).get(&MaterialType::Wheat), Some(&1));
+        assert_eq!(game.hand(b).get(&MaterialType::Brick), Some(&1));
+    }
+
+    #[test]
+    fn running_out_of_turn_time_passes_the_turn() {
+        let mut game = game_in_main(ClockConfig { turn_secs: Some(30), ..Default::default() });
+        let me = game.current_player();
+        assert!(game.advance_clock(Duration::from_secs(29)).is_empty());
+
+        let events = game.advance_clock(Duration::from_secs(1));
+        assert!(events.contains(&GameEvent::TimedOut { player: me }));
+        assert_ne!(game.current_player(), me);
+        assert_eq!(game.phase, GamePhase::Roll);
+    }
+
+    #[test]
+    fn running_out_of_decision_time_discards_at_random() {
+        let mut game = game_in_main(ClockConfig { decision_secs: Some(10), ..Default::default() });
+        let rival = game.turn_order[1];
+        give_only(&mut game, rival, MaterialType::Wood, 8);
+        game.phase = GamePhase::Discard { pending: vec![rival] };
+        game.clocks.start_decision();
+
+        let events = game.advance_clock(Duration::from_secs(10));
+        assert!(events.contains(&GameEvent::TimedOut { player: rival }));
+        assert_eq!(game.hand(rival).get(&MaterialType::Wood), Some(&4));
+        assert_eq!(game.phase, GamePhase::MoveRobber);
+    }
+}
@@ -0,0 +1,21 @@
+// en src/engine/mod.rs
+//
+// Motor de reglas: una partida completa (fundación, tiradas, ladrón,
+// turnos) que recibe `Action`s, las valida con `game_logic` y devuelve
+// los `GameEvent`s resultantes. Lo usan el servidor y los clientes.
+// Los frontends y los bots miran la partida a través de `PlayerView`.
+
+mod actions;
+pub mod bot;
+mod card_counter;
+mod clock;
+mod events;
+mod game;
+mod view;
+
+pub use actions::Action;
+pub use card_counter::{CardCounter, HandRange};
+pub use clock::{ClockConfig, Clocks};
+pub use events::GameEvent;
+pub use game::{Game, GamePhase, PendingTrade};
+pub use view::{BoardView, KnownSteal, PlayerView, ViewDiff};
@@ -0,0 +1,443 @@
+// en src/engine/view.rs
+//
+// Lo que un asiento puede ver del estado de la partida: su mano entera,
+// de los rivales sólo cuántas cartas tienen, el mazo reducido a su
+// tamaño y sin los puntos de las cartas de victoria. Todos los
+// frontends y los bots leen la partida desde acá, no del `Board` crudo.
+
+use crate::game_logic::{pirate_tile, robbable_players};
+use crate::rules::public_victory_points;
+use crate::types::*;
+use super::clock::Clocks;
+use super::game::{Game, GamePhase, PendingTrade};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Un robo, con el material sólo si quien mira lo puede saber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownSteal {
+    pub thief: PlayerType,
+    pub victim: PlayerType,
+    pub material: Option<MaterialType>,
+}
+
+/// Un `Board` visto desde un asiento (`None` = sin asiento).
+/// Sirve para los modos que no tienen una `Game`, como el de consola local.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardView {
+    pub viewer: Option<PlayerType>,
+    pub omniscient: bool,
+    /// Tablero con las manos y cartas de los rivales vaciadas y el mazo oculto.
+    pub board: Board,
+    pub hand_sizes: HashMap<PlayerType, u8>,
+    pub dev_card_counts: HashMap<PlayerType, u8>,
+    pub deck_size: u8,
+}
+
+impl BoardView {
+    pub fn new(board: &Board, viewer: Option<PlayerType>) -> Self {
+        Self::build(board, viewer, false)
+    }
+
+    /// Todas las manos a la vista, para cuando la partida terminó.
+    pub fn revealed(board: &Board) -> Self {
+        Self::build(board, None, true)
+    }
+
+    fn build(board: &Board, viewer: Option<PlayerType>, omniscient: bool) -> Self {
+        let mut board = board.clone();
+        let mut hand_sizes = HashMap::new();
+        let mut dev_card_counts = HashMap::new();
+
+        for player in board.players.iter_mut() {
+            hand_sizes.insert(player.id, player.resources.values().sum());
+            dev_card_counts.insert(player.id, (player.dev_cards.len() + player.progress_cards.len()) as u8);
+
+            if !omniscient && Some(player.id) != viewer {
+                // los puntos de las cartas de victoria no se ven hasta el final
+                player.victory_points = public_victory_points(player);
+                player.resources.clear();
+                player.dev_cards.clear();
+                player.progress_cards.clear();
+            }
+        }
+        let deck_size = board.development_cards.len() as u8;
+        board.development_cards.clear();
+        board.progress_decks.clear();
+        // el orden del mazo de dados tampoco se ve
+        board.dice.deck.clear();
+
+        BoardView { viewer, omniscient, board, hand_sizes, dev_card_counts, deck_size }
+    }
+
+    /// El jugador que mira, con su mano completa.
+    pub fn me(&self) -> Option<&Player> {
+        let viewer = self.viewer?;
+        self.board.players.iter().find(|p| p.id == viewer)
+    }
+
+    pub fn hand_size(&self, player: PlayerType) -> u8 {
+        self.hand_sizes.get(&player).copied().unwrap_or(0)
+    }
+
+    pub fn dev_card_count(&self, player: PlayerType) -> u8 {
+        self.dev_card_counts.get(&player).copied().unwrap_or(0)
+    }
+}
+
+/// Estado de la partida visto desde un asiento (`None` = sin asiento).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub viewer: Option<PlayerType>,
+    /// Vista de comentarista: todas las manos a la vista.
+    #[serde(default)]
+    pub omniscient: bool,
+    /// Tablero con las manos y cartas de los rivales vaciadas y el mazo oculto.
+    pub board: Board,
+    pub turn_order: Vec<PlayerType>,
+    pub current_player: PlayerType,
+    pub phase: GamePhase,
+    pub turn_number: u32,
+    pub last_roll: Option<u8>,
+    pub hand_sizes: HashMap<PlayerType, u8>,
+    pub dev_card_counts: HashMap<PlayerType, u8>,
+    pub deck_size: u8,
+    /// Todos los robos de la partida; el material sólo en los que participó quien mira.
+    #[serde(default)]
+    pub known_steals: Vec<KnownSteal>,
+    #[serde(default)]
+    pub clocks: Clocks,
+    /// La oferta de comercio abierta, que ven todos.
+    #[serde(default)]
+    pub trade: Option<PendingTrade>,
+}
+
+/// Cambios entre dos `PlayerView` del mismo asiento.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewDiff {
+    pub vertices: Vec<(VertexId, Vertex)>,
+    pub edges: Vec<(EdgeId, Edge)>,
+    /// Las fichas de escenario de las casillas que cambiaron.
+    #[serde(default)]
+    pub tile_pieces: Vec<(TileId, Vec<ScenarioPiece>)>,
+    /// Dónde está el ladrón ahora; `None` si salió del tablero.
+    pub robber_tile: Option<TileId>,
+    /// Dónde está el pirata ahora; `None` si no hay.
+    #[serde(default)]
+    pub pirate_tile: Option<TileId>,
+    pub players: Vec<Player>,
+    pub largest_army: Option<PlayerType>,
+    pub largest_army_size: u8,
+    pub longest_road: Option<PlayerType>,
+    pub longest_road_size: u8,
+    pub current_player: PlayerType,
+    pub phase: GamePhase,
+    pub turn_number: u32,
+    pub last_roll: Option<u8>,
+    pub hand_sizes: HashMap<PlayerType, u8>,
+    pub dev_card_counts: HashMap<PlayerType, u8>,
+    pub deck_size: u8,
+    /// Robos nuevos desde la vista anterior.
+    #[serde(default)]
+    pub new_steals: Vec<KnownSteal>,
+    #[serde(default)]
+    pub clocks: Clocks,
+    /// Los dos dados de la última tirada.
+    #[serde(default)]
+    pub last_dice: Option<(u8, u8)>,
+    #[serde(default)]
+    pub trade: Option<PendingTrade>,
+    /// Ciudades y Caballeros: el barco de los bárbaros y los ataques que hubo.
+    #[serde(default)]
+    pub barbarian_position: u8,
+    #[serde(default)]
+    pub barbarian_attacks: u8,
+    /// Ciudades y Caballeros: todas las metrópolis actuales.
+    #[serde(default)]
+    pub metropolises: HashMap<ImprovementTrack, VertexId>,
+}
+
+impl PlayerView {
+    pub fn for_viewer(game: &Game, viewer: Option<PlayerType>) -> Self {
+        Self::build(game, viewer, false)
+    }
+
+    /// Todo a la vista menos el orden del mazo, para espectadores comentaristas.
+    pub fn omniscient(game: &Game) -> Self {
+        Self::build(game, None, true)
+    }
+
+    fn build(game: &Game, viewer: Option<PlayerType>, omniscient: bool) -> Self {
+        let table = BoardView::build(&game.board, viewer, omniscient);
+        let known_steals = game
+            .steals
+            .iter()
+            .map(|steal| {
+                let involved = viewer == Some(steal.thief) || viewer == Some(steal.victim);
+                KnownSteal {
+                    material: if omniscient || involved { steal.material } else { None },
+                    ..*steal
+                }
+            })
+            .collect();
+
+        PlayerView {
+            viewer,
+            omniscient,
+            board: table.board,
+            turn_order: game.turn_order.clone(),
+            current_player: game.current_player(),
+            phase: game.phase.clone(),
+            turn_number: game.turn_number,
+            last_roll: game.last_roll,
+            hand_sizes: table.hand_sizes,
+            dev_card_counts: table.dev_card_counts,
+            deck_size: table.deck_size,
+            known_steals,
+            clocks: game.clocks.clone(),
+            trade: game.trade.clone(),
+        }
+    }
+
+    /// El jugador que mira, con su mano completa.
+    pub fn me(&self) -> Option<&Player> {
+        let viewer = self.viewer?;
+        self.board.players.iter().find(|p| p.id == viewer)
+    }
+
+    pub fn hand_size(&self, player: PlayerType) -> u8 {
+        self.hand_sizes.get(&player).copied().unwrap_or(0)
+    }
+
+    pub fn dev_card_count(&self, player: PlayerType) -> u8 {
+        self.dev_card_counts.get(&player).copied().unwrap_or(0)
+    }
+
+    /// ¿Tiene quien mira que decidir algo ahora mismo?
+    pub fn needs_decision(&self) -> bool {
+        let Some(viewer) = self.viewer else {
+            return false;
+        };
+        if self.is_waiting_on(viewer) {
+            return true;
+        }
+        match &self.phase {
+            GamePhase::Finished { .. } => false,
+            GamePhase::Discard { pending } => pending.contains(&viewer),
+            GamePhase::GoldChoice { pending } => pending.iter().any(|&(p, _)| p == viewer),
+            _ => self.current_player == viewer,
+        }
+    }
+
+    /// ¿Espera la oferta de comercio abierta una respuesta de `player`?
+    pub fn is_waiting_on(&self, player: PlayerType) -> bool {
+        self.trade.as_ref().is_some_and(|t| t.waiting_on(&self.turn_order).contains(&player))
+    }
+
+    /// Tiempo que le quedaba a quien tenía que decidir cuando se armó la vista.
+    /// A quien tiene que responder una oferta le corre el reloj de la oferta.
+    pub fn time_left(&self) -> Option<std::time::Duration> {
+        match self.phase {
+            GamePhase::Discard { .. } | GamePhase::GoldChoice { .. } => self.clocks.decision_left(),
+            _ if self.viewer.is_some_and(|v| self.is_waiting_on(v)) => self.clocks.decision_left(),
+            _ => self.clocks.time_left(self.current_player),
+        }
+    }
+
+    /// Jugadores a los que se puede robar desde `tile` (misma regla que `Game`).
+    pub fn robbable_players(&self, tile: TileId, thief: PlayerType) -> Vec<PlayerType> {
+        robbable_players(&self.board, tile, thief)
+    }
+
+    pub fn robber_tile(&self) -> Option<TileId> {
+        self.board.tiles.iter().position(|t| t.has_robber)
+    }
+
+    pub fn pirate_tile(&self) -> Option<TileId> {
+        pirate_tile(&self.board)
+    }
+
+    pub fn diff(&self, newer: &PlayerView) -> ViewDiff {
+        let vertices = newer
+            .board
+            .vertices
+            .iter()
+            .enumerate()
+            .filter(|&(i, v)| self.board.vertices.get(i) != Some(v))
+            .map(|(i, v)| (i, v.clone()))
+            .collect();
+        let edges = newer
+            .board
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|&(i, e)| self.board.edges.get(i) != Some(e))
+            .map(|(i, e)| (i, e.clone()))
+            .collect();
+        let tile_pieces = newer
+            .board
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|&(i, t)| self.board.tiles.get(i).map(|old| &old.pieces) != Some(&t.pieces))
+            .map(|(i, t)| (i, t.pieces.clone()))
+            .collect();
+        let players = newer
+            .board
+            .players
+            .iter()
+            .filter(|p| !self.board.players.contains(p))
+            .cloned()
+            .collect();
+        ViewDiff {
+            vertices,
+            edges,
+            tile_pieces,
+            robber_tile: newer.robber_tile(),
+            pirate_tile: newer.pirate_tile(),
+            players,
+            largest_army: newer.board.largest_army,
+            largest_army_size: newer.board.largest_army_size,
+            longest_road: newer.board.longest_road,
+            longest_road_size: newer.board.longest_road_size,
+            current_player: newer.current_player,
+            phase: newer.phase.clone(),
+            turn_number: newer.turn_number,
+            last_roll: newer.last_roll,
+            hand_sizes: newer.hand_sizes.clone(),
+            dev_card_counts: newer.dev_card_counts.clone(),
+            deck_size: newer.deck_size,
+            new_steals: newer.known_steals.iter().skip(self.known_steals.len()).copied().collect(),
+            clocks: newer.clocks.clone(),
+            last_dice: newer.board.dice.last,
+            trade: newer.trade.clone(),
+            barbarian_position: newer.board.barbarian_position,
+            barbarian_attacks: newer.board.barbarian_attacks,
+            metropolises: newer.board.metropolises.clone(),
+        }
+    }
+
+    pub fn apply_diff(&mut self, diff: ViewDiff) {
+        for (id, vertex) in diff.vertices {
+            if let Some(v) = self.board.vertices.get_mut(id) {
+                *v = vertex;
+            }
+        }
+        for (id, edge) in diff.edges {
+            if let Some(e) = self.board.edges.get_mut(id) {
+                *e = edge;
+            }
+        }
+        for (id, pieces) in diff.tile_pieces {
+            if let Some(t) = self.board.tiles.get_mut(id) {
+                t.pieces = pieces;
+            }
+        }
+        // siempre llega la posición actual: `None` es que no está en el tablero
+        for (i, tile) in self.board.tiles.iter_mut().enumerate() {
+            tile.has_robber = diff.robber_tile == Some(i);
+            tile.has_pirate = diff.pirate_tile == Some(i);
+        }
+        for player in diff.players {
+            if let Some(p) = self.board.players.iter_mut().find(|p| p.id == player.id) {
+                *p = player;
+            }
+        }
+        self.board.largest_army = diff.largest_army;
+        self.board.largest_army_size = diff.largest_army_size;
+        self.board.longest_road = diff.longest_road;
+        self.board.longest_road_size = diff.longest_road_size;
+        self.current_player = diff.current_player;
+        self.phase = diff.phase;
+        self.turn_number = diff.turn_number;
+        self.last_roll = diff.last_roll;
+        self.hand_sizes = diff.hand_sizes;
+        self.dev_card_counts = diff.dev_card_counts;
+        self.deck_size = diff.deck_size;
+        self.known_steals.extend(diff.new_steals);
+        self.clocks = diff.clocks;
+        self.board.dice.last = diff.last_dice;
+        self.trade = diff.trade;
+        self.board.barbarian_position = diff.barbarian_position;
+        self.board.barbarian_attacks = diff.barbarian_attacks;
+        self.board.metropolises = diff.metropolises;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Aplicar el diff a la vista vieja tiene que dar la vista nueva.
+    fn assert_roundtrip(game: &Game, before: &Game) {
+        let mut old = PlayerView::for_viewer(before, Some(PlayerType::Player1));
+        let new = PlayerView::for_viewer(game, Some(PlayerType::Player1));
+        old.apply_diff(old.diff(&new));
+        assert_eq!(old, new);
+    }
+
+    #[test]
+    fn robber_leaving_the_board_is_sent() {
+        let before = Game::new(3);
+        let mut game = before.clone();
+        for tile in game.board.tiles.iter_mut() {
+            tile.has_robber = false;
+        }
+        assert_roundtrip(&game, &before);
+    }
+
+    #[test]
+    fn robber_moving_is_sent() {
+        let before = Game::new(3);
+        let mut game = before.clone();
+        let from = game.board.tiles.iter().position(|t| t.has_robber).unwrap();
+        let to = (from + 1) % game.board.tiles.len();
+        game.board.tiles[from].has_robber = false;
+        game.board.tiles[to].has_robber = true;
+        assert_roundtrip(&game, &before);
+    }
+
+    #[test]
+    fn barbarians_and_metropolises_are_sent() {
+        let before = Game::new(3);
+        let mut game = before.clone();
+        game.board.barbarian_position = 4;
+        game.board.barbarian_attacks = 1;
+        game.board.metropolises.insert(ImprovementTrack::Trade, 10);
+        assert_roundtrip(&game, &before);
+
+        // y también cuando se pierde una metrópolis
+        let before = game.clone();
+        game.board.metropolises.clear();
+        assert_roundtrip(&game, &before);
+    }
+
+    #[test]
+    fn tile_pieces_are_sent() {
+        let before = Game::new(3);
+        let mut game = before.clone();
+        game.board.tiles[5].pieces.push(ScenarioPiece {
+            kind: "fish".to_string(),
+            owner: None,
+            number: 4,
+        });
+        let mut old = PlayerView::for_viewer(&before, Some(PlayerType::Player1));
+        let new = PlayerView::for_viewer(&game, Some(PlayerType::Player1));
+        let diff = old.diff(&new);
+        // sólo viaja la casilla que cambió
+        assert_eq!(diff.tile_pieces.len(), 1);
+        old.apply_diff(diff);
+        assert_eq!(old, new);
+    }
+
+    #[test]
+    fn hidden_victory_points_never_go_negative() {
+        let mut game = Game::new(3);
+        let rival = game.board.players.iter_mut().find(|p| p.id == PlayerType::Player2).unwrap();
+        rival.victory_points = 0;
+        rival.dev_cards.push(DevelopmentCard::VictoryPoint);
+        let view = PlayerView::for_viewer(&game, Some(PlayerType::Player1));
+        let rival = view.board.players.iter().find(|p| p.id == PlayerType::Player2).unwrap();
+        assert_eq!(rival.victory_points, 0);
+    }
+}
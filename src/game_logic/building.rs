@@ -2,15 +2,17 @@
 
 use crate::types::*;
 use super::economy::{has_resources, spend_resources};
+use super::hooks::{build_cost, run_build_hooks, Construction};
+use super::seafarers::{is_land_edge, is_land_vertex, record_island};
 use super::victory::{check_for_winner, update_longest_road};
 
 // --- CONSTANTES DE COSTO ---
-const SETTLEMENT_COST: &[(MaterialType, u8)] = &[
+pub const SETTLEMENT_COST: &[(MaterialType, u8)] = &[
     (MaterialType::Brick, 1), (MaterialType::Wood, 1),
     (MaterialType::Sheep, 1), (MaterialType::Wheat, 1),
 ];
-const ROAD_COST: &[(MaterialType, u8)] = &[(MaterialType::Brick, 1), (MaterialType::Wood, 1)];
-const CITY_COST: &[(MaterialType, u8)] = &[(MaterialType::Wheat, 2), (MaterialType::Stone, 3)];
+pub const ROAD_COST: &[(MaterialType, u8)] = &[(MaterialType::Brick, 1), (MaterialType::Wood, 1)];
+pub const CITY_COST: &[(MaterialType, u8)] = &[(MaterialType::Wheat, 2), (MaterialType::Stone, 3)];
 
 // --- FUNCIONES AUXILIARES (PRIVADAS) ---
 
@@ -32,6 +34,12 @@ fn can_place_house(board: &Board, position: VertexId) -> Result<(), &'static str
     if pos.owner.is_some() {
         return Err("No se puede construir: la casilla ya está ocupada.");
     }
+    if pos.knight.is_some() {
+        return Err("No se puede construir: Hay un caballero en ese vértice.");
+    }
+    if !is_land_vertex(board, position) {
+        return Err("No se puede construir: Ese vértice está en medio del mar.");
+    }
     for &edge_id in &pos.adjacent_edges {
         let edge = &board.edges[edge_id];
         let (v1, v2) = edge.vertices;
@@ -68,19 +76,48 @@ pub fn is_road_connectable(board: &Board, player_id: PlayerType, edge_id: EdgeId
     if board.vertices[v1].owner == Some(player_id) || board.vertices[v2].owner == Some(player_id) {
         return true;
     }
+    // un camino no sigue a un barco: entre los dos hace falta una casa propia
+    let is_own_road = |e: EdgeId| board.edges[e].owner == Some(player_id) && !board.edges[e].ship;
     for &other_edge_id in &board.vertices[v1].adjacent_edges {
-        if other_edge_id != edge_id && board.edges[other_edge_id].owner == Some(player_id) {
+        if other_edge_id != edge_id && is_own_road(other_edge_id) {
             return true;
         }
     }
     for &other_edge_id in &board.vertices[v2].adjacent_edges {
-        if other_edge_id != edge_id && board.edges[other_edge_id].owner == Some(player_id) {
+        if other_edge_id != edge_id && is_own_road(other_edge_id) {
             return true;
         }
     }
     false
 }
 
+/// Vértices donde `player_id` puede poner un asentamiento, sin mirar la mano.
+/// En la fundación no hace falta un camino propio.
+pub fn settlement_spots(board: &Board, player_id: PlayerType, is_first_turn: bool) -> Vec<VertexId> {
+    (0..board.vertices.len())
+        .filter(|&v| can_place_house(board, v).is_ok())
+        .filter(|&v| is_first_turn || has_road_connected(board, player_id, v))
+        .collect()
+}
+
+/// Asentamientos de `player_id` que se pueden convertir en ciudad.
+pub fn city_spots(board: &Board, player_id: PlayerType) -> Vec<VertexId> {
+    (0..board.vertices.len())
+        .filter(|&v| is_settlement_owned_by(board, player_id, v))
+        .collect()
+}
+
+/// Bordes donde `player_id` puede tender un camino, sin mirar la mano.
+pub fn road_spots(board: &Board, player_id: PlayerType, turn_phase: TurnPhase) -> Vec<EdgeId> {
+    (0..board.edges.len())
+        .filter(|&e| board.edges[e].owner.is_none() && is_land_edge(board, e))
+        .filter(|&e| match turn_phase {
+            TurnPhase::Setup { anchor_vertex } => is_road_adjacent_to_vertex(board, e, anchor_vertex),
+            TurnPhase::Normal | TurnPhase::FreeRoad => is_road_connectable(board, player_id, e),
+        })
+        .collect()
+}
+
 pub fn place_road (
     board: &mut Board, 
     player_id_type: PlayerType, 
@@ -103,9 +140,14 @@ pub fn place_road (
     if board.edges[edge_position].owner.is_some() {
         return Err("No se puede construir: El borde ya está ocupado.");
     }
+    if !is_land_edge(board, edge_position) {
+        return Err("No se puede construir: Los caminos van por tierra; en el mar, barcos.");
+    }
 
+    // los escenarios pueden cobrar de más (un puente sobre el río, por ejemplo)
+    let cost = build_cost(board, ROAD_COST, Construction::Road(edge_position));
     if let TurnPhase::Normal = turn_phase {
-        if !has_resources(&board.players[player_index], ROAD_COST) {
+        if !has_resources(&board.players[player_index], &cost) {
             return Err("No se puede construir: No tienes los recursos necesarios.");
         }
     }
@@ -129,13 +171,14 @@ pub fn place_road (
     player.road_quantity -= 1;
 
     if let TurnPhase::Normal = turn_phase {
-        spend_resources(player, ROAD_COST);
+        spend_resources(player, &cost);
         println!("¡Camino construido con éxito en {}! (Recursos gastados)", edge_position);
     } else {
         println!("¡Camino construido con éxito en {}! (Sin costo)", edge_position);
     }
     
     println!("A {:?} le quedan {} caminos.", player.id, player.road_quantity);
+    run_build_hooks(board, player_id_type, Construction::Road(edge_position));
     Ok(update_longest_road(board, player_id_type)) // <-- DEVUELVE OK
 }
 
@@ -156,7 +199,8 @@ pub fn place_city (board: &mut Board, player_id_type: PlayerType, position: Vert
     if board.players[player_index].city_quantity == 0 {
         return Err("No se puede construir: No tienes más ciudades disponibles.");
     }
-    if !has_resources(&board.players[player_index], CITY_COST) {
+    let cost = build_cost(board, CITY_COST, Construction::City(position));
+    if !has_resources(&board.players[player_index], &cost) {
         return Err("No se puede construir: No tienes los recursos necesarios.");
     }
 
@@ -166,10 +210,11 @@ pub fn place_city (board: &mut Board, player_id_type: PlayerType, position: Vert
     player.settlement_quantity += 1;
     player.victory_points += 1;
 
-    spend_resources(player, CITY_COST);
+    spend_resources(player, &cost);
 
     println!("¡Ciudad construida con éxito en {} para {:?}!", position, player_id_type);
     println!("A {:?} le quedan {} ciudades y tiene {} puntos.", player.id, player.city_quantity, player.victory_points);
+    run_build_hooks(board, player_id_type, Construction::City(position));
     Ok(check_for_winner(board)) // <-- DEVUELVE OK
 }
 
@@ -184,13 +229,14 @@ pub fn place_house (board: &mut Board, player_id_type: PlayerType, position: Ver
         None => return Err("Error: No se encontró al jugador."),
     };
     
+    let cost = build_cost(board, SETTLEMENT_COST, Construction::Settlement(position));
     if is_first_turn {
         // Turno de fundación
     } else {
         if !has_road_connected(board, player_id_type, position) {
             return Err("No se puede construir: No tienes un camino conectado.");
         }
-        if !has_resources(&board.players[player_index], SETTLEMENT_COST) {
+        if !has_resources(&board.players[player_index], &cost) {
             return Err("No se puede construir: No tienes los recursos necesarios.");
         }
     }
@@ -216,12 +262,14 @@ pub fn place_house (board: &mut Board, player_id_type: PlayerType, position: Ver
     }
 
     if !is_first_turn {
-        spend_resources(player, SETTLEMENT_COST);
+        spend_resources(player, &cost);
         println!("¡Casa ubicada con éxito en {}! (Recursos gastados)", position);
     } else {
         println!("¡Casa ubicada con éxito en {}! (Turno de fundación)", position);
     }
     
     println!("A {:?} le quedan {} asentamientos y tiene {} puntos.", player.id, player.settlement_quantity, player.victory_points);
+    record_island(board, player_id_type, position, is_first_turn);
+    run_build_hooks(board, player_id_type, Construction::Settlement(position));
     Ok(check_for_winner(board)) // <-- DEVUELVE OK
 }
\ No newline at end of file
@@ -0,0 +1,780 @@
+// en src/game_logic/cities_knights.rs
+//
+// Ciudades y Caballeros: mercancías, mejoras de ciudad con metrópolis,
+// caballeros, el barco bárbaro con el dado de eventos y las cartas de
+// progreso. Todo esto sólo corre con `rules.cities_and_knights`; el
+// reparto de mercancías vive en `give_materials_on_roll`.
+
+use crate::types::*;
+use super::building::place_road;
+use super::economy::{has_resources, move_robber, spend_resources, steal_random_resource};
+use super::seafarers::gold_owed;
+use rand::Rng;
+use std::collections::HashMap;
+
+pub const KNIGHT_COST: &[(MaterialType, u8)] = &[(MaterialType::Sheep, 1), (MaterialType::Stone, 1)];
+pub const KNIGHT_ACTIVATION_COST: &[(MaterialType, u8)] = &[(MaterialType::Wheat, 1)];
+pub const KNIGHT_PROMOTION_COST: &[(MaterialType, u8)] = &[(MaterialType::Sheep, 1), (MaterialType::Stone, 1)];
+
+/// Casilleros que recorre el barco bárbaro antes de atacar.
+pub const BARBARIAN_TRACK_LENGTH: u8 = 7;
+/// Piezas de caballero por nivel y por jugador.
+const KNIGHTS_PER_LEVEL: usize = 2;
+/// Cartas de progreso que se pueden tener en la mano.
+const MAX_PROGRESS_CARDS: usize = 4;
+/// Nivel de mejora que da la metrópolis (y a partir del cual rinde la habilidad de la rama).
+const METROPOLIS_LEVEL: u8 = 4;
+const ABILITY_LEVEL: u8 = 3;
+const MAX_IMPROVEMENT_LEVEL: u8 = 5;
+
+// --- FUNCIONES AUXILIARES ---
+
+fn require_cities_and_knights(board: &Board) -> Result<(), &'static str> {
+    if board.rules.cities_and_knights {
+        Ok(())
+    } else {
+        Err("Esta partida no se juega con Ciudades y Caballeros.")
+    }
+}
+
+fn player_index(board: &Board, player_id: PlayerType) -> Result<usize, &'static str> {
+    board
+        .players
+        .iter()
+        .position(|p| p.id == player_id)
+        .ok_or("Error: No se encontró al jugador.")
+}
+
+pub fn improvement_level(player: &Player, track: ImprovementTrack) -> u8 {
+    player.improvements.get(&track).copied().unwrap_or(0)
+}
+
+/// Política 3: se pueden ascender caballeros a poderosos.
+pub fn can_promote_to_mighty(player: &Player) -> bool {
+    improvement_level(player, ImprovementTrack::Politics) >= ABILITY_LEVEL
+}
+
+/// Comercio 3: las mercancías se cambian 2:1 con el banco.
+pub fn has_trading_house(player: &Player) -> bool {
+    improvement_level(player, ImprovementTrack::Trade) >= ABILITY_LEVEL
+}
+
+fn cities_of(board: &Board, player_id: PlayerType) -> Vec<VertexId> {
+    board
+        .vertices
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.owner == Some(player_id) && v.building == Some(BuildingType::City))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+pub fn metropolis_track(board: &Board, vertex_id: VertexId) -> Option<ImprovementTrack> {
+    board.metropolises.iter().find(|&(_, &v)| v == vertex_id).map(|(&t, _)| t)
+}
+
+fn knights_at_level(board: &Board, player_id: PlayerType, level: u8) -> usize {
+    board
+        .vertices
+        .iter()
+        .filter_map(|v| v.knight)
+        .filter(|k| k.owner == player_id && k.level == level)
+        .count()
+}
+
+// --- MEJORAS DE CIUDAD ---
+
+/// Sube un nivel en `track` pagando tantas mercancías como el nivel nuevo.
+/// Devuelve la ciudad que pasó a ser metrópolis, si hubo una.
+pub fn build_improvement(
+    board: &mut Board,
+    player_id: PlayerType,
+    track: ImprovementTrack,
+) -> Result<Option<VertexId>, &'static str> {
+    require_cities_and_knights(board)?;
+    let index = player_index(board, player_id)?;
+    let level = improvement_level(&board.players[index], track);
+    if level >= MAX_IMPROVEMENT_LEVEL {
+        return Err("No se puede mejorar: Ya tienes el nivel máximo en esa rama.");
+    }
+    if cities_of(board, player_id).is_empty() {
+        return Err("No se puede mejorar: Necesitas al menos una ciudad.");
+    }
+    let cost = [(track.commodity(), level + 1)];
+    if !has_resources(&board.players[index], &cost) {
+        return Err("No se puede mejorar: No tienes las mercancías necesarias.");
+    }
+
+    let player = &mut board.players[index];
+    spend_resources(player, &cost);
+    player.improvements.insert(track, level + 1);
+    println!("{:?} sube {:?} a nivel {}.", player_id, track, level + 1);
+
+    Ok(claim_metropolis(board, player_id, track, level + 1))
+}
+
+/// El primero en llegar al nivel 4 se lleva la metrópolis; al 5 se la puede
+/// quitar a quien la tiene con nivel 4.
+fn claim_metropolis(board: &mut Board, player_id: PlayerType, track: ImprovementTrack, level: u8) -> Option<VertexId> {
+    if level < METROPOLIS_LEVEL {
+        return None;
+    }
+    let holder = board.metropolises.get(&track).map(|&v| (v, board.vertices[v].owner));
+    if let Some((_, Some(owner))) = holder {
+        let owner_level = board
+            .players
+            .iter()
+            .find(|p| p.id == owner)
+            .map_or(0, |p| improvement_level(p, track));
+        if owner == player_id || level < MAX_IMPROVEMENT_LEVEL || owner_level >= MAX_IMPROVEMENT_LEVEL {
+            return None;
+        }
+    }
+    let city = cities_of(board, player_id)
+        .into_iter()
+        .find(|&v| metropolis_track(board, v).is_none())?;
+
+    if let Some((_, Some(owner))) = holder {
+        if let Some(p) = board.players.iter_mut().find(|p| p.id == owner) {
+            p.victory_points = p.victory_points.saturating_sub(2);
+        }
+    }
+    board.metropolises.insert(track, city);
+    if let Some(p) = board.players.iter_mut().find(|p| p.id == player_id) {
+        p.victory_points += 2;
+    }
+    println!("¡{:?} tiene la metrópolis de {:?} en {}!", player_id, track, city);
+    Some(city)
+}
+
+// --- CABALLEROS ---
+
+/// Caballero básico nuevo en un vértice libre que toque un camino propio.
+pub fn place_knight(board: &mut Board, player_id: PlayerType, vertex_id: VertexId) -> Result<(), &'static str> {
+    require_cities_and_knights(board)?;
+    if vertex_id >= board.vertices.len() {
+        return Err("Posición inválida: El vértice no existe.");
+    }
+    let index = player_index(board, player_id)?;
+    let vertex = &board.vertices[vertex_id];
+    if vertex.owner.is_some() || vertex.knight.is_some() {
+        return Err("No se puede poner: El vértice ya está ocupado.");
+    }
+    if !vertex.adjacent_edges.iter().any(|&e| board.edges[e].owner == Some(player_id)) {
+        return Err("No se puede poner: El caballero tiene que tocar uno de tus caminos.");
+    }
+    if knights_at_level(board, player_id, 1) >= KNIGHTS_PER_LEVEL {
+        return Err("No se puede poner: No te quedan caballeros básicos.");
+    }
+    if !has_resources(&board.players[index], KNIGHT_COST) {
+        return Err("No se puede poner: No tienes los recursos necesarios.");
+    }
+
+    spend_resources(&mut board.players[index], KNIGHT_COST);
+    board.vertices[vertex_id].knight = Some(Knight { owner: player_id, level: 1, active: false });
+    println!("{:?} pone un caballero en {}.", player_id, vertex_id);
+    Ok(())
+}
+
+fn own_knight(board: &Board, player_id: PlayerType, vertex_id: VertexId) -> Result<Knight, &'static str> {
+    match board.vertices.get(vertex_id).and_then(|v| v.knight) {
+        Some(knight) if knight.owner == player_id => Ok(knight),
+        _ => Err("No tienes un caballero en ese vértice."),
+    }
+}
+
+pub fn activate_knight(
+    board: &mut Board,
+    player_id: PlayerType,
+    vertex_id: VertexId,
+    free: bool,
+) -> Result<(), &'static str> {
+    require_cities_and_knights(board)?;
+    let index = player_index(board, player_id)?;
+    let knight = own_knight(board, player_id, vertex_id)?;
+    if knight.active {
+        return Err("Ese caballero ya está activo.");
+    }
+    if !free && !has_resources(&board.players[index], KNIGHT_ACTIVATION_COST) {
+        return Err("No se puede activar: Hace falta un trigo.");
+    }
+    if !free {
+        spend_resources(&mut board.players[index], KNIGHT_ACTIVATION_COST);
+    }
+    board.vertices[vertex_id].knight = Some(Knight { active: true, ..knight });
+    Ok(())
+}
+
+/// Sube el caballero un nivel; el activo sigue activo.
+pub fn promote_knight(
+    board: &mut Board,
+    player_id: PlayerType,
+    vertex_id: VertexId,
+    free: bool,
+) -> Result<u8, &'static str> {
+    require_cities_and_knights(board)?;
+    let index = player_index(board, player_id)?;
+    let knight = own_knight(board, player_id, vertex_id)?;
+    let level = knight.level + 1;
+    if level > 3 {
+        return Err("Ese caballero ya es poderoso.");
+    }
+    if level == 3 && !can_promote_to_mighty(&board.players[index]) {
+        return Err("No se puede ascender: Los caballeros poderosos piden política nivel 3.");
+    }
+    if knights_at_level(board, player_id, level) >= KNIGHTS_PER_LEVEL {
+        return Err("No se puede ascender: No te quedan caballeros de ese nivel.");
+    }
+    if !free && !has_resources(&board.players[index], KNIGHT_PROMOTION_COST) {
+        return Err("No se puede ascender: No tienes los recursos necesarios.");
+    }
+    if !free {
+        spend_resources(&mut board.players[index], KNIGHT_PROMOTION_COST);
+    }
+    board.vertices[vertex_id].knight = Some(Knight { level, ..knight });
+    Ok(level)
+}
+
+/// Suma de los niveles de los caballeros activos del jugador.
+pub fn knight_strength(board: &Board, player_id: PlayerType) -> u8 {
+    board
+        .vertices
+        .iter()
+        .filter_map(|v| v.knight)
+        .filter(|k| k.owner == player_id && k.active)
+        .map(|k| k.level)
+        .sum()
+}
+
+// --- BÁRBAROS ---
+
+pub fn roll_event_die() -> EventDie {
+    match rand::rng().random_range(1..=6) {
+        4 => EventDie::Gate(ImprovementTrack::Trade),
+        5 => EventDie::Gate(ImprovementTrack::Politics),
+        6 => EventDie::Gate(ImprovementTrack::Science),
+        _ => EventDie::Barbarians,
+    }
+}
+
+/// Cómo terminó un ataque bárbaro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarbarianAttack {
+    /// Una por ciudad en el tablero.
+    pub strength: u8,
+    /// Suma de todos los caballeros activos.
+    pub defense: u8,
+    /// Único mejor defensor: se lleva un punto (Defensor de Catán).
+    pub defender: Option<PlayerType>,
+    /// Empate entre los mejores defensores: cada uno saca una carta de progreso.
+    pub rewarded: Vec<(PlayerType, Option<ProgressCard>)>,
+    /// Ciudades que volvieron a ser casas por perder el ataque.
+    pub pillaged: Vec<(PlayerType, VertexId)>,
+}
+
+/// El barco bárbaro avanza un casillero; si llega, ataca y vuelve al principio.
+pub fn advance_barbarians(board: &mut Board) -> Option<BarbarianAttack> {
+    board.barbarian_position += 1;
+    if board.barbarian_position < BARBARIAN_TRACK_LENGTH {
+        return None;
+    }
+
+    let strength = board
+        .vertices
+        .iter()
+        .filter(|v| v.building == Some(BuildingType::City))
+        .count() as u8;
+    let players: Vec<PlayerType> = board.players.iter().map(|p| p.id).collect();
+    let defense_of: HashMap<PlayerType, u8> = players.iter().map(|&p| (p, knight_strength(board, p))).collect();
+    let defense: u8 = defense_of.values().sum();
+
+    let mut attack = BarbarianAttack { strength, defense, defender: None, rewarded: Vec::new(), pillaged: Vec::new() };
+    if defense >= strength {
+        let best = defense_of.values().copied().max().unwrap_or(0);
+        let mut top: Vec<PlayerType> = players.iter().copied().filter(|p| defense_of[p] == best).collect();
+        if best > 0 && top.len() == 1 {
+            let defender = top.remove(0);
+            if let Some(p) = board.players.iter_mut().find(|p| p.id == defender) {
+                p.victory_points += 1;
+            }
+            attack.defender = Some(defender);
+        } else if best > 0 {
+            for player_id in top {
+                let track = best_track(board, player_id);
+                let card = draw_progress_card(board, player_id, track);
+                attack.rewarded.push((player_id, card));
+            }
+        }
+    } else {
+        // pierden una ciudad los que menos defendieron entre los que tienen ciudades sin metrópolis
+        let exposed: Vec<(PlayerType, VertexId)> = players
+            .iter()
+            .filter_map(|&p| {
+                cities_of(board, p)
+                    .into_iter()
+                    .find(|&v| metropolis_track(board, v).is_none())
+                    .map(|v| (p, v))
+            })
+            .collect();
+        let weakest = exposed.iter().map(|(p, _)| defense_of[p]).min();
+        for &(player_id, vertex_id) in &exposed {
+            if Some(defense_of[&player_id]) == weakest {
+                pillage_city(board, player_id, vertex_id);
+                attack.pillaged.push((player_id, vertex_id));
+            }
+        }
+    }
+
+    for vertex in board.vertices.iter_mut() {
+        if let Some(knight) = vertex.knight.as_mut() {
+            knight.active = false;
+        }
+    }
+    board.barbarian_position = 0;
+    board.barbarian_attacks += 1;
+    println!("¡Ataque bárbaro! Fuerza {} contra {} de los caballeros.", strength, defense);
+    Some(attack)
+}
+
+fn pillage_city(board: &mut Board, player_id: PlayerType, vertex_id: VertexId) {
+    board.vertices[vertex_id].building = Some(BuildingType::Settlement);
+    if let Some(p) = board.players.iter_mut().find(|p| p.id == player_id) {
+        p.victory_points = p.victory_points.saturating_sub(1);
+        p.city_quantity += 1;
+        p.settlement_quantity = p.settlement_quantity.saturating_sub(1);
+    }
+    println!("Los bárbaros saquean la ciudad de {:?} en {}.", player_id, vertex_id);
+}
+
+/// Rama en la que más avanzó el jugador (para premios sin elección).
+fn best_track(board: &Board, player_id: PlayerType) -> ImprovementTrack {
+    let player = board.players.iter().find(|p| p.id == player_id);
+    ImprovementTrack::ALL
+        .into_iter()
+        .max_by_key(|&t| player.map_or(0, |p| improvement_level(p, t)))
+        .unwrap_or(ImprovementTrack::Science)
+}
+
+// --- CARTAS DE PROGRESO ---
+
+/// Saca una carta de `track`. Las de punto se juegan solas; si la mano está
+/// llena, la carta vuelve al fondo del mazo.
+fn draw_progress_card(board: &mut Board, player_id: PlayerType, track: ImprovementTrack) -> Option<ProgressCard> {
+    let index = board.players.iter().position(|p| p.id == player_id)?;
+    let deck = board.progress_decks.get_mut(&track)?;
+    let card = deck.pop()?;
+    let player = &mut board.players[index];
+    if card.is_victory_point() {
+        player.victory_points += 1;
+    } else if player.progress_cards.len() >= MAX_PROGRESS_CARDS {
+        deck.insert(0, card);
+        return None;
+    } else {
+        player.progress_cards.push(card);
+    }
+    Some(card)
+}
+
+/// Puerta de `track` con el dado rojo `red`: saca carta quien tenga nivel
+/// en la rama y `red` no pase de su nivel + 1.
+pub fn draw_progress_cards(board: &mut Board, track: ImprovementTrack, red: u8) -> Vec<(PlayerType, ProgressCard)> {
+    let eligible: Vec<PlayerType> = board
+        .players
+        .iter()
+        .filter(|p| {
+            let level = improvement_level(p, track);
+            level > 0 && red <= level + 1
+        })
+        .map(|p| p.id)
+        .collect();
+    eligible
+        .into_iter()
+        .filter_map(|p| draw_progress_card(board, p, track).map(|card| (p, card)))
+        .collect()
+}
+
+/// Ciencia 3 (acueducto): quien no recibió nada en la tirada elige un recurso.
+pub fn aqueduct_owed(
+    board: &Board,
+    payouts: &HashMap<PlayerType, HashMap<MaterialType, u8>>,
+) -> Vec<(PlayerType, u8)> {
+    if !board.rules.cities_and_knights {
+        return Vec::new();
+    }
+    board
+        .players
+        .iter()
+        .filter(|p| improvement_level(p, ImprovementTrack::Science) >= ABILITY_LEVEL)
+        .filter(|p| payouts.get(&p.id).is_none_or(|gains| gains.is_empty()))
+        .map(|p| (p.id, 1))
+        .collect()
+}
+
+/// Cartas a elección que deja la tirada: las del oro más las del acueducto.
+pub fn choices_owed(
+    board: &Board,
+    payouts: &HashMap<PlayerType, HashMap<MaterialType, u8>>,
+) -> Vec<(PlayerType, u8)> {
+    let mut owed = gold_owed(payouts);
+    for (player, amount) in aqueduct_owed(board, payouts) {
+        match owed.iter_mut().find(|(p, _)| *p == player) {
+            Some((_, n)) => *n += amount,
+            None => owed.push((player, amount)),
+        }
+    }
+    owed
+}
+
+/// Lo que dejó una carta de progreso, para contarlo como eventos.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgressOutcome {
+    /// Recursos que dio el banco.
+    pub gained: Vec<MaterialType>,
+    /// Cartas quitadas a cada rival.
+    pub taken: Vec<(PlayerType, MaterialType, u8)>,
+    /// Caballeros que cambiaron (activados o ascendidos).
+    pub knights: Vec<VertexId>,
+    pub roads: Vec<EdgeId>,
+}
+
+/// Juega una carta de progreso. Si algo falla, el tablero queda como estaba.
+pub fn play_progress_card(
+    board: &mut Board,
+    player_id: PlayerType,
+    play: ProgressPlay,
+) -> Result<ProgressOutcome, &'static str> {
+    require_cities_and_knights(board)?;
+    let index = player_index(board, player_id)?;
+    let card = play.card();
+    let Some(position) = board.players[index].progress_cards.iter().position(|&c| c == card) else {
+        return Err("No tienes esa carta de progreso.");
+    };
+
+    let before = board.clone();
+    board.players[index].progress_cards.remove(position);
+    let result = resolve_progress(board, player_id, play);
+    if result.is_err() {
+        *board = before;
+    } else {
+        println!("¡{:?} juega {:?}!", player_id, card);
+    }
+    result
+}
+
+fn resolve_progress(board: &mut Board, player_id: PlayerType, play: ProgressPlay) -> Result<ProgressOutcome, &'static str> {
+    let mut outcome = ProgressOutcome::default();
+    match play {
+        ProgressPlay::Irrigation | ProgressPlay::Mining => {
+            let material = match play {
+                ProgressPlay::Irrigation => MaterialType::Wheat,
+                _ => MaterialType::Stone,
+            };
+            let tiles = board
+                .tiles
+                .iter()
+                .filter(|t| t.material == material)
+                .filter(|t| t.vertices.iter().any(|&v| board.vertices[v].owner == Some(player_id)))
+                .count();
+            outcome.gained = vec![material; tiles * 2];
+            if let Some(p) = board.players.iter_mut().find(|p| p.id == player_id) {
+                *p.resources.entry(material).or_insert(0) += (tiles * 2) as u8;
+            }
+        }
+        ProgressPlay::RoadBuilding { first_edge, second_edge } => {
+            place_road(board, player_id, first_edge, TurnPhase::FreeRoad)?;
+            place_road(board, player_id, second_edge, TurnPhase::FreeRoad)?;
+            outcome.roads = vec![first_edge, second_edge];
+        }
+        ProgressPlay::Smith { first, second } => {
+            if first == second {
+                return Err("El herrero asciende dos caballeros distintos.");
+            }
+            promote_knight(board, player_id, first, true)?;
+            promote_knight(board, player_id, second, true)?;
+            outcome.knights = vec![first, second];
+        }
+        ProgressPlay::ResourceMonopoly { material } | ProgressPlay::TradeMonopoly { material } => {
+            let (valid, most) = match play {
+                ProgressPlay::ResourceMonopoly { .. } => (material.is_resource(), 2),
+                _ => (material.is_commodity(), 1),
+            };
+            if !valid {
+                return Err("Material inválido para ese monopolio.");
+            }
+            let mut total = 0;
+            for rival in board.players.iter_mut().filter(|p| p.id != player_id) {
+                let count = rival.resources.entry(material).or_insert(0);
+                let amount = (*count).min(most);
+                if amount > 0 {
+                    *count -= amount;
+                    total += amount;
+                    outcome.taken.push((rival.id, material, amount));
+                }
+            }
+            if let Some(p) = board.players.iter_mut().find(|p| p.id == player_id) {
+                *p.resources.entry(material).or_insert(0) += total;
+            }
+        }
+        ProgressPlay::Warlord => {
+            for (vertex_id, vertex) in board.vertices.iter_mut().enumerate() {
+                if let Some(knight) = vertex.knight.as_mut() {
+                    if knight.owner == player_id && !knight.active {
+                        knight.active = true;
+                        outcome.knights.push(vertex_id);
+                    }
+                }
+            }
+        }
+        ProgressPlay::Bishop { tile } => {
+            if tile >= board.tiles.len() || board.tiles[tile].has_robber {
+                return Err("No se puede mover: Debes mover el ladrón a una *nueva* casilla.");
+            }
+            move_robber(board, tile)?;
+            let mut victims: Vec<PlayerType> = board.tiles[tile]
+                .vertices
+                .iter()
+                .filter_map(|&v| board.vertices[v].owner)
+                .filter(|&p| p != player_id)
+                .collect();
+            victims.sort_by_key(|&p| p as u8);
+            victims.dedup();
+            for victim in victims {
+                if let Some(material) = steal_random_resource(board, player_id, victim) {
+                    outcome.taken.push((victim, material, 1));
+                }
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+// --- FUNDACIÓN ---
+
+/// En Ciudades y Caballeros la segunda pieza de la fundación es una ciudad.
+pub fn found_city(board: &mut Board, player_id: PlayerType, vertex_id: VertexId) -> Result<(), &'static str> {
+    require_cities_and_knights(board)?;
+    if vertex_id >= board.vertices.len() {
+        return Err("Posición inválida: El vértice no existe.");
+    }
+    let index = player_index(board, player_id)?;
+    let vertex = &mut board.vertices[vertex_id];
+    if vertex.owner != Some(player_id) || vertex.building != Some(BuildingType::Settlement) {
+        return Err("No tienes una casa en ese vértice.");
+    }
+    vertex.building = Some(BuildingType::City);
+    let player = &mut board.players[index];
+    player.settlement_quantity += 1;
+    player.city_quantity = player.city_quantity.saturating_sub(1);
+    player.victory_points += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setup::{add_player, set_rules, setup_board_with_layout, MapLayout};
+    use crate::rules::RuleSet;
+
+    /// Tablero fijo de Ciudades y Caballeros con `players` jugadores.
+    fn ck_board(players: usize) -> Board {
+        let mut board = setup_board_with_layout(MapLayout::Fixed);
+        for _ in 0..players {
+            add_player(&mut board);
+        }
+        set_rules(&mut board, RuleSet::default().with_cities_and_knights());
+        board
+    }
+
+    fn put_city(board: &mut Board, player_id: PlayerType, vertex_id: VertexId) {
+        board.vertices[vertex_id].owner = Some(player_id);
+        board.vertices[vertex_id].building = Some(BuildingType::City);
+    }
+
+    fn put_knight(board: &mut Board, player_id: PlayerType, vertex_id: VertexId, level: u8, active: bool) {
+        board.vertices[vertex_id].knight = Some(Knight { owner: player_id, level, active });
+    }
+
+    fn player(board: &Board, player_id: PlayerType) -> &Player {
+        board.players.iter().find(|p| p.id == player_id).unwrap()
+    }
+
+    fn player_mut(board: &mut Board, player_id: PlayerType) -> &mut Player {
+        board.players.iter_mut().find(|p| p.id == player_id).unwrap()
+    }
+
+    /// Deja el barco a un casillero de atacar.
+    fn barbarians_at_the_coast(board: &mut Board) {
+        board.barbarian_position = BARBARIAN_TRACK_LENGTH - 1;
+    }
+
+    #[test]
+    fn single_best_defender_gets_a_point() {
+        let mut board = ck_board(2);
+        put_city(&mut board, PlayerType::Player1, 0);
+        put_city(&mut board, PlayerType::Player1, 10);
+        put_city(&mut board, PlayerType::Player2, 20);
+        put_knight(&mut board, PlayerType::Player1, 30, 2, true);
+        put_knight(&mut board, PlayerType::Player2, 40, 1, true);
+        barbarians_at_the_coast(&mut board);
+
+        let attack = advance_barbarians(&mut board).unwrap();
+        assert_eq!((attack.strength, attack.defense), (3, 3));
+        assert_eq!(attack.defender, Some(PlayerType::Player1));
+        assert!(attack.rewarded.is_empty() && attack.pillaged.is_empty());
+        assert_eq!(player(&board, PlayerType::Player1).victory_points, 1);
+        assert_eq!(player(&board, PlayerType::Player2).victory_points, 0);
+        // después del ataque los caballeros descansan y el barco vuelve a salir
+        assert!(board.vertices.iter().filter_map(|v| v.knight).all(|k| !k.active));
+        assert_eq!((board.barbarian_position, board.barbarian_attacks), (0, 1));
+    }
+
+    #[test]
+    fn tied_defenders_draw_progress_cards() {
+        let mut board = ck_board(2);
+        for deck in board.progress_decks.values_mut() {
+            *deck = vec![ProgressCard::Mining; 2];
+        }
+        put_city(&mut board, PlayerType::Player1, 0);
+        put_city(&mut board, PlayerType::Player2, 20);
+        put_knight(&mut board, PlayerType::Player1, 30, 2, true);
+        put_knight(&mut board, PlayerType::Player2, 40, 2, true);
+        barbarians_at_the_coast(&mut board);
+
+        let attack = advance_barbarians(&mut board).unwrap();
+        assert_eq!(attack.defender, None);
+        assert_eq!(
+            attack.rewarded,
+            vec![
+                (PlayerType::Player1, Some(ProgressCard::Mining)),
+                (PlayerType::Player2, Some(ProgressCard::Mining)),
+            ]
+        );
+        for p in &board.players {
+            assert_eq!(p.progress_cards, vec![ProgressCard::Mining]);
+            assert_eq!(p.victory_points, 0);
+        }
+    }
+
+    #[test]
+    fn lost_attack_pillages_only_the_weakest_unprotected_city() {
+        let mut board = ck_board(3);
+        // Player1 no defiende: tiene una metrópolis (a salvo) y una ciudad común
+        put_city(&mut board, PlayerType::Player1, 0);
+        put_city(&mut board, PlayerType::Player1, 10);
+        board.metropolises.insert(ImprovementTrack::Science, 0);
+        player_mut(&mut board, PlayerType::Player1).victory_points = 5;
+        // Player2 defiende con uno
+        put_city(&mut board, PlayerType::Player2, 20);
+        put_knight(&mut board, PlayerType::Player2, 40, 1, true);
+        // Player3 tampoco defiende, pero su única ciudad es metrópolis
+        put_city(&mut board, PlayerType::Player3, 30);
+        board.metropolises.insert(ImprovementTrack::Trade, 30);
+        barbarians_at_the_coast(&mut board);
+        let cities_left = player(&board, PlayerType::Player1).city_quantity;
+
+        let attack = advance_barbarians(&mut board).unwrap();
+        assert_eq!((attack.strength, attack.defense), (4, 1));
+        assert_eq!(attack.pillaged, vec![(PlayerType::Player1, 10)]);
+        assert_eq!(board.vertices[10].building, Some(BuildingType::Settlement));
+        for vertex_id in [0, 20, 30] {
+            assert_eq!(board.vertices[vertex_id].building, Some(BuildingType::City));
+        }
+        let p1 = player(&board, PlayerType::Player1);
+        assert_eq!(p1.victory_points, 4);
+        assert_eq!(p1.city_quantity, cities_left + 1);
+    }
+
+    #[test]
+    fn level_five_takes_the_metropolis_from_level_four() {
+        let mut board = ck_board(2);
+        let track = ImprovementTrack::Science;
+        put_city(&mut board, PlayerType::Player1, 0);
+        put_city(&mut board, PlayerType::Player2, 20);
+        board.metropolises.insert(track, 0);
+        let p1 = player_mut(&mut board, PlayerType::Player1);
+        p1.improvements.insert(track, METROPOLIS_LEVEL);
+        p1.victory_points = 2;
+        let p2 = player_mut(&mut board, PlayerType::Player2);
+        p2.improvements.insert(track, 3);
+        p2.resources.insert(track.commodity(), 4 + 5);
+
+        // llegar al 4 cuando otro ya lo tiene no alcanza
+        assert_eq!(build_improvement(&mut board, PlayerType::Player2, track), Ok(None));
+        assert_eq!(board.metropolises[&track], 0);
+
+        assert_eq!(build_improvement(&mut board, PlayerType::Player2, track), Ok(Some(20)));
+        assert_eq!(board.metropolises[&track], 20);
+        assert_eq!(metropolis_track(&board, 0), None);
+        assert_eq!(player(&board, PlayerType::Player1).victory_points, 0);
+        assert_eq!(player(&board, PlayerType::Player2).victory_points, 2);
+    }
+
+    #[test]
+    fn promotion_respects_pieces_per_level_and_politics() {
+        let mut board = ck_board(1);
+        let me = PlayerType::Player1;
+        put_knight(&mut board, me, 0, 2, false);
+        put_knight(&mut board, me, 10, 2, false);
+        put_knight(&mut board, me, 20, 1, false);
+
+        // los dos fuertes ya están en juego
+        assert!(promote_knight(&mut board, me, 20, true).is_err());
+        assert_eq!(board.vertices[20].knight.unwrap().level, 1);
+
+        // a poderoso sólo con política 3
+        assert!(promote_knight(&mut board, me, 0, true).is_err());
+        player_mut(&mut board, me).improvements.insert(ImprovementTrack::Politics, ABILITY_LEVEL);
+        assert_eq!(promote_knight(&mut board, me, 0, true), Ok(3));
+        assert_eq!(promote_knight(&mut board, me, 0, true), Err("Ese caballero ya es poderoso."));
+
+        // liberado un fuerte, el básico puede subir
+        assert_eq!(promote_knight(&mut board, me, 20, true), Ok(2));
+        assert_eq!(knights_at_level(&board, me, 2), KNIGHTS_PER_LEVEL);
+    }
+
+    #[test]
+    fn failed_second_road_rolls_the_board_back() {
+        let mut board = ck_board(1);
+        let me = PlayerType::Player1;
+        board.vertices[0].owner = Some(me);
+        board.vertices[0].building = Some(BuildingType::Settlement);
+        player_mut(&mut board, me).progress_cards.push(ProgressCard::RoadBuilding);
+        let before = board.clone();
+
+        let first_edge = board.vertices[0].adjacent_edges[0];
+        let play = ProgressPlay::RoadBuilding { first_edge, second_edge: board.edges.len() };
+        assert!(play_progress_card(&mut board, me, play).is_err());
+        assert_eq!(board.edges[first_edge].owner, None);
+        assert_eq!(board.players, before.players);
+    }
+
+    #[test]
+    fn failed_second_promotion_rolls_the_board_back() {
+        let mut board = ck_board(1);
+        let me = PlayerType::Player1;
+        put_knight(&mut board, me, 0, 1, false);
+        put_knight(&mut board, me, 10, 2, false);
+        player_mut(&mut board, me).progress_cards.push(ProgressCard::Smith);
+
+        // el segundo pide política 3
+        let play = ProgressPlay::Smith { first: 0, second: 10 };
+        assert!(play_progress_card(&mut board, me, play).is_err());
+        assert_eq!(board.vertices[0].knight.unwrap().level, 1);
+        assert_eq!(player(&board, me).progress_cards, vec![ProgressCard::Smith]);
+    }
+
+    #[test]
+    fn founding_city_checks_the_vertex() {
+        let mut board = ck_board(1);
+        let me = PlayerType::Player1;
+        let outside = board.vertices.len();
+        assert_eq!(found_city(&mut board, me, outside), Err("Posición inválida: El vértice no existe."));
+        assert!(found_city(&mut board, me, 0).is_err());
+
+        board.vertices[0].owner = Some(me);
+        board.vertices[0].building = Some(BuildingType::Settlement);
+        assert_eq!(found_city(&mut board, me, 0), Ok(()));
+        assert_eq!(board.vertices[0].building, Some(BuildingType::City));
+        assert_eq!(player(&board, me).victory_points, 1);
+    }
+}
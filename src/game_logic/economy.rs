@@ -1,11 +1,15 @@
 // en src/game_logic/economy.rs
+use super::cities_knights::has_trading_house;
+use super::hooks::run_roll_hooks;
+use super::seafarers::{is_sea, move_pirate, players_with_ships_on_tile};
 use super::victory::check_for_winner;
 use crate::types::*;
+use crate::rules::RuleSet;
 use rand::prelude::*;
 use std::collections::HashMap;
 
 // --- CONSTANTES DE COSTO ---
-const DEVELOPMENT_CARD_COST: &[(MaterialType, u8)] = &[
+pub const DEVELOPMENT_CARD_COST: &[(MaterialType, u8)] = &[
     (MaterialType::Sheep, 1),
     (MaterialType::Wheat, 1),
     (MaterialType::Stone, 1),
@@ -45,9 +49,41 @@ pub fn get_players_adjacent_to_tile(board: &Board, tile_id: TileId) -> Vec<Playe
     players_on_tile
 }
 
+/// ¿Puede ir el ladrón (o el pirata, si es mar) a `tile_id`? Tiene que
+/// ser una casilla del tablero donde no estén ya ni uno ni otro.
+pub fn is_new_robber_tile(board: &Board, tile_id: TileId) -> bool {
+    board.tiles.get(tile_id).is_some_and(|t| !t.has_robber && !t.has_pirate)
+}
+
+/// Jugadores a los que `thief` puede robar desde `tile_id`: los que tienen
+/// edificios ahí (barcos, si es mar y va el pirata), menos él mismo y los
+/// que protege el ladrón amistoso.
+pub fn robbable_players(board: &Board, tile_id: TileId, thief: PlayerType) -> Vec<PlayerType> {
+    if tile_id >= board.tiles.len() {
+        return Vec::new();
+    }
+    let candidates = if is_sea(board, tile_id) {
+        players_with_ships_on_tile(board, tile_id)
+    } else {
+        get_players_adjacent_to_tile(board, tile_id)
+    };
+    candidates
+        .into_iter()
+        .filter(|&p| p != thief)
+        .filter(|&p| board.players.iter().any(|q| q.id == p && board.rules.can_be_robbed(q)))
+        .collect()
+}
+
 // --- FUNCIONES PÚBLICAS ---
 
-pub fn give_materials_on_roll(board: &mut Board, number_rolled: u8) {
+/// Reparte la producción de la tirada y devuelve lo que recibió cada jugador.
+/// Lo de los campos de oro vuelve como `MaterialType::Gold` y no entra en la
+/// mano: cada uno elige después qué recursos quiere. Con Ciudades y Caballeros
+/// las ciudades de bosque, pasto y montaña dan un recurso y una mercancía.
+pub fn give_materials_on_roll(
+    board: &mut Board,
+    number_rolled: u8
+) -> HashMap<PlayerType, HashMap<MaterialType, u8>> {
     let mut payouts: HashMap<PlayerType, HashMap<MaterialType, u8>> = HashMap::new();
 
     for tile in board.tiles.iter() {
@@ -56,33 +92,48 @@ pub fn give_materials_on_roll(board: &mut Board, number_rolled: u8) {
             for &vertex_id in &tile.vertices {
                 if let Some(owner_id) = board.vertices[vertex_id].owner {
                     if let Some(building) = board.vertices[vertex_id].building {
-                        let amount = match building {
-                            BuildingType::Settlement => 1,
-                            BuildingType::City => 2,
+                        let commodity = match building {
+                            BuildingType::City if board.rules.cities_and_knights => material.commodity(),
+                            _ => None,
+                        };
+                        let amount = match (building, commodity) {
+                            (BuildingType::Settlement, _) | (BuildingType::City, Some(_)) => 1,
+                            (BuildingType::City, None) => 2,
                         };
                         let player_payout = payouts.entry(owner_id).or_insert(HashMap::new());
                         let material_count = player_payout.entry(material).or_insert(0);
                         *material_count += amount;
+                        if let Some(commodity) = commodity {
+                            *player_payout.entry(commodity).or_insert(0) += 1;
+                        }
                     }
                 }
             }
         }
     }
 
+    // los escenarios reparten lo suyo (pescado, por ejemplo) aunque no salga nada más
+    run_roll_hooks(board, number_rolled);
+
     if payouts.is_empty() {
         println!("Tirada {}: Ninguna casilla produjo recursos.", number_rolled);
-        return;
+        return payouts;
     }
     println!("Tirada {}: ¡Repartiendo recursos!", number_rolled);
     for player in board.players.iter_mut() {
         if let Some(gains) = payouts.get(&player.id) {
             for (&material, &amount) in gains {
+                if material == MaterialType::Gold {
+                    println!("- {:?} recibe {} de oro (a elegir)", player.id, amount);
+                    continue;
+                }
                 let resource_count = player.resources.entry(material).or_insert(0);
                 *resource_count += amount;
                 println!("- {:?} recibe {} de {:?}", player.id, amount, material);
             }
         }
     }
+    payouts
 }
 
 pub fn give_starting_resources(
@@ -93,7 +144,7 @@ pub fn give_starting_resources(
     let mut resources_to_gain: Vec<MaterialType> = Vec::new();
     for &tile_id in &board.vertices[settlement_pos].adjacent_tiles {
         let tile = &board.tiles[tile_id];
-        if tile.material != MaterialType::Dessert {
+        if tile.material.is_resource() {
             resources_to_gain.push(tile.material);
         }
     }
@@ -114,6 +165,95 @@ pub fn give_starting_resources(
     }
 }
 
+/// Cuántas cartas debe descartar el jugador al salir un 7 (0 si no supera el límite).
+pub fn cards_to_discard(rules: &RuleSet, player: &Player) -> u8 {
+    let total: u8 = player.resources.values().sum();
+    if total > rules.discard_limit {
+        total / 2
+    } else {
+        0
+    }
+}
+
+pub fn discard_resource(
+    board: &mut Board,
+    player_id: PlayerType,
+    material: MaterialType
+) -> Result<(), &'static str> {
+    let player = match board.players.iter_mut().find(|p| p.id == player_id) {
+        Some(p) => p,
+        None => return Err("Error: No se encontró al jugador."),
+    };
+
+    match player.resources.get_mut(&material) {
+        Some(count) if *count > 0 => {
+            *count -= 1;
+            println!("{:?} descarta 1 de {:?}.", player_id, material);
+            Ok(())
+        }
+        _ => Err("No se puede descartar: No tienes ese material."),
+    }
+}
+
+/// Cuántas cartas de `material` pide el banco a este jugador (2, 3 o la tasa de las reglas según sus puertos).
+pub fn bank_rate(rules: &RuleSet, player: &Player, material: MaterialType) -> u8 {
+    let has_specific_port =
+        (material == MaterialType::Wheat && player.power_ups.contains(&PowerUp::Wheat2)) ||
+        (material == MaterialType::Brick && player.power_ups.contains(&PowerUp::Brick2)) ||
+        (material == MaterialType::Stone && player.power_ups.contains(&PowerUp::Stone2)) ||
+        (material == MaterialType::Sheep && player.power_ups.contains(&PowerUp::Sheep2)) ||
+        (material == MaterialType::Wood  && player.power_ups.contains(&PowerUp::Wood2));
+
+    if has_specific_port || (material.is_commodity() && has_trading_house(player)) {
+        2
+    } else if player.power_ups.contains(&PowerUp::Any3) {
+        3.min(rules.bank_rate)
+    } else {
+        rules.bank_rate
+    }
+}
+
+/// ¿Tiene el jugador todas esas cartas? (una entrada por carta)
+pub fn has_cards(player: &Player, materials: &[MaterialType]) -> bool {
+    let mut needed: HashMap<MaterialType, u8> = HashMap::new();
+    for &m in materials {
+        *needed.entry(m).or_default() += 1;
+    }
+    needed.iter().all(|(m, &count)| *player.resources.get(m).unwrap_or(&0) >= count)
+}
+
+/// Comercio entre jugadores: `from` le da `give` a `to` y recibe `get`.
+pub fn trade_between_players(
+    board: &mut Board,
+    from: PlayerType,
+    to: PlayerType,
+    give: &[MaterialType],
+    get: &[MaterialType],
+) -> Result<(), &'static str> {
+    if from == to {
+        return Err("Error de intercambio: No puedes comerciar contigo mismo.");
+    }
+    let from_index = board.players.iter().position(|p| p.id == from).ok_or("Error: No se encontró al jugador.")?;
+    let to_index = board.players.iter().position(|p| p.id == to).ok_or("Error: No se encontró al jugador.")?;
+    if !has_cards(&board.players[from_index], give) {
+        return Err("Error de intercambio: Quien ofrece ya no tiene esas cartas.");
+    }
+    if !has_cards(&board.players[to_index], get) {
+        return Err("Error de intercambio: No tienes las cartas que te piden.");
+    }
+
+    for &m in give {
+        *board.players[from_index].resources.entry(m).or_insert(0) -= 1;
+        *board.players[to_index].resources.entry(m).or_insert(0) += 1;
+    }
+    for &m in get {
+        *board.players[to_index].resources.entry(m).or_insert(0) -= 1;
+        *board.players[from_index].resources.entry(m).or_insert(0) += 1;
+    }
+    println!("{:?} le da {:?} a {:?} a cambio de {:?}.", from, give, to, get);
+    Ok(())
+}
+
 pub fn trade_with_bank(
     board: &mut Board,
     player_id: PlayerType,
@@ -124,8 +264,9 @@ pub fn trade_with_bank(
         println!("Error de intercambio: No puedes intercambiar un material por sí mismo.");
         return false;
     }
-    if material_to_give == MaterialType::Dessert || material_to_get == MaterialType::Dessert {
-        println!("Error de intercambio: No se puede comerciar con el Desierto.");
+    let tradeable = |m: MaterialType| m.is_resource() || (board.rules.cities_and_knights && m.is_commodity());
+    if !tradeable(material_to_give) || !tradeable(material_to_get) {
+        println!("Error de intercambio: Sólo se comercia con recursos (y mercancías).");
         return false;
     }
 
@@ -138,20 +279,7 @@ pub fn trade_with_bank(
     };
 
     let player = &board.players[player_index];
-    let mut required_to_give = 4;
-
-    let has_specific_port = 
-        (material_to_give == MaterialType::Wheat && player.power_ups.contains(&PowerUp::Wheat2)) ||
-        (material_to_give == MaterialType::Brick && player.power_ups.contains(&PowerUp::Brick2)) ||
-        (material_to_give == MaterialType::Stone && player.power_ups.contains(&PowerUp::Stone2)) ||
-        (material_to_give == MaterialType::Sheep && player.power_ups.contains(&PowerUp::Sheep2)) ||
-        (material_to_give == MaterialType::Wood  && player.power_ups.contains(&PowerUp::Wood2));
-    
-    if has_specific_port {
-        required_to_give = 2;
-    } else if player.power_ups.contains(&PowerUp::Any3) {
-        required_to_give = 3;
-    }
+    let required_to_give = bank_rate(&board.rules, player, material_to_give);
 
     let current_resource_count = player.resources.get(&material_to_give).unwrap_or(&0);
     if *current_resource_count < required_to_give {
@@ -185,6 +313,9 @@ pub fn buy_development_card(
         None => return Err("Error: No se encontró al jugador."),
     };
 
+    if board.rules.cities_and_knights {
+        return Err("No se puede comprar: En Ciudades y Caballeros se ganan cartas de progreso con el dado de eventos.");
+    }
     if board.development_cards.is_empty() {
         return Err("No se puede comprar: ¡El mazo de cartas de desarrollo está vacío!");
     }
@@ -217,15 +348,24 @@ pub fn place_robber (
     new_tile_pos: TileId,
     player_to_rob_id: PlayerType
 ) {
-    let current_robber_index = match board.tiles.iter().position(|t| t.has_robber) {
-        Some(index) => index,
-        None => {
-            println!("Error crítico: ¡El ladrón no está en el tablero!");
+    if new_tile_pos < board.tiles.len() && is_sea(board, new_tile_pos) {
+        // en el mar va el pirata, que le roba a los barcos
+        if !robbable_players(board, new_tile_pos, player_id_type).contains(&player_to_rob_id) {
+            println!("No se puede robar: {:?} no tiene barcos junto a la casilla {}.", player_to_rob_id, new_tile_pos);
             return;
         }
-    };
+        if let Err(msg) = move_pirate(board, new_tile_pos) {
+            println!("{}", msg);
+            return;
+        }
+        steal_random_resource(board, player_id_type, player_to_rob_id);
+        return;
+    }
 
-    if current_robber_index == new_tile_pos {
+    // `None`: el ladrón estaba fuera del tablero (Pescadores)
+    let current_robber_index = board.tiles.iter().position(|t| t.has_robber);
+
+    if current_robber_index == Some(new_tile_pos) {
         println!("No se puede mover: Debes mover el ladrón a una *nueva* casilla.");
         return;
     }
@@ -240,13 +380,53 @@ pub fn place_robber (
         println!("No se puede robar: No puedes robarte a ti mismo.");
         return;
     }
+
+    if !robbable_players(board, new_tile_pos, player_id_type).contains(&player_to_rob_id) {
+        println!("No se puede robar: El ladrón amistoso protege a {:?}.", player_to_rob_id);
+        return;
+    }
     
+    if let Some(current_robber_index) = current_robber_index {
+        board.tiles[current_robber_index].has_robber = false;
+    }
+    board.tiles[new_tile_pos].has_robber = true;
+    println!("Ladrón movido a la casilla {}.", new_tile_pos);
+
+    steal_random_resource(board, player_id_type, player_to_rob_id);
+}
+
+/// Mueve el ladrón sin robar (por ejemplo, cuando no hay nadie en la casilla).
+pub fn move_robber(board: &mut Board, new_tile_pos: TileId) -> Result<(), &'static str> {
+    if new_tile_pos >= board.tiles.len() {
+        return Err("Posición inválida: La casilla no existe.");
+    }
+    if is_sea(board, new_tile_pos) {
+        return move_pirate(board, new_tile_pos);
+    }
+    // el ladrón puede estar fuera del tablero (Pescadores): entonces vuelve
+    let Some(current_robber_index) = board.tiles.iter().position(|t| t.has_robber) else {
+        board.tiles[new_tile_pos].has_robber = true;
+        println!("El ladrón vuelve al tablero en la casilla {}.", new_tile_pos);
+        return Ok(());
+    };
+    if current_robber_index == new_tile_pos {
+        return Err("No se puede mover: Debes mover el ladrón a una *nueva* casilla.");
+    }
+
     board.tiles[current_robber_index].has_robber = false;
     board.tiles[new_tile_pos].has_robber = true;
     println!("Ladrón movido de la casilla {} a la {}.", current_robber_index, new_tile_pos);
+    Ok(())
+}
 
-    let player_moving_index = board.players.iter().position(|p| p.id == player_id_type).unwrap();
-    let player_robbed_index = board.players.iter().position(|p| p.id == player_to_rob_id).unwrap();
+/// Pasa una carta al azar de `player_to_rob_id` a `player_id_type`. Devuelve la carta robada.
+pub fn steal_random_resource(
+    board: &mut Board,
+    player_id_type: PlayerType,
+    player_to_rob_id: PlayerType
+) -> Option<MaterialType> {
+    let player_moving_index = board.players.iter().position(|p| p.id == player_id_type)?;
+    let player_robbed_index = board.players.iter().position(|p| p.id == player_to_rob_id)?;
 
     let robbable_resources: Vec<MaterialType> = board.players[player_robbed_index]
         .resources
@@ -257,7 +437,7 @@ pub fn place_robber (
 
     if robbable_resources.is_empty() {
         println!("¡El jugador {:?} no tiene cartas para robar!", player_to_rob_id);
-        return;
+        return None;
     }
 
     let &resource_stolen = robbable_resources.choose(&mut rand::thread_rng()).unwrap();
@@ -272,4 +452,5 @@ pub fn place_robber (
         let resource_count = player_moving.resources.entry(resource_stolen).or_insert(0);
         *resource_count += 1;
     }
+    Some(resource_stolen)
 }
\ No newline at end of file
@@ -0,0 +1,212 @@
+// en src/game_logic/fishermen.rs
+//
+// Los Pescadores de Catán, como escenario enchufable. El desierto es un
+// lago que da pescado con 2, 3, 11 y 12, y seis zonas de pesca sobre la
+// costa dan pescado con su número a las casas de sus dos vértices (una
+// ficha por casa, dos por ciudad). El pescado se canjea: 2 sacan al
+// ladrón del tablero, 4 dan un recurso y 5 un camino. La primera pesca
+// del lago trae la bota vieja: quien la tiene necesita un punto más para
+// ganar, y al terminar su turno se la pasa a quien tenga al menos sus puntos.
+
+use crate::types::*;
+use super::hooks::{add_tokens, has_piece, is_coast_edge, token_count, RewardKind, ScenarioHooks, TokenExchange};
+
+pub const FISH: &str = "pescado";
+const OLD_BOOT: &str = "bota vieja";
+const FISHING_GROUND: &str = "zona de pesca";
+const LAKE: &str = "lago";
+
+const FISHING_NUMBERS: [u8; 6] = [4, 5, 6, 8, 9, 10];
+const LAKE_NUMBERS: [u8; 4] = [2, 3, 11, 12];
+
+const EXCHANGES: &[TokenExchange] = &[
+    TokenExchange { token: FISH, cost: 2, reward: RewardKind::RemoveRobber },
+    TokenExchange { token: FISH, cost: 4, reward: RewardKind::Resource },
+    TokenExchange { token: FISH, cost: 5, reward: RewardKind::Road },
+];
+
+pub struct Fishermen;
+
+fn piece(kind: &str, number: u8) -> ScenarioPiece {
+    ScenarioPiece { kind: kind.to_string(), owner: None, number }
+}
+
+/// Casas sobre `vertices`: (dueño, fichas que le tocan).
+fn catches(board: &Board, vertices: &[VertexId]) -> Vec<(PlayerType, u8)> {
+    vertices
+        .iter()
+        .filter_map(|&v| match (board.vertices[v].owner, board.vertices[v].building) {
+            (Some(owner), Some(BuildingType::Settlement)) => Some((owner, 1)),
+            (Some(owner), Some(BuildingType::City)) => Some((owner, 2)),
+            _ => None,
+        })
+        .collect()
+}
+
+impl ScenarioHooks for Fishermen {
+    fn name(&self) -> &'static str {
+        "Los Pescadores de Catán"
+    }
+
+    fn prepare(&self, board: &mut Board) {
+        if let Some(lake) = board.tiles.iter_mut().find(|t| t.material == MaterialType::Dessert) {
+            lake.pieces.push(piece(LAKE, 0));
+        }
+        // zonas de pesca repartidas a lo largo de la costa
+        let coast: Vec<EdgeId> = (0..board.edges.len()).filter(|&e| is_coast_edge(board, e)).collect();
+        let step = (coast.len() / FISHING_NUMBERS.len()).max(1);
+        for (i, &number) in FISHING_NUMBERS.iter().enumerate() {
+            if let Some(&edge) = coast.get(i * step) {
+                board.edges[edge].pieces.push(piece(FISHING_GROUND, number));
+            }
+        }
+    }
+
+    fn on_roll(&self, board: &mut Board, roll: u8) {
+        let mut vertices = Vec::new();
+        for edge in &board.edges {
+            if edge.pieces.iter().any(|p| p.kind == FISHING_GROUND && p.number == roll) {
+                vertices.extend([edge.vertices.0, edge.vertices.1]);
+            }
+        }
+        let mut lake_catches = Vec::new();
+        if LAKE_NUMBERS.contains(&roll) {
+            for tile in board.tiles.iter().filter(|t| has_piece(&t.pieces, LAKE)) {
+                lake_catches.extend(catches(board, &tile.vertices));
+            }
+        }
+
+        for (owner, amount) in catches(board, &vertices).into_iter().chain(lake_catches.iter().copied()) {
+            add_tokens(board, owner, FISH, amount);
+            println!("- {:?} pesca {} de {}", owner, amount, FISH);
+        }
+
+        let boot_in_play = board.players.iter().any(|p| token_count(p, OLD_BOOT) > 0);
+        if let Some(&(owner, _)) = lake_catches.first().filter(|_| !boot_in_play) {
+            add_tokens(board, owner, OLD_BOOT, 1);
+            println!("¡{:?} saca la {} del lago!", owner, OLD_BOOT);
+        }
+    }
+
+    fn on_turn_end(&self, board: &mut Board, player: PlayerType) {
+        let Some(holder) = board.players.iter().find(|p| p.id == player && token_count(p, OLD_BOOT) > 0) else {
+            return;
+        };
+        let points = holder.victory_points;
+        let next = board
+            .players
+            .iter()
+            .filter(|p| p.id != player && p.victory_points >= points)
+            .max_by_key(|p| p.victory_points)
+            .map(|p| p.id);
+        if let Some(next) = next {
+            if let Some(holder) = board.players.iter_mut().find(|p| p.id == player) {
+                holder.tokens.remove(OLD_BOOT);
+            }
+            add_tokens(board, next, OLD_BOOT, 1);
+            println!("{:?} le pasa la {} a {:?}.", player, OLD_BOOT, next);
+        }
+    }
+
+    fn victory_points(&self, board: &Board, player: PlayerType) -> i8 {
+        match board.players.iter().find(|p| p.id == player) {
+            Some(p) if token_count(p, OLD_BOOT) > 0 => -1,
+            _ => 0,
+        }
+    }
+
+    fn exchanges(&self) -> &'static [TokenExchange] {
+        EXCHANGES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_logic::{exchange_tokens, is_road_connectable, run_roll_hooks};
+    use crate::rules::RuleSet;
+    use crate::setup::{add_player, set_rules, setup_board_with_layout, MapLayout};
+
+    /// Tablero fijo con los Pescadores y dos jugadores; Player1 con `fish` de pescado.
+    fn fishing_board(fish: u8) -> Board {
+        let mut board = setup_board_with_layout(MapLayout::Fixed);
+        add_player(&mut board);
+        add_player(&mut board);
+        set_rules(&mut board, RuleSet::default().with_plugin(ScenarioPlugin::Fishermen));
+        add_tokens(&mut board, PlayerType::Player1, FISH, fish);
+        board
+    }
+
+    fn fish_of(board: &Board, player_id: PlayerType) -> u8 {
+        board.players.iter().find(|p| p.id == player_id).map_or(0, |p| token_count(p, FISH))
+    }
+
+    #[test]
+    fn fishing_grounds_pay_the_houses_on_their_edge() {
+        let mut board = fishing_board(0);
+        let ground = board
+            .edges
+            .iter()
+            .position(|e| e.pieces.iter().any(|p| p.kind == FISHING_GROUND && p.number == 4))
+            .unwrap();
+        let (v1, v2) = board.edges[ground].vertices;
+        board.vertices[v1].owner = Some(PlayerType::Player1);
+        board.vertices[v1].building = Some(BuildingType::Settlement);
+        board.vertices[v2].owner = Some(PlayerType::Player2);
+        board.vertices[v2].building = Some(BuildingType::City);
+
+        run_roll_hooks(&mut board, 4);
+        assert_eq!(fish_of(&board, PlayerType::Player1), 1);
+        assert_eq!(fish_of(&board, PlayerType::Player2), 2);
+    }
+
+    #[test]
+    fn four_fish_buy_a_resource() {
+        let mut board = fishing_board(3);
+        let me = PlayerType::Player1;
+        let wheat = TokenReward::Resource(MaterialType::Wheat);
+        assert!(exchange_tokens(&mut board, me, FISH, wheat).is_err());
+
+        add_tokens(&mut board, me, FISH, 1);
+        assert!(exchange_tokens(&mut board, me, FISH, TokenReward::Resource(MaterialType::Gold)).is_err());
+        assert_eq!(fish_of(&board, me), 4);
+        assert!(exchange_tokens(&mut board, me, FISH, wheat).is_ok());
+        assert_eq!(fish_of(&board, me), 0);
+        assert_eq!(board.players[0].resources.get(&MaterialType::Wheat), Some(&1));
+    }
+
+    #[test]
+    fn five_fish_build_a_connected_road() {
+        let mut board = fishing_board(5);
+        let me = PlayerType::Player1;
+        board.vertices[0].owner = Some(me);
+        board.vertices[0].building = Some(BuildingType::Settlement);
+        let roads = board.players[0].road_quantity;
+
+        // un camino suelto no se da y el pescado queda
+        let far = (0..board.edges.len())
+            .find(|&e| !is_road_connectable(&board, me, e))
+            .unwrap();
+        assert!(exchange_tokens(&mut board, me, FISH, TokenReward::Road(far)).is_err());
+        assert_eq!(fish_of(&board, me), 5);
+
+        let edge = board.vertices[0].adjacent_edges[0];
+        assert!(exchange_tokens(&mut board, me, FISH, TokenReward::Road(edge)).is_ok());
+        assert_eq!(board.edges[edge].owner, Some(me));
+        assert_eq!(board.players[0].road_quantity, roads - 1);
+        assert_eq!(fish_of(&board, me), 0);
+    }
+
+    #[test]
+    fn two_fish_take_the_robber_off_the_board() {
+        let mut board = fishing_board(4);
+        let me = PlayerType::Player1;
+        assert!(exchange_tokens(&mut board, me, FISH, TokenReward::RemoveRobber).is_ok());
+        assert!(board.tiles.iter().all(|t| !t.has_robber));
+        assert_eq!(fish_of(&board, me), 2);
+
+        // sin ladrón en el tablero no hay canje
+        assert!(exchange_tokens(&mut board, me, FISH, TokenReward::RemoveRobber).is_err());
+        assert_eq!(fish_of(&board, me), 2);
+    }
+}
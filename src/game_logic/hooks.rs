@@ -0,0 +1,264 @@
+// en src/game_logic/hooks.rs
+//
+// Ganchos de escenario. En vez de meter cada expansión a mano en la
+// lógica, un escenario implementa `ScenarioHooks` y se activa desde las
+// reglas (`rules.plugins`). El reparto de la tirada, la construcción, el
+// fin de turno y el conteo de victoria llaman a los ganchos de los
+// escenarios activos. Lo que un escenario necesite guardar va en las
+// piezas de vértices, bordes y casillas (`pieces`) y en las fichas de
+// cada jugador (`tokens`), así el tablero se sigue guardando como JSON.
+
+use crate::types::*;
+use super::building::place_road;
+use super::fishermen::Fishermen;
+use super::rivers::Rivers;
+use super::seafarers::{edge_tiles, is_sea};
+use super::victory::check_for_winner;
+
+/// Lo que se construyó (o se quiere construir).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Construction {
+    Settlement(VertexId),
+    City(VertexId),
+    Road(EdgeId),
+}
+
+/// Tipo de premio de un canje, sin lo que elige quien canjea.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardKind {
+    Resource,
+    Road,
+    RemoveRobber,
+}
+
+impl TokenReward {
+    pub fn kind(self) -> RewardKind {
+        match self {
+            TokenReward::Resource(_) => RewardKind::Resource,
+            TokenReward::Road(_) => RewardKind::Road,
+            TokenReward::RemoveRobber => RewardKind::RemoveRobber,
+        }
+    }
+}
+
+/// Un canje que ofrece un escenario: `cost` fichas de `token` por un premio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenExchange {
+    pub token: &'static str,
+    pub cost: u8,
+    pub reward: RewardKind,
+}
+
+/// Lo que puede cambiar un escenario. Todo tiene un valor por defecto que
+/// no hace nada, así cada uno implementa sólo lo que usa.
+pub trait ScenarioHooks: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Pone las piezas del escenario en un tablero recién armado.
+    fn prepare(&self, _board: &mut Board) {}
+
+    /// Después del reparto de una tirada que no fue 7.
+    fn on_roll(&self, _board: &mut Board, _roll: u8) {}
+
+    /// Lo que cuesta de más construir; se cobra junto con el costo normal.
+    fn extra_cost(&self, _board: &Board, _construction: Construction) -> &'static [(MaterialType, u8)] {
+        &[]
+    }
+
+    /// Después de construir (también en la fundación y con caminos gratis).
+    fn on_build(&self, _board: &mut Board, _player: PlayerType, _construction: Construction) {}
+
+    /// Al terminar el turno de `player`.
+    fn on_turn_end(&self, _board: &mut Board, _player: PlayerType) {}
+
+    /// Puntos que el escenario suma (o resta) al contar la victoria.
+    fn victory_points(&self, _board: &Board, _player: PlayerType) -> i8 {
+        0
+    }
+
+    /// Canjes de fichas que ofrece el escenario.
+    fn exchanges(&self) -> &'static [TokenExchange] {
+        &[]
+    }
+}
+
+impl ScenarioPlugin {
+    pub fn hooks(self) -> &'static dyn ScenarioHooks {
+        match self {
+            ScenarioPlugin::Fishermen => &Fishermen,
+            ScenarioPlugin::Rivers => &Rivers,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.hooks().name()
+    }
+}
+
+// --- FUNCIONES AUXILIARES ---
+
+fn active(board: &Board) -> Vec<&'static dyn ScenarioHooks> {
+    board.rules.plugins.iter().map(|p| p.hooks()).collect()
+}
+
+/// Casillas de tierra que tocan el vértice (para buscar costas y ríos).
+pub(crate) fn land_tiles_around(board: &Board, vertex_id: VertexId) -> usize {
+    board.vertices[vertex_id].adjacent_tiles.iter().filter(|&&t| !is_sea(board, t)).count()
+}
+
+/// ¿El borde da a la costa? Tiene tierra de un solo lado.
+pub(crate) fn is_coast_edge(board: &Board, edge_id: EdgeId) -> bool {
+    edge_tiles(board, edge_id).iter().filter(|&&t| !is_sea(board, t)).count() == 1
+}
+
+pub(crate) fn has_piece(pieces: &[ScenarioPiece], kind: &str) -> bool {
+    pieces.iter().any(|p| p.kind == kind)
+}
+
+pub fn token_count(player: &Player, token: &str) -> u8 {
+    player.tokens.get(token).copied().unwrap_or(0)
+}
+
+pub(crate) fn add_tokens(board: &mut Board, player_id: PlayerType, token: &str, amount: u8) {
+    if let Some(player) = board.players.iter_mut().find(|p| p.id == player_id) {
+        *player.tokens.entry(token.to_string()).or_insert(0) += amount;
+    }
+}
+
+// --- FUNCIONES PÚBLICAS ---
+
+/// Arma las piezas de los escenarios activos. Se llama al fijar las reglas.
+pub fn prepare_scenarios(board: &mut Board) {
+    for hooks in active(board) {
+        hooks.prepare(board);
+    }
+}
+
+pub fn run_roll_hooks(board: &mut Board, roll: u8) {
+    for hooks in active(board) {
+        hooks.on_roll(board, roll);
+    }
+}
+
+pub fn run_build_hooks(board: &mut Board, player_id: PlayerType, construction: Construction) {
+    for hooks in active(board) {
+        hooks.on_build(board, player_id, construction);
+    }
+}
+
+pub fn run_turn_end_hooks(board: &mut Board, player_id: PlayerType) {
+    for hooks in active(board) {
+        hooks.on_turn_end(board, player_id);
+    }
+}
+
+/// Lo que piden de más los escenarios por construir esto.
+pub fn extra_build_cost(board: &Board, construction: Construction) -> Vec<(MaterialType, u8)> {
+    let mut cost = Vec::new();
+    for hooks in active(board) {
+        cost = merge_cost(&cost, hooks.extra_cost(board, construction));
+    }
+    cost
+}
+
+/// Costo normal más el de los escenarios, con cada material una sola vez.
+pub fn build_cost(board: &Board, base: &[(MaterialType, u8)], construction: Construction) -> Vec<(MaterialType, u8)> {
+    merge_cost(base, &extra_build_cost(board, construction))
+}
+
+fn merge_cost(a: &[(MaterialType, u8)], b: &[(MaterialType, u8)]) -> Vec<(MaterialType, u8)> {
+    let mut cost = a.to_vec();
+    for &(material, amount) in b {
+        match cost.iter_mut().find(|(m, _)| *m == material) {
+            Some((_, n)) => *n += amount,
+            None => cost.push((material, amount)),
+        }
+    }
+    cost
+}
+
+/// Suma de lo que dan (o quitan) los escenarios al contar la victoria.
+pub fn scenario_points(board: &Board, player_id: PlayerType) -> i16 {
+    active(board).iter().map(|h| h.victory_points(board, player_id) as i16).sum()
+}
+
+pub fn available_exchanges(board: &Board) -> Vec<TokenExchange> {
+    active(board).iter().flat_map(|h| h.exchanges().iter().copied()).collect()
+}
+
+/// Canjea fichas de `token` por `reward`. Si el premio no se puede dar, no se gasta nada.
+pub fn exchange_tokens(
+    board: &mut Board,
+    player_id: PlayerType,
+    token: &str,
+    reward: TokenReward,
+) -> Result<Option<PlayerType>, &'static str> {
+    let exchange = available_exchanges(board)
+        .into_iter()
+        .find(|e| e.token == token && e.reward == reward.kind())
+        .ok_or("Ese canje no existe en esta partida.")?;
+    let index = board
+        .players
+        .iter()
+        .position(|p| p.id == player_id)
+        .ok_or("Error: No se encontró al jugador.")?;
+    if token_count(&board.players[index], token) < exchange.cost {
+        return Err("No tienes fichas suficientes para ese canje.");
+    }
+
+    match reward {
+        TokenReward::Resource(material) => {
+            if !material.is_resource() {
+                return Err("Material no válido.");
+            }
+            *board.players[index].resources.entry(material).or_insert(0) += 1;
+        }
+        TokenReward::Road(edge) => {
+            place_road(board, player_id, edge, TurnPhase::FreeRoad)?;
+        }
+        TokenReward::RemoveRobber => {
+            let robber = board.tiles.iter_mut().find(|t| t.has_robber).ok_or("El ladrón ya no está en el tablero.")?;
+            robber.has_robber = false;
+        }
+    }
+
+    if let Some(count) = board.players[index].tokens.get_mut(token) {
+        *count -= exchange.cost;
+    }
+    println!("{:?} canjea {} de {} por {:?}.", player_id, exchange.cost, token, reward);
+    Ok(check_for_winner(board))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RuleSet;
+    use crate::setup::{add_player, set_rules, setup_board_with_layout, MapLayout};
+
+    #[test]
+    fn exchanges_come_only_from_active_scenarios() {
+        let mut board = setup_board_with_layout(MapLayout::Fixed);
+        add_player(&mut board);
+        add_tokens(&mut board, PlayerType::Player1, "pescado", 4);
+        assert!(available_exchanges(&board).is_empty());
+        assert_eq!(
+            exchange_tokens(&mut board, PlayerType::Player1, "pescado", TokenReward::RemoveRobber),
+            Err("Ese canje no existe en esta partida.")
+        );
+
+        let rules = RuleSet::default()
+            .with_plugin(ScenarioPlugin::Fishermen)
+            .with_plugin(ScenarioPlugin::Rivers);
+        set_rules(&mut board, rules);
+        assert_eq!(available_exchanges(&board).len(), 4);
+    }
+
+    #[test]
+    fn extra_costs_add_up_per_material() {
+        let cost = merge_cost(
+            &[(MaterialType::Brick, 1), (MaterialType::Wood, 1)],
+            &[(MaterialType::Brick, 1), (MaterialType::Wheat, 1)],
+        );
+        assert_eq!(cost, vec![(MaterialType::Brick, 2), (MaterialType::Wood, 1), (MaterialType::Wheat, 1)]);
+    }
+}
@@ -2,7 +2,12 @@
 
 // 1. Declara los nuevos módulos de archivos
 mod building;
+mod cities_knights;
 mod economy;
+mod fishermen;
+mod hooks;
+mod rivers;
+mod seafarers;
 mod victory;
 
 // 2. Exporta (hace públicas) las funciones que `main.rs`
@@ -14,21 +19,103 @@ pub use building::{
     place_city, 
     place_road,
     is_road_adjacent_to_vertex,
-    is_road_connectable
+    is_road_connectable,
+    settlement_spots,
+    city_spots,
+    road_spots,
+    SETTLEMENT_COST,
+    ROAD_COST,
+    CITY_COST
 };
 
 // Desde `economy.rs`
 pub use economy::{
     give_materials_on_roll, 
     give_starting_resources, 
+    cards_to_discard,
+    discard_resource,
+    bank_rate,
     trade_with_bank, 
+    trade_between_players,
+    has_cards,
     buy_development_card, 
     place_robber,
-    get_players_adjacent_to_tile // <-- ¡AÑADE ESTA LÍNEA!
+    move_robber,
+    is_new_robber_tile,
+    steal_random_resource,
+    get_players_adjacent_to_tile, // <-- ¡AÑADE ESTA LÍNEA!
+    robbable_players,
+    has_resources,
+    DEVELOPMENT_CARD_COST
 };
 
+// Desde `seafarers.rs`
+pub use seafarers::{
+    place_ship,
+    move_ship,
+    movable_ships,
+    reset_ship_turn,
+    is_sea,
+    edge_tiles,
+    is_ship_owned_by,
+    pirate_tile,
+    players_with_ships_on_tile,
+    gold_owed,
+    take_gold,
+    islands,
+    island_of_vertex,
+    SHIP_COST
+};
+
+// Desde `cities_knights.rs`
+pub use cities_knights::{
+    build_improvement,
+    place_knight,
+    activate_knight,
+    promote_knight,
+    knight_strength,
+    improvement_level,
+    can_promote_to_mighty,
+    metropolis_track,
+    roll_event_die,
+    advance_barbarians,
+    draw_progress_cards,
+    aqueduct_owed,
+    choices_owed,
+    play_progress_card,
+    found_city,
+    BarbarianAttack,
+    ProgressOutcome,
+    KNIGHT_COST,
+    KNIGHT_ACTIVATION_COST,
+    KNIGHT_PROMOTION_COST,
+    BARBARIAN_TRACK_LENGTH
+};
+
+// Desde `hooks.rs` (escenarios enchufables)
+pub use hooks::{
+    prepare_scenarios,
+    run_roll_hooks,
+    run_build_hooks,
+    run_turn_end_hooks,
+    extra_build_cost,
+    build_cost,
+    scenario_points,
+    available_exchanges,
+    exchange_tokens,
+    token_count,
+    Construction,
+    RewardKind,
+    ScenarioHooks,
+    TokenExchange
+};
+pub use fishermen::FISH;
+pub use rivers::GOLD_COINS;
+
 // Desde `victory.rs`
 pub use victory::{
     check_for_winner, 
-    update_largest_army
+    check_for_winner_with_target,
+    update_largest_army,
+    calculate_player_longest_road
 };
\ No newline at end of file
@@ -0,0 +1,186 @@
+// en src/game_logic/rivers.rs
+//
+// Los Ríos de Catán, como escenario enchufable. Dos ríos bajan desde la
+// costa hacia el centro por los bordes entre casillas. Un camino sobre el
+// río es un puente: cuesta un ladrillo más y da 3 monedas de oro; cada
+// casa junto al río da una moneda. Dos monedas se canjean por un recurso.
+// Quien tiene más monedas, solo, es el colono más rico (+1 punto); los
+// que tienen menos, si no están todos iguales, son colonos pobres (-2).
+
+use crate::types::*;
+use super::hooks::{add_tokens, has_piece, land_tiles_around, token_count, Construction, RewardKind, ScenarioHooks, TokenExchange};
+use super::seafarers::is_land_edge;
+
+pub const GOLD_COINS: &str = "monedas";
+const RIVER: &str = "río";
+
+const RIVERS: usize = 2;
+/// Bordes de cada río.
+const RIVER_LENGTH: usize = 4;
+const BRIDGE_EXTRA_COST: &[(MaterialType, u8)] = &[(MaterialType::Brick, 1)];
+const BRIDGE_COINS: u8 = 3;
+const RIVERSIDE_COINS: u8 = 1;
+
+const EXCHANGES: &[TokenExchange] = &[TokenExchange { token: GOLD_COINS, cost: 2, reward: RewardKind::Resource }];
+
+pub struct Rivers;
+
+fn is_river(board: &Board, edge_id: EdgeId) -> bool {
+    has_piece(&board.edges[edge_id].pieces, RIVER)
+}
+
+/// Baja desde `start` siempre hacia el vértice con más tierra alrededor.
+fn trace_river(board: &mut Board, start: VertexId) {
+    let mut current = start;
+    for _ in 0..RIVER_LENGTH {
+        let next = board.vertices[current]
+            .adjacent_edges
+            .iter()
+            .copied()
+            .filter(|&e| is_land_edge(board, e) && !is_river(board, e))
+            .map(|e| {
+                let (v1, v2) = board.edges[e].vertices;
+                (e, if v1 == current { v2 } else { v1 })
+            })
+            .max_by_key(|&(e, v)| (land_tiles_around(board, v), std::cmp::Reverse(e)));
+        let Some((edge, vertex)) = next else {
+            return;
+        };
+        board.edges[edge].pieces.push(ScenarioPiece { kind: RIVER.to_string(), owner: None, number: 0 });
+        current = vertex;
+    }
+}
+
+impl ScenarioHooks for Rivers {
+    fn name(&self) -> &'static str {
+        "Los Ríos de Catán"
+    }
+
+    fn prepare(&self, board: &mut Board) {
+        // nacen en vértices de la costa que tocan dos casillas de tierra
+        let springs: Vec<VertexId> = (0..board.vertices.len())
+            .filter(|&v| land_tiles_around(board, v) == 2 && board.vertices[v].adjacent_tiles.len() < 3)
+            .collect();
+        for i in 0..RIVERS {
+            if let Some(&spring) = springs.get(i * springs.len() / RIVERS) {
+                trace_river(board, spring);
+            }
+        }
+    }
+
+    fn extra_cost(&self, board: &Board, construction: Construction) -> &'static [(MaterialType, u8)] {
+        match construction {
+            Construction::Road(edge) if is_river(board, edge) => BRIDGE_EXTRA_COST,
+            _ => &[],
+        }
+    }
+
+    fn on_build(&self, board: &mut Board, player: PlayerType, construction: Construction) {
+        let coins = match construction {
+            Construction::Road(edge) if is_river(board, edge) => BRIDGE_COINS,
+            Construction::Settlement(vertex)
+                if board.vertices[vertex].adjacent_edges.iter().any(|&e| is_river(board, e)) =>
+            {
+                RIVERSIDE_COINS
+            }
+            _ => return,
+        };
+        add_tokens(board, player, GOLD_COINS, coins);
+        println!("{:?} recibe {} {} junto al río.", player, coins, GOLD_COINS);
+    }
+
+    fn victory_points(&self, board: &Board, player: PlayerType) -> i8 {
+        let coins: Vec<(PlayerType, u8)> = board.players.iter().map(|p| (p.id, token_count(p, GOLD_COINS))).collect();
+        let Some(&(_, mine)) = coins.iter().find(|(p, _)| *p == player) else {
+            return 0;
+        };
+        let most = coins.iter().map(|&(_, c)| c).max().unwrap_or(0);
+        let least = coins.iter().map(|&(_, c)| c).min().unwrap_or(0);
+        if most == least {
+            return 0;
+        }
+        if mine == most && coins.iter().filter(|&&(_, c)| c == most).count() == 1 {
+            1
+        } else if mine == least {
+            -2
+        } else {
+            0
+        }
+    }
+
+    fn exchanges(&self) -> &'static [TokenExchange] {
+        EXCHANGES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_logic::{build_cost, check_for_winner, place_road, scenario_points, ROAD_COST};
+    use crate::rules::RuleSet;
+    use crate::setup::{add_player, set_rules, setup_board_with_layout, MapLayout};
+
+    /// Tablero fijo con los Ríos y `players` jugadores.
+    fn river_board(players: usize) -> Board {
+        let mut board = setup_board_with_layout(MapLayout::Fixed);
+        for _ in 0..players {
+            add_player(&mut board);
+        }
+        set_rules(&mut board, RuleSet::default().with_plugin(ScenarioPlugin::Rivers));
+        board
+    }
+
+    fn set_coins(board: &mut Board, coins: [u8; 3]) {
+        for (player, amount) in board.players.iter_mut().zip(coins) {
+            player.tokens.insert(GOLD_COINS.to_string(), amount);
+        }
+    }
+
+    #[test]
+    fn bridge_costs_a_brick_more_and_pays_coins() {
+        let mut board = river_board(1);
+        let me = PlayerType::Player1;
+        let bridge = (0..board.edges.len()).find(|&e| is_river(&board, e)).unwrap();
+        let land = (0..board.edges.len()).find(|&e| is_land_edge(&board, e) && !is_river(&board, e)).unwrap();
+        assert_eq!(build_cost(&board, ROAD_COST, Construction::Road(land)), ROAD_COST.to_vec());
+        assert_eq!(
+            build_cost(&board, ROAD_COST, Construction::Road(bridge)),
+            vec![(MaterialType::Brick, 2), (MaterialType::Wood, 1)]
+        );
+
+        let (v1, _) = board.edges[bridge].vertices;
+        board.vertices[v1].owner = Some(me);
+        board.vertices[v1].building = Some(BuildingType::Settlement);
+        board.players[0].resources.insert(MaterialType::Brick, 1);
+        board.players[0].resources.insert(MaterialType::Wood, 1);
+        assert!(place_road(&mut board, me, bridge, TurnPhase::Normal).is_err());
+
+        board.players[0].resources.insert(MaterialType::Brick, 2);
+        assert!(place_road(&mut board, me, bridge, TurnPhase::Normal).is_ok());
+        assert!(board.players[0].resources.values().all(|&n| n == 0));
+        assert_eq!(token_count(&board.players[0], GOLD_COINS), BRIDGE_COINS);
+    }
+
+    #[test]
+    fn richest_and_poorest_settlers_count_for_the_win() {
+        let mut board = river_board(3);
+        board.players[0].victory_points = board.rules.vp_target - 1;
+        board.players[1].victory_points = board.rules.vp_target;
+
+        // todos iguales: nadie suma ni resta
+        set_coins(&mut board, [2, 2, 2]);
+        assert_eq!(check_for_winner(&board), Some(PlayerType::Player2));
+
+        // el más rico llega a la meta y el pobre se queda a dos
+        set_coins(&mut board, [4, 1, 1]);
+        assert_eq!(scenario_points(&board, PlayerType::Player1), 1);
+        assert_eq!(scenario_points(&board, PlayerType::Player2), -2);
+        assert_eq!(check_for_winner(&board), Some(PlayerType::Player1));
+
+        // empatados arriba, no hay colono más rico
+        set_coins(&mut board, [4, 4, 1]);
+        assert_eq!(scenario_points(&board, PlayerType::Player1), 0);
+        assert_eq!(scenario_points(&board, PlayerType::Player3), -2);
+        assert_eq!(check_for_winner(&board), Some(PlayerType::Player2));
+    }
+}
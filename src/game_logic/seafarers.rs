@@ -0,0 +1,506 @@
+// en src/game_logic/seafarers.rs
+//
+// Navegantes de Catán: mar, campos de oro, barcos, el pirata y las islas.
+// En un tablero sin casillas de mar nada de esto cambia el juego: no hay
+// bordes donde poner barcos y toda la tierra es una sola isla.
+
+use crate::types::*;
+use super::economy::{has_resources, spend_resources};
+use super::victory::{check_for_winner, update_longest_road};
+use std::collections::HashMap;
+
+pub const SHIP_COST: &[(MaterialType, u8)] = &[(MaterialType::Wood, 1), (MaterialType::Sheep, 1)];
+
+// --- FUNCIONES AUXILIARES ---
+
+pub fn is_sea(board: &Board, tile_id: TileId) -> bool {
+    board.tiles[tile_id].material == MaterialType::Sea
+}
+
+/// Casillas que tocan el borde (una o dos).
+pub fn edge_tiles(board: &Board, edge_id: EdgeId) -> Vec<TileId> {
+    let (v1, v2) = board.edges[edge_id].vertices;
+    board.vertices[v1]
+        .adjacent_tiles
+        .iter()
+        .copied()
+        .filter(|t| board.vertices[v2].adjacent_tiles.contains(t))
+        .collect()
+}
+
+/// Bordes de una casilla (los que unen dos de sus vértices).
+pub fn tile_edges(board: &Board, tile_id: TileId) -> Vec<EdgeId> {
+    let tile = &board.tiles[tile_id];
+    board
+        .edges
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| tile.vertices.contains(&e.vertices.0) && tile.vertices.contains(&e.vertices.1))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// ¿El vértice toca tierra? En el mar abierto no se puede fundar.
+pub fn is_land_vertex(board: &Board, vertex_id: VertexId) -> bool {
+    board.vertices[vertex_id].adjacent_tiles.iter().any(|&t| !is_sea(board, t))
+}
+
+/// ¿Se puede poner un camino? Hace falta tierra a algún lado.
+pub fn is_land_edge(board: &Board, edge_id: EdgeId) -> bool {
+    edge_tiles(board, edge_id).iter().any(|&t| !is_sea(board, t))
+}
+
+/// ¿Se puede poner un barco? Hace falta mar a algún lado.
+pub fn is_sea_edge(board: &Board, edge_id: EdgeId) -> bool {
+    edge_tiles(board, edge_id).iter().any(|&t| is_sea(board, t))
+}
+
+pub fn is_ship_owned_by(board: &Board, player_id: PlayerType, edge_id: EdgeId) -> bool {
+    let edge = &board.edges[edge_id];
+    edge.ship && edge.owner == Some(player_id)
+}
+
+/// Junto al pirata no se ponen ni se mueven barcos.
+fn blocked_by_pirate(board: &Board, edge_id: EdgeId) -> bool {
+    edge_tiles(board, edge_id).iter().any(|&t| board.tiles[t].has_pirate)
+}
+
+/// Un barco sale de una casa propia o sigue a otro barco propio
+/// (salvo que en el medio haya una casa ajena).
+fn is_ship_connectable(board: &Board, player_id: PlayerType, edge_id: EdgeId) -> bool {
+    let (v1, v2) = board.edges[edge_id].vertices;
+    [v1, v2].into_iter().any(|v| match board.vertices[v].owner {
+        Some(owner) => owner == player_id,
+        None => board.vertices[v]
+            .adjacent_edges
+            .iter()
+            .any(|&e| e != edge_id && is_ship_owned_by(board, player_id, e)),
+    })
+}
+
+/// ¿Este extremo del barco queda suelto (sin casa propia ni otro barco propio)?
+fn is_loose_end(board: &Board, player_id: PlayerType, edge_id: EdgeId, vertex_id: VertexId) -> bool {
+    board.vertices[vertex_id].owner != Some(player_id)
+        && !board.vertices[vertex_id]
+            .adjacent_edges
+            .iter()
+            .any(|&e| e != edge_id && is_ship_owned_by(board, player_id, e))
+}
+
+/// Una ruta de barcos está cerrada si une dos casas propias.
+fn is_route_closed(board: &Board, player_id: PlayerType, edge_id: EdgeId) -> bool {
+    let mut seen = vec![edge_id];
+    let mut pending = vec![edge_id];
+    let mut towns = Vec::new();
+    while let Some(e) = pending.pop() {
+        let (v1, v2) = board.edges[e].vertices;
+        for v in [v1, v2] {
+            if board.vertices[v].owner == Some(player_id) {
+                if !towns.contains(&v) {
+                    towns.push(v);
+                }
+                continue;
+            }
+            if board.vertices[v].owner.is_some() {
+                continue;
+            }
+            for &next in &board.vertices[v].adjacent_edges {
+                if !seen.contains(&next) && is_ship_owned_by(board, player_id, next) {
+                    seen.push(next);
+                    pending.push(next);
+                }
+            }
+        }
+    }
+    towns.len() >= 2
+}
+
+// --- BARCOS ---
+
+pub fn place_ship(
+    board: &mut Board,
+    player_id: PlayerType,
+    edge_id: EdgeId,
+    free: bool,
+) -> Result<Option<PlayerType>, &'static str> {
+    if edge_id >= board.edges.len() {
+        return Err("Posición inválida: El borde no existe.");
+    }
+    let player_index = match board.players.iter().position(|p| p.id == player_id) {
+        Some(index) => index,
+        None => return Err("Error: No se encontró al jugador."),
+    };
+    if board.players[player_index].ship_quantity == 0 {
+        return Err("No se puede construir: No tienes más barcos disponibles.");
+    }
+    if board.edges[edge_id].owner.is_some() {
+        return Err("No se puede construir: El borde ya está ocupado.");
+    }
+    if !is_sea_edge(board, edge_id) {
+        return Err("No se puede construir: Los barcos van sobre el mar.");
+    }
+    if blocked_by_pirate(board, edge_id) {
+        return Err("No se puede construir: El pirata bloquea ese borde.");
+    }
+    if !free && !has_resources(&board.players[player_index], SHIP_COST) {
+        return Err("No se puede construir: No tienes los recursos necesarios.");
+    }
+    if !is_ship_connectable(board, player_id, edge_id) {
+        return Err("No se puede construir: El barco no está conectado a tu ruta.");
+    }
+
+    board.edges[edge_id].owner = Some(player_id);
+    board.edges[edge_id].ship = true;
+
+    let player = &mut board.players[player_index];
+    player.ship_quantity -= 1;
+    player.new_ships.push(edge_id);
+    if !free {
+        spend_resources(player, SHIP_COST);
+    }
+    println!("¡Barco construido en {}! A {:?} le quedan {} barcos.", edge_id, player.id, player.ship_quantity);
+    Ok(update_longest_road(board, player_id))
+}
+
+/// Barcos que `player_id` puede mover este turno: el último de una ruta
+/// abierta, que no haya puesto este turno y que no esté junto al pirata.
+pub fn movable_ships(board: &Board, player_id: PlayerType) -> Vec<EdgeId> {
+    let Some(player) = board.players.iter().find(|p| p.id == player_id) else {
+        return Vec::new();
+    };
+    if player.moved_ship_this_turn {
+        return Vec::new();
+    }
+    (0..board.edges.len())
+        .filter(|&e| is_ship_owned_by(board, player_id, e))
+        .filter(|e| !player.new_ships.contains(e))
+        .filter(|&e| !blocked_by_pirate(board, e))
+        .filter(|&e| {
+            let (v1, v2) = board.edges[e].vertices;
+            is_loose_end(board, player_id, e, v1) || is_loose_end(board, player_id, e, v2)
+        })
+        .filter(|&e| !is_route_closed(board, player_id, e))
+        .collect()
+}
+
+/// Mueve un barco de `from` a `to` (una vez por turno). Si no se puede, nada cambia.
+pub fn move_ship(
+    board: &mut Board,
+    player_id: PlayerType,
+    from: EdgeId,
+    to: EdgeId,
+) -> Result<Option<PlayerType>, &'static str> {
+    if from >= board.edges.len() || to >= board.edges.len() {
+        return Err("Posición inválida: El borde no existe.");
+    }
+    if !is_ship_owned_by(board, player_id, from) {
+        return Err("No se puede mover: No tienes un barco en ese borde.");
+    }
+    if from == to {
+        return Err("No se puede mover: El barco ya está ahí.");
+    }
+    if !movable_ships(board, player_id).contains(&from) {
+        return Err("No se puede mover: Sólo se mueve una vez por turno el último barco de una ruta abierta.");
+    }
+
+    let before = board.clone();
+    board.edges[from].owner = None;
+    board.edges[from].ship = false;
+    if let Some(player) = board.players.iter_mut().find(|p| p.id == player_id) {
+        player.ship_quantity += 1;
+    }
+    match place_ship(board, player_id, to, true) {
+        Ok(_) => {
+            if let Some(player) = board.players.iter_mut().find(|p| p.id == player_id) {
+                player.moved_ship_this_turn = true;
+                // el barco movido no cuenta como nuevo
+                player.new_ships.retain(|&e| e != to);
+            }
+            Ok(check_for_winner(board))
+        }
+        Err(msg) => {
+            *board = before;
+            Err(msg)
+        }
+    }
+}
+
+/// Al empezar el turno se puede volver a mover y los barcos nuevos dejan de serlo.
+pub fn reset_ship_turn(player: &mut Player) {
+    player.moved_ship_this_turn = false;
+    player.new_ships.clear();
+}
+
+// --- PIRATA ---
+
+pub fn pirate_tile(board: &Board) -> Option<TileId> {
+    board.tiles.iter().position(|t| t.has_pirate)
+}
+
+pub fn move_pirate(board: &mut Board, tile_id: TileId) -> Result<(), &'static str> {
+    if tile_id >= board.tiles.len() {
+        return Err("Posición inválida: La casilla no existe.");
+    }
+    if !is_sea(board, tile_id) {
+        return Err("No se puede mover: El pirata va sobre el mar.");
+    }
+    if board.tiles[tile_id].has_pirate {
+        return Err("No se puede mover: Debes mover el pirata a una *nueva* casilla.");
+    }
+    if let Some(current) = pirate_tile(board) {
+        board.tiles[current].has_pirate = false;
+    }
+    board.tiles[tile_id].has_pirate = true;
+    println!("Pirata movido a la casilla {}.", tile_id);
+    Ok(())
+}
+
+/// Jugadores con barcos alrededor de la casilla: a ellos roba el pirata.
+pub fn players_with_ships_on_tile(board: &Board, tile_id: TileId) -> Vec<PlayerType> {
+    let mut players = Vec::new();
+    for edge_id in tile_edges(board, tile_id) {
+        let edge = &board.edges[edge_id];
+        if let (true, Some(owner)) = (edge.ship, edge.owner) {
+            if !players.contains(&owner) {
+                players.push(owner);
+            }
+        }
+    }
+    players
+}
+
+// --- ORO ---
+
+/// Cuántas cartas a elección le tocan a cada uno por los campos de oro de la tirada.
+pub fn gold_owed(payouts: &HashMap<PlayerType, HashMap<MaterialType, u8>>) -> Vec<(PlayerType, u8)> {
+    let mut owed: Vec<(PlayerType, u8)> = payouts
+        .iter()
+        .filter_map(|(&p, gains)| gains.get(&MaterialType::Gold).map(|&n| (p, n)))
+        .collect();
+    owed.sort_by_key(|&(p, _)| p as u8);
+    owed
+}
+
+/// Entrega las cartas elegidas por el oro.
+pub fn take_gold(board: &mut Board, player_id: PlayerType, materials: &[MaterialType]) -> Result<(), &'static str> {
+    if materials.iter().any(|m| !m.is_resource()) {
+        return Err("El oro se cambia por recursos: madera, ladrillo, oveja, trigo o piedra.");
+    }
+    let player = board
+        .players
+        .iter_mut()
+        .find(|p| p.id == player_id)
+        .ok_or("Error: No se encontró al jugador.")?;
+    for &material in materials {
+        *player.resources.entry(material).or_insert(0) += 1;
+    }
+    println!("{:?} cambia su oro por {:?}.", player_id, materials);
+    Ok(())
+}
+
+// --- ISLAS ---
+
+/// Isla de cada casilla: la casilla de tierra de menor id de su grupo (`None` en el mar).
+pub fn islands(board: &Board) -> Vec<Option<TileId>> {
+    let mut island: Vec<Option<TileId>> = vec![None; board.tiles.len()];
+    for start in 0..board.tiles.len() {
+        if is_sea(board, start) || island[start].is_some() {
+            continue;
+        }
+        island[start] = Some(start);
+        let mut pending = vec![start];
+        while let Some(t) = pending.pop() {
+            for &v in &board.tiles[t].vertices {
+                for &n in &board.vertices[v].adjacent_tiles {
+                    if island[n].is_none() && !is_sea(board, n) {
+                        island[n] = Some(start);
+                        pending.push(n);
+                    }
+                }
+            }
+        }
+    }
+    island
+}
+
+pub fn island_of_vertex(board: &Board, vertex_id: VertexId) -> Option<TileId> {
+    let island = islands(board);
+    board.vertices[vertex_id].adjacent_tiles.iter().find_map(|&t| island[t])
+}
+
+/// Anota la isla de una casa nueva. En la fundación es isla de origen;
+/// después, la primera casa en otra isla da los puntos de las reglas.
+/// Devuelve los puntos ganados.
+pub fn record_island(board: &mut Board, player_id: PlayerType, vertex_id: VertexId, setup: bool) -> u8 {
+    let Some(island) = island_of_vertex(board, vertex_id) else {
+        return 0;
+    };
+    let bonus = board.rules.island_bonus;
+    let Some(player) = board.players.iter_mut().find(|p| p.id == player_id) else {
+        return 0;
+    };
+    if setup {
+        if !player.home_islands.contains(&island) {
+            player.home_islands.push(island);
+        }
+        return 0;
+    }
+    if bonus == 0 || player.home_islands.contains(&island) || player.bonus_islands.contains(&island) {
+        return 0;
+    }
+    player.bonus_islands.push(island);
+    player.victory_points += bonus;
+    println!("¡{:?} llega a una isla nueva y gana {} puntos!", player_id, bonus);
+    bonus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setup::{add_player, board_from_hexes};
+
+    /// Dos islas de una casilla con dos de mar en el medio, en fila:
+    /// madera (0), mar (1), mar (2), trigo (3). Juegan dos.
+    fn strait() -> Board {
+        let mut board = board_from_hexes(&[
+            (0, 0, MaterialType::Wood, 6),
+            (1, 0, MaterialType::Sea, 0),
+            (2, 0, MaterialType::Sea, 0),
+            (3, 0, MaterialType::Wheat, 8),
+        ]);
+        add_player(&mut board);
+        add_player(&mut board);
+        board
+    }
+
+    /// Esquina `i` de la casilla, desde arriba y en sentido horario.
+    fn corner(board: &Board, tile_id: TileId, i: usize) -> VertexId {
+        board.tiles[tile_id].vertices[i]
+    }
+
+    fn edge(board: &Board, a: VertexId, b: VertexId) -> EdgeId {
+        board
+            .edges
+            .iter()
+            .position(|e| e.vertices == (a.min(b), a.max(b)))
+            .unwrap()
+    }
+
+    /// Casa de `player_id` en la esquina `i` de la casilla.
+    fn settle(board: &mut Board, player_id: PlayerType, tile_id: TileId, i: usize) {
+        let vertex_id = corner(board, tile_id, i);
+        board.vertices[vertex_id].owner = Some(player_id);
+        board.vertices[vertex_id].building = Some(BuildingType::Settlement);
+    }
+
+    /// Los cuatro bordes del norte del estrecho, de la isla de madera a la de trigo.
+    fn northern_route(board: &Board) -> [EdgeId; 4] {
+        [
+            edge(board, corner(board, 1, 5), corner(board, 1, 0)),
+            edge(board, corner(board, 1, 0), corner(board, 1, 1)),
+            edge(board, corner(board, 2, 5), corner(board, 2, 0)),
+            edge(board, corner(board, 2, 0), corner(board, 2, 1)),
+        ]
+    }
+
+    fn player(board: &mut Board, player_id: PlayerType) -> &mut Player {
+        board.players.iter_mut().find(|p| p.id == player_id).unwrap()
+    }
+
+    #[test]
+    fn ships_start_at_a_house_and_follow_ships() {
+        let mut board = strait();
+        let me = PlayerType::Player1;
+        let route = northern_route(&board);
+        settle(&mut board, me, 0, 1);
+
+        let inland = edge(&board, corner(&board, 0, 4), corner(&board, 0, 5));
+        assert!(place_ship(&mut board, me, inland, true).is_err());
+        assert!(place_ship(&mut board, me, route[1], true).is_err());
+        assert!(place_ship(&mut board, me, route[0], true).is_ok());
+        assert!(place_ship(&mut board, me, route[1], true).is_ok());
+        assert!(is_ship_owned_by(&board, me, route[1]));
+
+        // una casa ajena corta la ruta
+        settle(&mut board, PlayerType::Player2, 2, 0);
+        assert!(place_ship(&mut board, me, route[2], true).is_ok());
+        assert!(place_ship(&mut board, me, route[3], true).is_err());
+    }
+
+    #[test]
+    fn only_the_loose_end_of_an_open_route_moves() {
+        let mut board = strait();
+        let me = PlayerType::Player1;
+        let route = northern_route(&board);
+        settle(&mut board, me, 0, 1);
+        place_ship(&mut board, me, route[0], true).unwrap();
+        place_ship(&mut board, me, route[1], true).unwrap();
+
+        // los barcos de este turno no se mueven
+        assert!(movable_ships(&board, me).is_empty());
+        reset_ship_turn(player(&mut board, me));
+        assert_eq!(movable_ships(&board, me), vec![route[1]]);
+
+        // junto al pirata, tampoco
+        board.tiles[1].has_pirate = true;
+        assert!(movable_ships(&board, me).is_empty());
+        board.tiles[1].has_pirate = false;
+
+        // una ruta que une dos casas propias queda fija
+        place_ship(&mut board, me, route[2], true).unwrap();
+        place_ship(&mut board, me, route[3], true).unwrap();
+        settle(&mut board, me, 3, 5);
+        reset_ship_turn(player(&mut board, me));
+        assert!(movable_ships(&board, me).is_empty());
+    }
+
+    #[test]
+    fn failed_move_leaves_the_ship_in_place() {
+        let mut board = strait();
+        let me = PlayerType::Player1;
+        let route = northern_route(&board);
+        settle(&mut board, me, 0, 1);
+        place_ship(&mut board, me, route[0], true).unwrap();
+        place_ship(&mut board, me, route[1], true).unwrap();
+        reset_ship_turn(player(&mut board, me));
+        let before = board.clone();
+
+        // sin el barco de `route[1]`, `route[3]` queda suelto en el mar
+        assert!(move_ship(&mut board, me, route[1], route[3]).is_err());
+        assert_eq!(board, before);
+
+        let coast = edge(&board, corner(&board, 0, 1), corner(&board, 0, 2));
+        assert!(move_ship(&mut board, me, route[1], coast).is_ok());
+        assert!(is_ship_owned_by(&board, me, coast));
+        assert_eq!(board.edges[route[1]].owner, None);
+        assert_eq!(player(&mut board, me).ship_quantity, before.players[0].ship_quantity);
+        // una sola vez por turno
+        assert!(movable_ships(&board, me).is_empty());
+    }
+
+    #[test]
+    fn each_land_group_is_its_own_island() {
+        let board = strait();
+        assert_eq!(islands(&board), vec![Some(0), None, None, Some(3)]);
+        assert_eq!(island_of_vertex(&board, corner(&board, 0, 1)), Some(0));
+        assert_eq!(island_of_vertex(&board, corner(&board, 1, 0)), None);
+    }
+
+    #[test]
+    fn first_house_on_a_new_island_scores_once() {
+        let mut board = strait();
+        board.rules.island_bonus = 2;
+        let me = PlayerType::Player1;
+        let home = corner(&board, 0, 1);
+        let away = corner(&board, 3, 5);
+
+        assert_eq!(record_island(&mut board, me, home, true), 0);
+        assert_eq!(record_island(&mut board, me, home, false), 0);
+        let sea = corner(&board, 1, 0);
+        assert_eq!(record_island(&mut board, me, sea, false), 0);
+        assert_eq!(record_island(&mut board, me, away, false), 2);
+        assert_eq!(record_island(&mut board, me, away, false), 0);
+        let p1 = player(&mut board, me);
+        assert_eq!((p1.home_islands.clone(), p1.bonus_islands.clone(), p1.victory_points), (vec![0], vec![3], 2));
+    }
+}
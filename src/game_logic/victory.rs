@@ -1,12 +1,20 @@
 // en src/game_logic/victory.rs
 
 use crate::types::*;
+use super::hooks::scenario_points;
 use std::collections::HashSet;
 
 // Hacemos públicas las funciones que otros módulos necesitarán
 pub fn check_for_winner(board: &Board) -> Option<PlayerType> {
+    check_for_winner_with_target(board, board.rules.vp_target)
+}
+
+/// Como `check_for_winner`, pero con otra meta de puntos (partidas configuradas).
+/// Los escenarios enchufados pueden sumar o restar puntos a la cuenta.
+pub fn check_for_winner_with_target(board: &Board, target: u8) -> Option<PlayerType> {
     for player in &board.players {
-        if player.victory_points >= 10 {
+        let points = player.victory_points as i16 + scenario_points(board, player.id);
+        if points >= target as i16 {
             println!("¡JUEGO TERMINADO! ¡El ganador es {:?}!", player.id);
             return Some(player.id);
         }
@@ -19,7 +27,7 @@ pub fn update_largest_army(board: &mut Board, player_id: PlayerType) -> Option<P
     let player_index = board.players.iter().position(|p| p.id == player_id).unwrap();
     let knights_played = board.players[player_index].knights_played;
 
-    if knights_played >= 3 && knights_played > board.largest_army_size {
+    if knights_played >= board.rules.largest_army_min && knights_played > board.largest_army_size {
         
         if board.largest_army == Some(player_id) {
             board.largest_army_size = knights_played; 
@@ -48,18 +56,24 @@ pub fn update_largest_army(board: &mut Board, player_id: PlayerType) -> Option<P
 }
 
 // Esta función es una auxiliar privada, no necesita `pub`
+// `arrived_by_ship`: con qué pieza se llegó al vértice; caminos y barcos
+// sólo se encadenan pasando por una casa propia.
 fn find_path_length(
     board: &Board,
     player_id: PlayerType,
     current_vertex: VertexId,
+    arrived_by_ship: Option<bool>,
     visited_edges: &mut HashSet<EdgeId>
 ) -> u8 {
     
     let mut max_len = 0;
+    let own_town = board.vertices[current_vertex].owner == Some(player_id);
 
     for &edge_id in &board.vertices[current_vertex].adjacent_edges {
-        
-        if board.edges[edge_id].owner == Some(player_id) && !visited_edges.contains(&edge_id) {
+        let ship = board.edges[edge_id].ship;
+        let switches_piece = arrived_by_ship.is_some_and(|s| s != ship) && !own_town;
+
+        if board.edges[edge_id].owner == Some(player_id) && !visited_edges.contains(&edge_id) && !switches_piece {
             
             visited_edges.insert(edge_id); 
 
@@ -72,7 +86,7 @@ fn find_path_length(
             let current_len = if is_blocked {
                 1
             } else {
-                1 + find_path_length(board, player_id, next_vertex, visited_edges)
+                1 + find_path_length(board, player_id, next_vertex, Some(ship), visited_edges)
             };
 
             if current_len > max_len {
@@ -86,8 +100,8 @@ fn find_path_length(
     max_len
 }
 
-// Esta también es una auxiliar privada
-fn calculate_player_longest_road(board: &Board, player_id: PlayerType) -> u8 {
+/// Largo del camino continuo más largo de `player_id`.
+pub fn calculate_player_longest_road(board: &Board, player_id: PlayerType) -> u8 {
     let mut max_road = 0;
     let mut visited_edges: HashSet<EdgeId> = HashSet::new();
 
@@ -95,7 +109,7 @@ fn calculate_player_longest_road(board: &Board, player_id: PlayerType) -> u8 {
         if board.vertices[v_id].owner == Some(player_id) || 
            board.vertices[v_id].owner.is_none() {
             
-            let len = find_path_length(board, player_id, v_id, &mut visited_edges);
+            let len = find_path_length(board, player_id, v_id, None, &mut visited_edges);
             if len > max_road {
                 max_road = len;
             }
@@ -108,7 +122,7 @@ pub fn update_longest_road(board: &mut Board, player_id: PlayerType) -> Option<P
     
     let current_longest = calculate_player_longest_road(board, player_id);
 
-    if current_longest >= 5 && current_longest > board.longest_road_size {
+    if current_longest >= board.rules.longest_road_min && current_longest > board.longest_road_size {
         
         if board.longest_road == Some(player_id) {
             board.longest_road_size = current_longest;
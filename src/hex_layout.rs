@@ -0,0 +1,196 @@
+// src/hex_layout.rs
+//
+// Geometría del tablero para la ventana. Todo sale de un solo layout de
+// hex "pointy" (punta arriba): los centros de las casillas vienen de sus
+// coordenadas axiales y las esquinas de esos centros, así vértices y
+// bordes caen justo sobre las casillas. El tablero clásico no trae
+// coordenadas; se las damos con las filas de setup.rs.
+
+use bevy::math::Vec2;
+
+use crate::game_logic::edge_tiles;
+use crate::types::*;
+
+/// Filas del tablero clásico (de arriba a abajo), con los ids de setup.rs:
+/// el anillo de afuera (0-11) y el de adentro (12-17) en sentido horario.
+const CLASSIC_ROWS: [&[TileId]; 5] = [
+    &[11, 0, 1],
+    &[10, 12, 13, 2],
+    &[9, 17, 18, 14, 3],
+    &[8, 16, 15, 4],
+    &[7, 6, 5],
+];
+
+/// Hex con la punta arriba; `size` es el radio (centro a esquina).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HexLayout {
+    pub size: f32,
+}
+
+impl Default for HexLayout {
+    fn default() -> Self {
+        Self { size: 55.0 }
+    }
+}
+
+impl HexLayout {
+    /// Centro de la casilla (q, r). `r` crece hacia abajo, como en los mapas.
+    pub fn center(&self, q: i32, r: i32) -> Vec2 {
+        let width = 3f32.sqrt() * self.size;
+        Vec2::new(width * (q as f32 + r as f32 / 2.0), -1.5 * self.size * r as f32)
+    }
+
+    /// Esquina `i` de un hex: 0 arriba y después en sentido horario.
+    pub fn corner(&self, center: Vec2, i: usize) -> Vec2 {
+        let angle = (90.0 - 60.0 * i as f32).to_radians();
+        center + Vec2::new(angle.cos(), angle.sin()) * self.size
+    }
+
+    /// ¿El punto cae dentro del hex con centro `center`?
+    pub fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        let d = (point - center).abs();
+        d.x <= 3f32.sqrt() / 2.0 * self.size && d.y + d.x / 3f32.sqrt() <= self.size
+    }
+}
+
+/// Posiciones en pantalla de casillas y vértices, calculadas una vez por tablero.
+#[derive(Debug, Clone, Default)]
+pub struct BoardGeometry {
+    pub layout: HexLayout,
+    /// index = tile_id
+    pub tile_centers: Vec<Vec2>,
+    /// index = vertex_id
+    pub vertex_positions: Vec<Vec2>,
+}
+
+impl BoardGeometry {
+    pub fn new(board: &Board) -> Self {
+        let layout = HexLayout::default();
+        let tile_centers = tile_centers(board, layout);
+
+        let mut sums = vec![(Vec2::ZERO, 0.0_f32); board.vertices.len()];
+        for (tile_id, tile) in board.tiles.iter().enumerate() {
+            let corner_of = corner_order(board, layout, &tile_centers, tile_id);
+            for (i, &v) in tile.vertices.iter().enumerate() {
+                sums[v].0 += layout.corner(tile_centers[tile_id], corner_of(i));
+                sums[v].1 += 1.0;
+            }
+        }
+        let vertex_positions = sums
+            .into_iter()
+            .map(|(sum, count)| if count > 0.0 { sum / count } else { Vec2::ZERO })
+            .collect();
+
+        Self { layout, tile_centers, vertex_positions }
+    }
+
+    pub fn vertex(&self, vertex_id: VertexId) -> Vec2 {
+        self.vertex_positions[vertex_id]
+    }
+
+    /// Los dos extremos del borde.
+    pub fn edge_ends(&self, board: &Board, edge_id: EdgeId) -> (Vec2, Vec2) {
+        let (v1, v2) = board.edges[edge_id].vertices;
+        (self.vertex(v1), self.vertex(v2))
+    }
+
+    pub fn edge_midpoint(&self, board: &Board, edge_id: EdgeId) -> Vec2 {
+        let (p1, p2) = self.edge_ends(board, edge_id);
+        (p1 + p2) / 2.0
+    }
+
+    /// El vértice más cercano a `point`, si está a menos de `max`.
+    pub fn vertex_at(&self, point: Vec2, max: f32) -> Option<VertexId> {
+        self.vertex_positions
+            .iter()
+            .enumerate()
+            .map(|(id, v)| (id, v.distance(point)))
+            .filter(|&(_, d)| d < max)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// El borde cuyo segmento queda más cerca de `point`, si está a menos de `max`.
+    pub fn edge_at(&self, board: &Board, point: Vec2, max: f32) -> Option<EdgeId> {
+        (0..board.edges.len())
+            .map(|id| {
+                let (a, b) = self.edge_ends(board, id);
+                (id, distance_to_segment(point, a, b))
+            })
+            .filter(|&(_, d)| d < max)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// La casilla que tiene a `point` adentro.
+    pub fn tile_at(&self, point: Vec2) -> Option<TileId> {
+        self.tile_centers.iter().position(|&c| self.layout.contains(c, point))
+    }
+
+    /// Hacia dónde queda el mar desde un borde de la costa (unitario).
+    /// `None` si el borde tiene casillas de los dos lados.
+    pub fn outward(&self, board: &Board, edge_id: EdgeId) -> Option<Vec2> {
+        let tiles = edge_tiles(board, edge_id);
+        let &[tile] = tiles.as_slice() else {
+            return None;
+        };
+        Some((self.edge_midpoint(board, edge_id) - self.tile_centers[tile]).normalize_or_zero())
+    }
+}
+
+// --- FUNCIONES AUXILIARES ---
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+/// Centros de las casillas, con el mapa centrado en el origen.
+fn tile_centers(board: &Board, layout: HexLayout) -> Vec<Vec2> {
+    let mut coords: Vec<(i32, i32)> = board.tiles.iter().map(|t| t.coord.unwrap_or((0, 0))).collect();
+    if board.tiles.iter().any(|t| t.coord.is_none()) {
+        for (row, ids) in CLASSIC_ROWS.iter().enumerate() {
+            let r = row as i32 - 2;
+            let first_q = (-2 - r).max(-2);
+            for (col, &tile_id) in ids.iter().enumerate() {
+                if let Some(coord) = coords.get_mut(tile_id) {
+                    *coord = (first_q + col as i32, r);
+                }
+            }
+        }
+    }
+
+    let centers: Vec<Vec2> = coords.iter().map(|&(q, r)| layout.center(q, r)).collect();
+    if centers.is_empty() {
+        return centers;
+    }
+    let mid = centers.iter().copied().sum::<Vec2>() / centers.len() as f32;
+    centers.into_iter().map(|c| c - mid).collect()
+}
+
+/// A qué esquina del hex va cada vértice de la casilla. Las listas de
+/// setup.rs dan la vuelta al hex pero no siempre arrancan en la misma
+/// esquina, así que probamos los 6 giros (y los dos sentidos) y nos
+/// quedamos con el que deja los vértices compartidos a un radio de los
+/// centros de las casillas vecinas.
+fn corner_order(board: &Board, layout: HexLayout, centers: &[Vec2], tile_id: TileId) -> impl Fn(usize) -> usize {
+    let tile = &board.tiles[tile_id];
+    let mut best = (0, 1, f32::MAX);
+    for step in [1, 5] {
+        for start in 0..6 {
+            let mut error = 0.0;
+            for (i, &v) in tile.vertices.iter().enumerate() {
+                let corner = layout.corner(centers[tile_id], (start + step * i) % 6);
+                for &other in board.vertices[v].adjacent_tiles.iter().filter(|&&t| t != tile_id) {
+                    error += (corner.distance(centers[other]) - layout.size).abs();
+                }
+            }
+            if error < best.2 {
+                best = (start, step, error);
+            }
+        }
+    }
+    let (start, step, _) = best;
+    move |i| (start + step * i) % 6
+}
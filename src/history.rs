@@ -0,0 +1,155 @@
+// src/history.rs
+//
+// Deshacer y rehacer para las partidas locales (consola y Bevy sin red).
+// Antes de cada acción se guarda una copia del estado. Cuando la acción
+// revela información oculta (dados, carta sacada del mazo, robo) la
+// historia se sella: no se puede volver a antes de ese punto.
+
+use crate::types::*;
+
+/// Pilas de estados para deshacer y rehacer dentro de un turno.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        History { undo: Vec::new(), redo: Vec::new() }
+    }
+}
+
+impl<T> History<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guarda el estado de antes de una acción. Lo que se podía rehacer se pierde.
+    pub fn record(&mut self, before: T) {
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// Vuelve `current` al estado anterior. Devuelve `false` si no hay nada que deshacer.
+    pub fn undo(&mut self, current: &mut T) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(current, previous));
+        true
+    }
+
+    /// Rehace lo último que se deshizo. Devuelve `false` si no hay nada.
+    pub fn redo(&mut self, current: &mut T) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(current, next));
+        true
+    }
+
+    /// Se reveló información oculta: de acá para atrás ya no se puede deshacer.
+    pub fn seal(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+/// ¿La acción de `player` que llevó de `before` a `after` mostró algo oculto?
+/// Sacar una carta del mazo o tocar la mano de otro (robo, monopolio) cuenta.
+pub fn reveals_hidden_info(before: &Board, after: &Board, player: PlayerType) -> bool {
+    if after.development_cards.len() < before.development_cards.len() {
+        return true;
+    }
+    before.players.iter().filter(|p| p.id != player).any(|p| {
+        after
+            .players
+            .iter()
+            .find(|q| q.id == p.id)
+            .is_some_and(|q| q.resources != p.resources)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Game;
+
+    #[test]
+    fn undo_and_redo_walk_the_recorded_states() {
+        let mut history = History::new();
+        let mut state = 1;
+        history.record(state);
+        state = 2;
+        history.record(state);
+        state = 3;
+
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 2);
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 1);
+        assert!(!history.undo(&mut state));
+
+        assert!(history.redo(&mut state));
+        assert_eq!(state, 2);
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn a_new_action_drops_the_redo() {
+        let mut history = History::new();
+        let mut state = 1;
+        history.record(state);
+        state = 2;
+        assert!(history.undo(&mut state));
+        assert_eq!(state, 1);
+
+        history.record(state);
+        assert!(!history.can_redo());
+        assert!(!history.redo(&mut state));
+    }
+
+    #[test]
+    fn seal_forgets_everything() {
+        let mut history = History::new();
+        let mut state = 1;
+        history.record(state);
+        state = 2;
+        history.record(state);
+        assert!(history.undo(&mut state));
+
+        history.seal();
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+        assert!(!history.undo(&mut state));
+        assert_eq!(state, 2);
+    }
+
+    #[test]
+    fn drawing_a_card_or_touching_a_rival_hand_reveals_info() {
+        let before = Game::new(3).board;
+        let me = PlayerType::Player1;
+
+        // lo propio no revela nada
+        let mut after = before.clone();
+        after.players[0].resources.insert(MaterialType::Wood, 5);
+        assert!(!reveals_hidden_info(&before, &after, me));
+
+        let mut after = before.clone();
+        after.development_cards.pop();
+        assert!(reveals_hidden_info(&before, &after, me));
+
+        let mut after = before.clone();
+        after.players[1].resources.insert(MaterialType::Wood, 5);
+        assert!(reveals_hidden_info(&before, &after, me));
+    }
+}
@@ -0,0 +1,26 @@
+// src/lib.rs
+//
+// Toda la lógica del juego vive en la librería para que la compartan
+// el binario principal (Bevy / consola) y el servidor de red.
+
+pub mod setup;
+pub mod scenarios;
+pub mod types;
+pub mod rules;
+pub mod dice;
+pub mod game_logic;
+pub mod development_cards;
+pub mod stats;
+pub mod history;
+pub mod engine;
+pub mod net;
+pub mod terminal_game;
+pub mod terminal_client;
+pub mod hex_layout;
+pub mod visual_game;
+pub mod visual_net;
+pub mod visual_turns;
+pub mod visual_hud;
+pub mod visual_trade;
+pub mod visual_fx;
+pub mod visual_camera;
@@ -1,24 +1,179 @@
-mod setup;           // tu setup.rs
-mod types;
-mod game_logic;
-mod development_cards;
-mod visual_game;     // el que te dejo abajo
-
 use bevy::prelude::*;
-use visual_game::VisualGamePlugin;
+use bevy_hexx_demo::dice::DiceMode;
+use bevy_hexx_demo::engine::ClockConfig;
+use bevy_hexx_demo::net::SpectatorMode;
+use bevy_hexx_demo::rules::RuleSet;
+use bevy_hexx_demo::scenarios::Scenario;
+use bevy_hexx_demo::setup::{self, MapLayout};
+use bevy_hexx_demo::terminal_client::{self, JoinMode};
+use bevy_hexx_demo::terminal_game;
+use bevy_hexx_demo::types::{PlayerType, ScenarioPlugin};
+use bevy_hexx_demo::visual_game::{VisualGamePlugin, DEFAULT_LOCAL_PLAYERS};
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // `cargo run -- --connect host:port [1-4 | sala]` juega en consola contra un servidor;
+    // con `--token XXXX` vuelve al asiento de una partida ya empezada
+    if let Some(i) = args.iter().position(|arg| arg == "--connect") {
+        let addr = args.get(i + 1).map(String::as_str).unwrap_or("127.0.0.1:7878");
+        let token = args
+            .iter()
+            .position(|arg| arg == "--token")
+            .and_then(|t| args.get(t + 1));
+        let entry = match (token, args.get(i + 2).map(String::as_str)) {
+            (Some(token), _) => JoinMode::Reconnect(token.clone()),
+            (None, Some("sala")) => JoinMode::Lobby,
+            (None, seat) => JoinMode::Quick(seat.and_then(parse_seat)),
+        };
+        terminal_client::run_remote_game(addr, entry);
+        return;
+    }
+
+    // `cargo run -- --spectate host:port [partida] [--omniscient [turnos de retraso]]` mira sin jugar
+    if let Some(i) = args.iter().position(|arg| arg == "--spectate") {
+        let addr = args.get(i + 1).map(String::as_str).unwrap_or("127.0.0.1:7878");
+        let game_id = args.get(i + 2).and_then(|s| s.parse().ok());
+        let mode = match args.iter().position(|arg| arg == "--omniscient") {
+            Some(o) => SpectatorMode::Omniscient {
+                delay_turns: args.get(o + 1).and_then(|n| n.parse().ok()).unwrap_or(0),
+            },
+            None => SpectatorMode::Public,
+        };
+        terminal_client::run_remote_game(addr, JoinMode::Spectate(game_id, mode));
+        return;
+    }
+
+    // `cargo run -- --terminal` juega en consola en vez de abrir la ventana;
+    // `--turn-secs N`, `--game-mins N` y `--decision-secs N` ponen relojes
+    // y `--rules reglas.json` cambia las reglas; `--map nuevas|islas` juega un escenario de Navegantes
+    // y `--ciudades` suma Ciudades y Caballeros; `--escenario pescadores|rios` enchufa un escenario;
+    // `--dados mazo|equilibrados|fisicos` cambia de dónde salen los dados
+    if args.iter().any(|arg| arg == "--terminal") {
+        let layout = parse_map(&args);
+        let mut board = setup::setup_board_with_layout(layout);
+        let Some(mut rules) = parse_rules(&args) else {
+            return;
+        };
+        if args.iter().any(|arg| arg == "--ciudades") {
+            rules = rules.with_cities_and_knights();
+        }
+        for plugin in parse_plugins(&args) {
+            rules = rules.with_plugin(plugin);
+        }
+        if let Some(dice) = parse_dice(&args) {
+            rules.dice = dice;
+        }
+        setup::set_rules(&mut board, layout.adjust_rules(rules));
+        terminal_game::start_game_with_clocks(&mut board, parse_clocks(&args));
+        return;
+    }
+
+    // `cargo run -- --online` abre la ventana en modo red (pantalla de conexión);
+    // sin él, `--jugadores 2-4` elige cuántos juegan en la misma pantalla y
+    // `--rules`, `--dados` y los relojes de `--turn-secs` y compañía valen también en la ventana
+    let networked = args.iter().any(|arg| arg == "--online");
+    let Some(mut rules) = parse_rules(&args) else {
+        return;
+    };
+    if let Some(dice) = parse_dice(&args) {
+        rules.dice = dice;
+    }
+    let local_players = args
+        .iter()
+        .position(|arg| arg == "--jugadores")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|n| (2..=4).contains(n))
+        .unwrap_or(DEFAULT_LOCAL_PLAYERS);
+    let clocks = parse_clocks(&args);
+
     App::new()
         .add_plugins(
             DefaultPlugins.set(WindowPlugin {
                 primary_window: Some(Window {
                     title: "Catan en Bevy".to_string(),
                     resolution: (1280., 720.).into(),
+                    resize_constraints: WindowResizeConstraints { min_width: 800.0, min_height: 500.0, ..Default::default() },
                     ..Default::default()
                 }),
                 ..Default::default()
             })
         )
-        .add_plugins(VisualGamePlugin)
+        .add_plugins(VisualGamePlugin { networked, local_players, rules, clocks })
         .run();
 }
+
+fn parse_clocks(args: &[String]) -> ClockConfig {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+            .and_then(|n| n.parse::<u32>().ok())
+            .filter(|&n| n > 0)
+    };
+    ClockConfig {
+        turn_secs: value("--turn-secs"),
+        game_secs: value("--game-mins").map(|m| m * 60),
+        decision_secs: value("--decision-secs"),
+    }
+}
+
+/// Las reglas de `--rules archivo.json`, o las normales. `None` si el archivo no sirve.
+fn parse_rules(args: &[String]) -> Option<RuleSet> {
+    let Some(path) = args.iter().position(|arg| arg == "--rules").and_then(|i| args.get(i + 1)) else {
+        return Some(RuleSet::default());
+    };
+    match RuleSet::load(path) {
+        Ok(rules) => Some(rules),
+        Err(e) => {
+            println!("No se pudieron leer las reglas de {}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn parse_map(args: &[String]) -> MapLayout {
+    let map = args.iter().position(|arg| arg == "--map").and_then(|i| args.get(i + 1));
+    match map.map(String::as_str) {
+        Some("fijo") => MapLayout::Fixed,
+        Some("nuevas") => MapLayout::Seafarers(Scenario::NewShores),
+        Some("islas") => MapLayout::Seafarers(Scenario::FourIslands),
+        _ => MapLayout::Random,
+    }
+}
+
+/// Escenarios de `--escenario pescadores|rios` (se puede repetir).
+fn parse_plugins(args: &[String]) -> Vec<ScenarioPlugin> {
+    args.windows(2)
+        .filter(|pair| pair[0] == "--escenario")
+        .filter_map(|pair| match pair[1].as_str() {
+            "pescadores" => Some(ScenarioPlugin::Fishermen),
+            "rios" | "ríos" => Some(ScenarioPlugin::Rivers),
+            other => {
+                println!("Escenario desconocido: {}", other);
+                None
+            }
+        })
+        .collect()
+}
+
+/// El modo de `--dados normales|mazo|equilibrados|fisicos`, si se pidió uno válido.
+fn parse_dice(args: &[String]) -> Option<DiceMode> {
+    let mode = args.iter().position(|arg| arg == "--dados").and_then(|i| args.get(i + 1))?;
+    let dice = DiceMode::parse(mode);
+    if dice.is_none() {
+        println!("Modo de dados desconocido: {}", mode);
+    }
+    dice
+}
+
+fn parse_seat(s: &str) -> Option<PlayerType> {
+    match s {
+        "1" => Some(PlayerType::Player1),
+        "2" => Some(PlayerType::Player2),
+        "3" => Some(PlayerType::Player3),
+        "4" => Some(PlayerType::Player4),
+        _ => None,
+    }
+}
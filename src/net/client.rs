@@ -0,0 +1,158 @@
+// en src/net/client.rs
+//
+// Lado cliente del protocolo: la conexión con el servidor y el estado
+// que el cliente reconstruye a partir de lo que recibe.
+
+use crate::engine::{Action, CardCounter, GameEvent, PlayerView};
+use crate::types::*;
+use super::lobby::{GameId, GameOptions, GameSummary, SpectatorMode};
+use super::protocol::{read_message, write_message, ClientMessage, ServerMessage};
+use std::io::{self, BufReader};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Conexión con el servidor. Un hilo lee los mensajes y los deja en `incoming`.
+pub struct ServerConnection {
+    writer: TcpStream,
+    pub incoming: Receiver<ServerMessage>,
+}
+
+impl ServerConnection {
+    pub fn connect(addr: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let writer = stream.try_clone()?;
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            // al cerrarse la conexión se suelta `tx` y el cliente se entera
+            while let Ok(Some(message)) = read_message::<_, ServerMessage>(&mut reader) {
+                if tx.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ServerConnection { writer, incoming: rx })
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        write_message(&mut self.writer, message)
+    }
+
+    pub fn join(&mut self, seat: Option<PlayerType>) -> io::Result<()> {
+        self.send(&ClientMessage::Join { seat })
+    }
+
+    pub fn list_games(&mut self) -> io::Result<()> {
+        self.send(&ClientMessage::ListGames)
+    }
+
+    pub fn create_game(&mut self, options: GameOptions) -> io::Result<()> {
+        self.send(&ClientMessage::CreateGame { options })
+    }
+
+    pub fn join_game(&mut self, game_id: GameId, seat: Option<PlayerType>) -> io::Result<()> {
+        self.send(&ClientMessage::JoinGame { game_id, seat })
+    }
+
+    pub fn set_ready(&mut self, ready: bool) -> io::Result<()> {
+        self.send(&ClientMessage::SetReady { ready })
+    }
+
+    pub fn reconnect(&mut self, token: &str) -> io::Result<()> {
+        self.send(&ClientMessage::Reconnect { token: token.to_string() })
+    }
+
+    pub fn spectate(&mut self, game_id: Option<GameId>, mode: SpectatorMode) -> io::Result<()> {
+        self.send(&ClientMessage::Spectate { game_id, mode })
+    }
+
+    pub fn send_action(&mut self, action: Action) -> io::Result<()> {
+        self.send(&ClientMessage::Action { action })
+    }
+}
+
+/// Lo que el cliente sabe de la partida.
+#[derive(Debug, Clone, Default)]
+pub struct ClientState {
+    pub seat: Option<PlayerType>,
+    pub game_id: Option<GameId>,
+    /// Para volver al asiento con `Reconnect` si se corta la conexión.
+    pub token: Option<String>,
+    /// `Some` si miramos la partida como espectador.
+    pub spectating: Option<SpectatorMode>,
+    pub snapshot: Option<PlayerView>,
+    /// Última lista de partidas pedida con `ListGames`.
+    pub games: Vec<GameSummary>,
+    /// Cómo está la sala de nuestra partida.
+    pub room: Option<GameSummary>,
+    /// Lo que se deduce de las manos rivales a partir de los eventos.
+    pub counter: Option<CardCounter>,
+    /// Cuándo llegó el último estado, para descontar de los relojes.
+    pub received_at: Option<Instant>,
+}
+
+impl ClientState {
+    /// Cuánto le queda ahora a quien tiene que decidir, según el último estado.
+    pub fn time_left(&self) -> Option<Duration> {
+        let left = self.snapshot.as_ref()?.time_left()?;
+        let since = self.received_at.map(|t| t.elapsed()).unwrap_or_default();
+        Some(left.saturating_sub(since))
+    }
+
+    /// Incorpora un mensaje del servidor. Devuelve los eventos que traía, si había.
+    pub fn apply(&mut self, message: ServerMessage) -> Vec<GameEvent> {
+        match message {
+            ServerMessage::Welcome { seat, game_id, token } => {
+                self.seat = Some(seat);
+                self.game_id = Some(game_id);
+                self.token = Some(token);
+                Vec::new()
+            }
+            ServerMessage::Spectating { game_id, mode } => {
+                self.game_id = Some(game_id);
+                self.spectating = Some(mode);
+                Vec::new()
+            }
+            ServerMessage::GameList { games } => {
+                self.games = games;
+                Vec::new()
+            }
+            ServerMessage::LobbyUpdate { game } => {
+                self.room = Some(game);
+                Vec::new()
+            }
+            ServerMessage::Snapshot { state } => {
+                match self.counter.as_mut() {
+                    Some(counter) => counter.sync(&state),
+                    None => self.counter = Some(CardCounter::from_view(&state)),
+                }
+                self.snapshot = Some(*state);
+                self.received_at = Some(Instant::now());
+                Vec::new()
+            }
+            ServerMessage::Diff { diff } => {
+                if let Some(snapshot) = self.snapshot.as_mut() {
+                    snapshot.apply_diff(*diff);
+                    if let Some(counter) = self.counter.as_mut() {
+                        counter.sync(snapshot);
+                    }
+                    self.received_at = Some(Instant::now());
+                }
+                Vec::new()
+            }
+            ServerMessage::Events { events } => {
+                if let Some(counter) = self.counter.as_mut() {
+                    for event in &events {
+                        counter.observe(event);
+                    }
+                }
+                events
+            }
+            ServerMessage::Error { .. } => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,828 @@
+// en src/net/lobby.rs
+//
+// Sala de espera del servidor: varias partidas a la vez, cada una con
+// su propia `Game`. Los clientes listan, crean y se unen a partidas,
+// marcan que están listos, y pueden volver a su asiento con un token.
+// También se puede mirar una partida como espectador, sin asiento.
+
+use crate::engine::{bot, Action, ClockConfig, Game, GameEvent, PlayerView};
+use crate::rules::RuleSet;
+use crate::setup::MapLayout;
+use crate::types::*;
+use super::protocol::{ClientMessage, ServerMessage};
+use super::server::spawn_writer;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::SyncSender;
+use std::time::Duration;
+
+pub type ClientId = usize;
+pub type GameId = u32;
+
+/// Cota de acciones seguidas de bots, por si alguno se traba.
+const MAX_BOT_ACTIONS: usize = 500;
+/// Mensajes que se le guardan a un cliente que no lee; pasado esto se lo desconecta.
+const OUTBOX_SIZE: usize = 256;
+
+// -----------------------------------------------------------------------------
+// OPCIONES Y RESÚMENES
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameOptions {
+    pub name: String,
+    /// Asientos totales (2-4), bots incluidos.
+    pub players: usize,
+    /// Cuántos de esos asientos (los últimos) juegan solos.
+    pub bots: usize,
+    pub map: MapLayout,
+    /// Puntos para ganar, descartes, banco y reglas de la casa.
+    pub rules: RuleSet,
+    /// Límites de tiempo; por defecto, ninguno.
+    pub clocks: ClockConfig,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        GameOptions {
+            name: "Partida".to_string(),
+            players: 2,
+            bots: 0,
+            map: MapLayout::Random,
+            rules: RuleSet::default(),
+            clocks: ClockConfig::default(),
+        }
+    }
+}
+
+/// Qué ve un espectador.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpectatorMode {
+    /// Lo mismo que cualquiera sin asiento: tamaños de mano, cartas sin revelar.
+    Public,
+    /// Todas las manos, pero `delay_turns` turnos atrás para que no sirva para hacer trampa.
+    Omniscient { delay_turns: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatSummary {
+    pub player: PlayerType,
+    pub taken: bool,
+    pub bot: bool,
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub id: GameId,
+    pub options: GameOptions,
+    pub seats: Vec<SeatSummary>,
+    pub started: bool,
+    pub finished: bool,
+}
+
+// -----------------------------------------------------------------------------
+// PARTIDAS
+// -----------------------------------------------------------------------------
+
+pub struct Seat {
+    pub player: PlayerType,
+    /// Cliente conectado ahora mismo en este asiento.
+    pub client: Option<ClientId>,
+    /// Token para volver al asiento si se corta la conexión.
+    pub token: Option<String>,
+    pub ready: bool,
+    pub bot: bool,
+}
+
+pub struct GameRoom {
+    pub id: GameId,
+    pub options: GameOptions,
+    pub game: Game,
+    pub seats: Vec<Seat>,
+    pub started: bool,
+    /// Estado después de cada cambio, para los espectadores con retraso. Sólo
+    /// se graba mientras hay alguno, y nada más que lo que todavía pueden ver.
+    timeline: Vec<TimelineEntry>,
+    /// Índice absoluto de `timeline[0]`: lo anterior ya se descartó.
+    timeline_start: usize,
+}
+
+struct TimelineEntry {
+    turn: u32,
+    game: Game,
+    events: Vec<GameEvent>,
+}
+
+impl GameRoom {
+    pub fn new(id: GameId, mut options: GameOptions) -> Self {
+        options.players = options.players.clamp(2, 4);
+        options.bots = options.bots.min(options.players - 1);
+
+        let game = Game::with_options(options.players, options.map, options.rules.clone()).with_clocks(options.clocks);
+        let first_bot = options.players - options.bots;
+        let seats = game
+            .turn_order
+            .iter()
+            .enumerate()
+            .map(|(i, &player)| Seat {
+                player,
+                client: None,
+                token: None,
+                ready: i >= first_bot,
+                bot: i >= first_bot,
+            })
+            .collect();
+
+        let timeline = vec![TimelineEntry { turn: game.turn_number, game: game.clone(), events: Vec::new() }];
+        GameRoom { id, options, game, seats, started: false, timeline, timeline_start: 0 }
+    }
+
+    pub fn summary(&self) -> GameSummary {
+        GameSummary {
+            id: self.id,
+            options: self.options.clone(),
+            seats: self
+                .seats
+                .iter()
+                .map(|s| SeatSummary {
+                    player: s.player,
+                    taken: s.bot || s.token.is_some(),
+                    bot: s.bot,
+                    ready: s.ready,
+                })
+                .collect(),
+            started: self.started,
+            finished: self.game.winner().is_some(),
+        }
+    }
+
+    fn seat_is_free(seat: &Seat) -> bool {
+        !seat.bot && seat.token.is_none()
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.started && self.seats.iter().any(Self::seat_is_free)
+    }
+
+    fn free_seat(&self, requested: Option<PlayerType>) -> Result<PlayerType, &'static str> {
+        match requested {
+            Some(player) => match self.seats.iter().find(|s| s.player == player) {
+                None => Err("Ese asiento no existe en esta partida."),
+                Some(seat) if !Self::seat_is_free(seat) => Err("Ese asiento ya está ocupado."),
+                Some(_) => Ok(player),
+            },
+            None => self
+                .seats
+                .iter()
+                .find(|s| Self::seat_is_free(s))
+                .map(|s| s.player)
+                .ok_or("La partida está llena."),
+        }
+    }
+
+    fn seat_mut(&mut self, player: PlayerType) -> Option<&mut Seat> {
+        self.seats.iter_mut().find(|s| s.player == player)
+    }
+
+    fn all_ready(&self) -> bool {
+        self.seats.iter().all(|s| s.bot || (s.client.is_some() && s.ready))
+    }
+
+    fn has_clients(&self) -> bool {
+        self.seats.iter().any(|s| s.client.is_some())
+    }
+
+    /// Guarda el estado actual si hay espectadores con retraso (`max_delay`);
+    /// si no hay ninguno, el `timeline` se vacía.
+    fn record(&mut self, events: &[GameEvent], max_delay: Option<u32>) {
+        if max_delay.is_none() {
+            self.timeline_start += self.timeline.len();
+            self.timeline.clear();
+            return;
+        }
+        self.timeline.push(TimelineEntry {
+            turn: self.game.turn_number,
+            game: self.game.clone(),
+            events: events.to_vec(),
+        });
+    }
+
+    /// Para un espectador con retraso recién llegado: si no se estaba
+    /// grabando, empieza desde ahora (y lo ve cuando pase su retraso).
+    fn start_recording(&mut self) {
+        if self.timeline.is_empty() {
+            self.record(&[], Some(0));
+        }
+    }
+
+    /// Descarta lo que ya vio hasta el espectador con más retraso.
+    fn prune_timeline(&mut self, max_delay: Option<u32>) {
+        let Some(delay) = max_delay else {
+            return;
+        };
+        let cutoff = self.game.turn_number.saturating_sub(delay);
+        let old = self.timeline.iter().take_while(|e| e.turn < cutoff).count();
+        self.timeline.drain(..old);
+        self.timeline_start += old;
+    }
+
+    /// Lo nuevo que le toca ver a `client` (eventos y estado), o `None` si todavía nada.
+    fn next_view(&self, client: &mut Client, events: &[GameEvent]) -> Option<(Vec<GameEvent>, PlayerView)> {
+        match client.spectator {
+            None | Some(SpectatorMode::Public) => {
+                let visible = events.iter().map(|e| e.visible_to(client.seat)).collect();
+                Some((visible, PlayerView::for_viewer(&self.game, client.seat)))
+            }
+            Some(SpectatorMode::Omniscient { delay_turns }) => {
+                let finished = self.game.winner().is_some();
+                let mut visible = Vec::new();
+                let mut latest = None;
+                let skip = client.timeline_pos.saturating_sub(self.timeline_start);
+                for (i, entry) in self.timeline.iter().enumerate().skip(skip) {
+                    let pos = self.timeline_start + i;
+                    // al terminar la partida se muestra todo lo que faltaba
+                    if pos > 0 && !finished && entry.turn + delay_turns > self.game.turn_number {
+                        break;
+                    }
+                    visible.extend(entry.events.iter().cloned());
+                    latest = Some(&entry.game);
+                    client.timeline_pos = pos + 1;
+                }
+                latest.map(|game| (visible, PlayerView::omniscient(game)))
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// SALA DE ESPERA
+// -----------------------------------------------------------------------------
+
+struct Client {
+    /// Sólo para cortar la conexión; lo que se manda pasa por `outbox`.
+    stream: TcpStream,
+    /// Cola del hilo que escribe en el socket, para no trabar la partida.
+    outbox: SyncSender<ServerMessage>,
+    room: Option<GameId>,
+    seat: Option<PlayerType>,
+    spectator: Option<SpectatorMode>,
+    /// Hasta dónde del `timeline` de la partida ya vio este espectador.
+    timeline_pos: usize,
+    /// Último estado enviado, para mandar sólo diferencias.
+    last_snapshot: Option<PlayerView>,
+}
+
+pub struct Lobby {
+    clients: HashMap<ClientId, Client>,
+    rooms: BTreeMap<GameId, GameRoom>,
+    next_game_id: GameId,
+    /// Opciones de las partidas que se crean con un `Join` rápido.
+    quick_game: GameOptions,
+}
+
+impl Lobby {
+    pub fn new(quick_game: GameOptions) -> Self {
+        Lobby {
+            clients: HashMap::new(),
+            rooms: BTreeMap::new(),
+            next_game_id: 1,
+            quick_game,
+        }
+    }
+
+    pub fn connect(&mut self, id: ClientId, stream: TcpStream) {
+        println!("Cliente {} conectado.", id);
+        let outbox = match stream.try_clone() {
+            Ok(writer) => spawn_writer(writer, OUTBOX_SIZE),
+            Err(e) => {
+                println!("Cliente {}: no se pudo preparar la conexión: {}", id, e);
+                let _ = stream.shutdown(Shutdown::Both);
+                return;
+            }
+        };
+        let client = Client {
+            stream,
+            outbox,
+            room: None,
+            seat: None,
+            spectator: None,
+            timeline_pos: 0,
+            last_snapshot: None,
+        };
+        self.clients.insert(id, client);
+    }
+
+    /// El cliente se fue; su asiento queda guardado para reconectarse con el token.
+    pub fn disconnect(&mut self, id: ClientId) {
+        let Some(client) = self.clients.remove(&id) else {
+            return;
+        };
+        // también destraba al hilo escritor si estaba esperando al socket
+        let _ = client.stream.shutdown(Shutdown::Both);
+        println!("Cliente {} desconectado (partida {:?}, asiento {:?}).", id, client.room, client.seat);
+
+        if let (Some(game_id), Some(player)) = (client.room, client.seat) {
+            if let Some(room) = self.rooms.get_mut(&game_id) {
+                let started = room.started;
+                if let Some(seat) = room.seat_mut(player) {
+                    seat.client = None;
+                    seat.ready = false;
+                    if !started {
+                        // antes de empezar, el asiento se libera del todo
+                        seat.token = None;
+                    }
+                }
+            }
+            self.send_lobby_update(game_id);
+            self.cleanup_room(game_id);
+        }
+    }
+
+    pub fn handle_message(&mut self, id: ClientId, message: ClientMessage) {
+        match message {
+            ClientMessage::Join { seat } => self.quick_join(id, seat),
+            ClientMessage::ListGames => self.list_games(id),
+            ClientMessage::CreateGame { options } => self.create_game(id, options),
+            ClientMessage::JoinGame { game_id, seat } => self.join_game(id, game_id, seat, false),
+            ClientMessage::Reconnect { token } => self.reconnect(id, &token),
+            ClientMessage::Spectate { game_id, mode } => self.spectate(id, game_id, mode),
+            ClientMessage::SetReady { ready } => self.set_ready(id, ready),
+            ClientMessage::LeaveGame => self.leave_game(id),
+            ClientMessage::Action { action } => self.handle_action(id, action),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // MENSAJES DE LA SALA
+    // -------------------------------------------------------------------------
+
+    fn list_games(&mut self, id: ClientId) {
+        let games = self.rooms.values().map(|r| r.summary()).collect();
+        self.send(id, ServerMessage::GameList { games });
+    }
+
+    fn create_game(&mut self, id: ClientId, options: GameOptions) {
+        if self.client_room(id).is_some() {
+            self.send_error(id, "Ya estás en una partida.");
+            return;
+        }
+        let game_id = self.next_game_id;
+        self.next_game_id += 1;
+        let room = GameRoom::new(game_id, options);
+        println!("Partida {} creada: {:?}", game_id, room.options);
+        self.rooms.insert(game_id, room);
+        self.join_game(id, game_id, None, false);
+    }
+
+    /// `Join` de antes de la sala: entra a la primera partida abierta (o crea una) y queda listo.
+    fn quick_join(&mut self, id: ClientId, seat: Option<PlayerType>) {
+        let open = self
+            .rooms
+            .values()
+            .find(|r| r.is_open() && r.free_seat(seat).is_ok())
+            .map(|r| r.id);
+        let game_id = match open {
+            Some(game_id) => game_id,
+            None => {
+                let game_id = self.next_game_id;
+                self.next_game_id += 1;
+                self.rooms.insert(game_id, GameRoom::new(game_id, self.quick_game.clone()));
+                game_id
+            }
+        };
+        self.join_game(id, game_id, seat, true);
+    }
+
+    fn join_game(&mut self, id: ClientId, game_id: GameId, requested: Option<PlayerType>, ready: bool) {
+        if self.client_room(id).is_some() {
+            self.send_error(id, "Ya estás en una partida.");
+            return;
+        }
+        let Some(room) = self.rooms.get_mut(&game_id) else {
+            self.send_error(id, "Esa partida no existe.");
+            return;
+        };
+        if room.started {
+            self.send_error(id, "La partida ya empezó.");
+            return;
+        }
+        let player = match room.free_seat(requested) {
+            Ok(player) => player,
+            Err(msg) => {
+                self.send_error(id, msg);
+                return;
+            }
+        };
+
+        let token = new_token();
+        if let Some(seat) = room.seat_mut(player) {
+            seat.client = Some(id);
+            seat.token = Some(token.clone());
+            seat.ready = ready;
+        }
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.room = Some(game_id);
+            client.seat = Some(player);
+            client.spectator = None;
+            client.last_snapshot = None;
+        }
+        println!("Cliente {} se sienta como {:?} en la partida {}.", id, player, game_id);
+
+        self.send(id, ServerMessage::Welcome { seat: player, game_id, token });
+        self.send_snapshot(id);
+        self.send_lobby_update(game_id);
+        self.maybe_start(game_id);
+    }
+
+    fn reconnect(&mut self, id: ClientId, token: &str) {
+        if self.client_room(id).is_some() {
+            self.send_error(id, "Ya estás en una partida.");
+            return;
+        }
+        let found = self.rooms.values().find_map(|room| {
+            room.seats
+                .iter()
+                .find(|s| s.token.as_deref() == Some(token))
+                .map(|s| (room.id, s.player, s.client))
+        });
+        let Some((game_id, player, previous)) = found else {
+            self.send_error(id, "Token desconocido.");
+            return;
+        };
+
+        // si la conexión vieja sigue colgada, la reemplazamos
+        if let Some(old) = previous {
+            if let Some(old_client) = self.clients.get_mut(&old) {
+                old_client.room = None;
+                old_client.seat = None;
+            }
+        }
+        if let Some(seat) = self.rooms.get_mut(&game_id).and_then(|r| r.seat_mut(player)) {
+            seat.client = Some(id);
+            seat.ready = true;
+        }
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.room = Some(game_id);
+            client.seat = Some(player);
+            client.spectator = None;
+            client.last_snapshot = None;
+        }
+        println!("Cliente {} vuelve como {:?} a la partida {}.", id, player, game_id);
+
+        self.send(id, ServerMessage::Welcome { seat: player, game_id, token: token.to_string() });
+        self.send_snapshot(id);
+        self.send_lobby_update(game_id);
+        self.maybe_start(game_id);
+    }
+
+    /// Mirar una partida sin asiento. Con `None`, la primera que ya empezó.
+    fn spectate(&mut self, id: ClientId, requested: Option<GameId>, mode: SpectatorMode) {
+        if self.client_room(id).is_some() {
+            self.send_error(id, "Ya estás en una partida.");
+            return;
+        }
+        let game_id = match requested {
+            Some(game_id) if self.rooms.contains_key(&game_id) => game_id,
+            Some(_) => {
+                self.send_error(id, "Esa partida no existe.");
+                return;
+            }
+            None => match self.rooms.values().find(|r| r.started && r.game.winner().is_none()) {
+                Some(room) => room.id,
+                None => {
+                    self.send_error(id, "No hay partidas en juego para mirar.");
+                    return;
+                }
+            },
+        };
+
+        let timeline_pos = match self.rooms.get_mut(&game_id) {
+            Some(room) => {
+                if let SpectatorMode::Omniscient { .. } = mode {
+                    room.start_recording();
+                }
+                room.timeline_start
+            }
+            None => 0,
+        };
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.room = Some(game_id);
+            client.seat = None;
+            client.spectator = Some(mode);
+            client.timeline_pos = timeline_pos;
+            client.last_snapshot = None;
+        }
+        println!("Cliente {} mira la partida {} ({:?}).", id, game_id, mode);
+
+        self.send(id, ServerMessage::Spectating { game_id, mode });
+        self.send_snapshot(id);
+        if let Some(game) = self.rooms.get(&game_id).map(|r| r.summary()) {
+            self.send(id, ServerMessage::LobbyUpdate { game });
+        }
+    }
+
+    fn set_ready(&mut self, id: ClientId, ready: bool) {
+        let Some((game_id, player)) = self.client_seat(id) else {
+            self.send_error(id, "No estás en ninguna partida.");
+            return;
+        };
+        if let Some(seat) = self.rooms.get_mut(&game_id).and_then(|r| r.seat_mut(player)) {
+            seat.ready = ready;
+        }
+        self.send_lobby_update(game_id);
+        self.maybe_start(game_id);
+    }
+
+    fn leave_game(&mut self, id: ClientId) {
+        if self.clients.get(&id).is_some_and(|c| c.spectator.is_some()) {
+            if let Some(client) = self.clients.get_mut(&id) {
+                client.room = None;
+                client.spectator = None;
+                client.last_snapshot = None;
+            }
+            self.list_games(id);
+            return;
+        }
+        let Some((game_id, player)) = self.client_seat(id) else {
+            self.send_error(id, "No estás en ninguna partida.");
+            return;
+        };
+        if let Some(room) = self.rooms.get_mut(&game_id) {
+            let started = room.started;
+            if let Some(seat) = room.seat_mut(player) {
+                seat.client = None;
+                seat.ready = false;
+                if !started {
+                    seat.token = None;
+                }
+            }
+        }
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.room = None;
+            client.seat = None;
+            client.last_snapshot = None;
+        }
+        self.send_lobby_update(game_id);
+        self.cleanup_room(game_id);
+        self.list_games(id);
+    }
+
+    // -------------------------------------------------------------------------
+    // JUEGO
+    // -------------------------------------------------------------------------
+
+    fn maybe_start(&mut self, game_id: GameId) {
+        let Some(room) = self.rooms.get_mut(&game_id) else {
+            return;
+        };
+        if room.started || !room.all_ready() {
+            return;
+        }
+        room.started = true;
+        println!("¡Empieza la partida {}!", game_id);
+        self.send_lobby_update(game_id);
+        let Some(room) = self.rooms.get(&game_id) else {
+            return;
+        };
+        let events = vec![GameEvent::GameStarted { turn_order: room.game.turn_order.clone() }];
+        self.broadcast_room(game_id, &events);
+        self.run_bots(game_id);
+    }
+
+    fn handle_action(&mut self, id: ClientId, action: Action) {
+        let Some((game_id, player)) = self.client_seat(id) else {
+            self.send_error(id, "Primero tienes que sentarte en una partida.");
+            return;
+        };
+        let Some(room) = self.rooms.get_mut(&game_id) else {
+            return;
+        };
+        if !room.started {
+            self.send_error(id, "La partida todavía no empezó.");
+            return;
+        }
+        match room.game.apply(player, action) {
+            Ok(events) => {
+                self.broadcast_room(game_id, &events);
+                self.run_bots(game_id);
+            }
+            Err(msg) => self.send_error(id, msg),
+        }
+    }
+
+    /// Pasa el tiempo en todas las partidas en juego; a quien se le acabó, juega el motor.
+    pub fn tick(&mut self, elapsed: Duration) {
+        let running: Vec<GameId> = self.rooms.values().filter(|r| r.started).map(|r| r.id).collect();
+        for game_id in running {
+            let Some(room) = self.rooms.get_mut(&game_id) else {
+                continue;
+            };
+            let events = room.game.advance_clock(elapsed);
+            if !events.is_empty() {
+                self.broadcast_room(game_id, &events);
+                self.run_bots(game_id);
+            }
+        }
+    }
+
+    /// Los bots juegan mientras les toque decidir algo.
+    fn run_bots(&mut self, game_id: GameId) {
+        // bots que no encontraron nada válido: se saltean hasta que algo cambie
+        let mut stuck = HashSet::new();
+        for _ in 0..MAX_BOT_ACTIONS {
+            let Some(room) = self.rooms.get_mut(&game_id) else {
+                return;
+            };
+            let next = room
+                .seats
+                .iter()
+                .filter(|s| s.bot && !stuck.contains(&s.player))
+                .find_map(|s| {
+                    let view = PlayerView::for_viewer(&room.game, Some(s.player));
+                    bot::choose_action(&view).map(|a| (s.player, a))
+                });
+            let Some((player, action)) = next else {
+                return;
+            };
+            // el bot no ve todo: si se equivocó, una jugada válida de la fase para no trabar la partida
+            let result = room.game.apply(player, action).or_else(|msg| {
+                println!("El bot {:?} no pudo jugar: {}", player, msg);
+                let fallback = room.game.fallback_action(player).ok_or(msg)?;
+                room.game.apply(player, fallback)
+            });
+            match result {
+                Ok(events) => {
+                    stuck.clear();
+                    self.broadcast_room(game_id, &events);
+                }
+                Err(_) => {
+                    stuck.insert(player);
+                }
+            }
+        }
+    }
+
+    /// Manda a cada cliente de la partida los eventos que puede ver y la diferencia de estado.
+    fn broadcast_room(&mut self, game_id: GameId, events: &[GameEvent]) {
+        let max_delay = self
+            .clients
+            .values()
+            .filter(|c| c.room == Some(game_id))
+            .filter_map(|c| match c.spectator {
+                Some(SpectatorMode::Omniscient { delay_turns }) => Some(delay_turns),
+                _ => None,
+            })
+            .max();
+        let Some(room) = self.rooms.get_mut(&game_id) else {
+            return;
+        };
+        room.record(events, max_delay);
+
+        let mut outgoing = Vec::new();
+        for (&id, client) in self.clients.iter_mut().filter(|(_, c)| c.room == Some(game_id)) {
+            let Some((visible, newer)) = room.next_view(client, events) else {
+                continue; // espectador con retraso: todavía no le toca ver nada nuevo
+            };
+            outgoing.push((id, ServerMessage::Events { events: visible }));
+
+            let message = match client.last_snapshot.as_ref() {
+                Some(older) => ServerMessage::Diff { diff: Box::new(older.diff(&newer)) },
+                None => ServerMessage::Snapshot { state: Box::new(newer.clone()) },
+            };
+            outgoing.push((id, message));
+            client.last_snapshot = Some(newer);
+        }
+        room.prune_timeline(max_delay);
+        for (id, message) in outgoing {
+            self.send(id, message);
+        }
+    }
+
+    fn send_snapshot(&mut self, id: ClientId) {
+        let Some(client) = self.clients.get_mut(&id) else {
+            return;
+        };
+        let Some(room) = client.room.and_then(|g| self.rooms.get(&g)) else {
+            return;
+        };
+        let Some((events, state)) = room.next_view(client, &[]) else {
+            return;
+        };
+        client.last_snapshot = Some(state.clone());
+        if !events.is_empty() {
+            self.send(id, ServerMessage::Events { events });
+        }
+        self.send(id, ServerMessage::Snapshot { state: Box::new(state) });
+    }
+
+    fn send_lobby_update(&mut self, game_id: GameId) {
+        let Some(game) = self.rooms.get(&game_id).map(|r| r.summary()) else {
+            return;
+        };
+        let members: Vec<ClientId> = self
+            .clients
+            .iter()
+            .filter(|(_, c)| c.room == Some(game_id))
+            .map(|(&id, _)| id)
+            .collect();
+        for id in members {
+            self.send(id, ServerMessage::LobbyUpdate { game: game.clone() });
+        }
+    }
+
+    /// Borra partidas que ya no sirven: sin empezar y vacías, o terminadas y sin nadie.
+    fn cleanup_room(&mut self, game_id: GameId) {
+        let Some(room) = self.rooms.get(&game_id) else {
+            return;
+        };
+        let nobody_waiting = !room.started && !room.seats.iter().any(|s| s.token.is_some());
+        let finished_and_empty = room.game.winner().is_some() && !room.has_clients();
+        if nobody_waiting || finished_and_empty {
+            println!("Partida {} cerrada.", game_id);
+            self.rooms.remove(&game_id);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // HELPERS
+    // -------------------------------------------------------------------------
+
+    fn client_room(&self, id: ClientId) -> Option<GameId> {
+        self.clients.get(&id).and_then(|c| c.room)
+    }
+
+    fn client_seat(&self, id: ClientId) -> Option<(GameId, PlayerType)> {
+        let client = self.clients.get(&id)?;
+        Some((client.room?, client.seat?))
+    }
+
+    pub fn send_error(&mut self, id: ClientId, message: &str) {
+        self.send(id, ServerMessage::Error { message: message.to_string() });
+    }
+
+    /// Deja el mensaje en la cola del cliente sin esperar al socket. Si la
+    /// cola se llenó (no lee) o su conexión ya se cortó, se lo desconecta.
+    fn send(&mut self, id: ClientId, message: ServerMessage) {
+        let failed = match self.clients.get(&id) {
+            Some(client) => client.outbox.try_send(message).is_err(),
+            None => false,
+        };
+        if failed {
+            self.disconnect(id);
+        }
+    }
+}
+
+fn new_token() -> String {
+    format!("{:016x}", rand::rng().random::<u64>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn omniscient_spectator(delay_turns: u32) -> Client {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (outbox, _) = mpsc::sync_channel(1);
+        Client {
+            stream,
+            outbox,
+            room: Some(1),
+            seat: None,
+            spectator: Some(SpectatorMode::Omniscient { delay_turns }),
+            timeline_pos: 0,
+            last_snapshot: None,
+        }
+    }
+
+    #[test]
+    fn timeline_keeps_only_what_the_slowest_spectator_still_needs() {
+        let mut room = GameRoom::new(1, GameOptions::default());
+        let mut client = omniscient_spectator(2);
+        assert!(room.next_view(&mut client, &[]).is_some());
+
+        for turn in 1..=10 {
+            room.game.turn_number = turn;
+            room.record(&[], Some(2));
+            // cada turno se ve el de dos turnos atrás
+            let seen = room.next_view(&mut client, &[]).map(|(_, view)| view.turn_number);
+            assert_eq!(seen, turn.checked_sub(2).filter(|&t| t > 0));
+            room.prune_timeline(Some(2));
+            assert!(room.timeline.iter().all(|e| e.turn + 2 >= turn));
+            assert!(room.timeline.len() <= 3);
+        }
+
+        // sin espectadores con retraso no se graba nada
+        let recorded = room.timeline_start + room.timeline.len();
+        room.record(&[], None);
+        assert!(room.timeline.is_empty());
+        assert_eq!(room.timeline_start, recorded);
+    }
+}
@@ -0,0 +1,14 @@
+// en src/net/mod.rs
+//
+// Juego en red: protocolo JSON por líneas sobre TCP, el servidor con
+// su sala de partidas y la conexión que usan los clientes.
+
+pub mod client;
+pub mod lobby;
+pub mod protocol;
+pub mod server;
+
+pub use client::{ClientState, ServerConnection};
+pub use lobby::{GameId, GameOptions, GameSummary, Lobby, SeatSummary, SpectatorMode};
+pub use protocol::{read_message, write_message, ClientMessage, ServerMessage};
+pub use server::{run_server, serve};
@@ -0,0 +1,82 @@
+// en src/net/protocol.rs
+//
+// Protocolo JSON por líneas: cada mensaje es un objeto JSON en una
+// sola línea terminada en '\n'. Ejemplos de lo que manda un cliente:
+//
+//   {"type":"Join","seat":"Player2"}
+//   {"type":"ListGames"}
+//   {"type":"CreateGame","options":{"name":"Amigos","players":3,"bots":1}}
+//   {"type":"JoinGame","game_id":1,"seat":null}
+//   {"type":"SetReady","ready":true}
+//   {"type":"Spectate","game_id":null,"mode":{"Omniscient":{"delay_turns":2}}}
+//   {"type":"Action","action":{"type":"RollDice"}}
+//   {"type":"Action","action":{"type":"PlaceRoad","edge":12}}
+
+use crate::engine::{Action, GameEvent, PlayerView, ViewDiff};
+use crate::types::*;
+use super::lobby::{GameId, GameOptions, GameSummary, SpectatorMode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    /// Partida rápida: el primer asiento libre de cualquier partida abierta, ya listo.
+    /// Con `None` el servidor da el primero libre.
+    Join { seat: Option<PlayerType> },
+    ListGames,
+    CreateGame { options: GameOptions },
+    JoinGame { game_id: GameId, seat: Option<PlayerType> },
+    /// La partida empieza cuando todos los asientos humanos están listos.
+    SetReady { ready: bool },
+    /// Volver al asiento que se tenía antes de perder la conexión.
+    Reconnect { token: String },
+    /// Mirar una partida sin asiento; con `None` la primera en juego.
+    Spectate { game_id: Option<GameId>, mode: SpectatorMode },
+    LeaveGame,
+    Action { action: Action },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ServerMessage {
+    /// Asiento asignado y el token para reconectarse a él.
+    Welcome { seat: PlayerType, game_id: GameId, token: String },
+    /// Confirmación de `Spectate`.
+    Spectating { game_id: GameId, mode: SpectatorMode },
+    GameList { games: Vec<GameSummary> },
+    /// Cambió algo en la sala de la partida (asientos, listos, empezó).
+    LobbyUpdate { game: GameSummary },
+    /// Estado completo: al sentarse y al empezar la partida.
+    Snapshot { state: Box<PlayerView> },
+    /// Cambios desde el último estado que recibió este cliente.
+    Diff { diff: Box<ViewDiff> },
+    Events { events: Vec<GameEvent> },
+    Error { message: String },
+}
+
+/// Escribe un mensaje como una línea JSON.
+pub fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> io::Result<()> {
+    let line = serde_json::to_string(message).map_err(io::Error::other)?;
+    writer.write_all(line.as_bytes())?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+/// Lee la próxima línea JSON. Devuelve `Ok(None)` si la conexión se cerró.
+pub fn read_message<R: BufRead, T: DeserializeOwned>(reader: &mut R) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.trim().is_empty() {
+            continue; // líneas vacías (p. ej. desde `nc`) se ignoran
+        }
+        return serde_json::from_str(line.trim())
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+    }
+}
@@ -0,0 +1,131 @@
+// en src/net/server.rs
+//
+// Servidor. Un hilo acepta conexiones, cada cliente tiene un hilo
+// lector y otro escritor, y todo llega por un canal al hilo principal,
+// que es el único que toca la `Lobby` y sus partidas.
+
+use super::lobby::{ClientId, GameOptions, Lobby};
+use super::protocol::{read_message, write_message, ClientMessage, ServerMessage};
+use std::io::{self, BufReader, ErrorKind};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Cada cuánto se revisan los relojes aunque no llegue nada.
+const TICK: Duration = Duration::from_millis(250);
+
+/// Lo que los hilos de red le mandan al hilo principal.
+pub enum ServerInput {
+    Connected { id: ClientId, stream: TcpStream },
+    Message { id: ClientId, message: ClientMessage },
+    BadMessage { id: ClientId, error: String },
+    Disconnected { id: ClientId },
+}
+
+// -----------------------------------------------------------------------------
+// HILOS DE RED
+// -----------------------------------------------------------------------------
+
+/// Acepta conexiones en otro hilo y reenvía todo por `tx`.
+pub fn spawn_acceptor(listener: TcpListener, tx: Sender<ServerInput>) {
+    thread::spawn(move || {
+        for (id, stream) in listener.incoming().enumerate() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    println!("Error aceptando conexión: {}", e);
+                    continue;
+                }
+            };
+            let writer = match stream.try_clone() {
+                Ok(w) => w,
+                Err(_) => continue,
+            };
+            if tx.send(ServerInput::Connected { id, stream: writer }).is_err() {
+                return; // el hilo de la partida terminó
+            }
+            spawn_reader(id, stream, tx.clone());
+        }
+    });
+}
+
+fn spawn_reader(id: ClientId, stream: TcpStream, tx: Sender<ServerInput>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let input = match read_message::<_, ClientMessage>(&mut reader) {
+                Ok(Some(message)) => ServerInput::Message { id, message },
+                Ok(None) => break,
+                Err(e) if e.kind() == ErrorKind::InvalidData => {
+                    ServerInput::BadMessage { id, error: e.to_string() }
+                }
+                Err(_) => break,
+            };
+            if tx.send(input).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(ServerInput::Disconnected { id });
+    });
+}
+
+/// Escribe en otro hilo lo que llegue a la cola devuelta, que admite hasta
+/// `capacity` mensajes pendientes. Al soltarse la cola o fallar el socket,
+/// la conexión se cierra y el lector avisa la desconexión.
+pub(super) fn spawn_writer(mut stream: TcpStream, capacity: usize) -> SyncSender<ServerMessage> {
+    let (tx, rx) = mpsc::sync_channel(capacity);
+    thread::spawn(move || {
+        for message in rx {
+            if write_message(&mut stream, &message).is_err() {
+                break;
+            }
+        }
+        let _ = stream.shutdown(Shutdown::Both);
+    });
+    tx
+}
+
+/// Hospeda la sala de espera en `addr`. Las partidas que se crean con un
+/// `Join` rápido usan `quick_game` (jugadores, dados, reglas...).
+pub fn run_server(addr: &str, quick_game: GameOptions) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!(
+        "Servidor de Catan escuchando en {} (partidas rápidas de {} jugadores, {}).",
+        listener.local_addr()?,
+        quick_game.players,
+        quick_game.rules.dice.name()
+    );
+    serve(listener, quick_game);
+    Ok(())
+}
+
+/// Como `run_server`, con un `listener` ya abierto (p. ej. en un puerto libre).
+pub fn serve(listener: TcpListener, quick_game: GameOptions) {
+    let (tx, rx): (Sender<ServerInput>, Receiver<ServerInput>) = mpsc::channel();
+    spawn_acceptor(listener, tx);
+
+    let mut lobby = Lobby::new(quick_game);
+    let mut last_tick = Instant::now();
+    loop {
+        let input = rx.recv_timeout(TICK);
+        // el tiempo que pasó se le cobra a quien decidía antes de este mensaje
+        let now = Instant::now();
+        lobby.tick(now - last_tick);
+        last_tick = now;
+
+        let input = match input {
+            Ok(input) => input,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        match input {
+            ServerInput::Connected { id, stream } => lobby.connect(id, stream),
+            ServerInput::Message { id, message } => lobby.handle_message(id, message),
+            ServerInput::BadMessage { id, error } => {
+                lobby.send_error(id, &format!("Mensaje inválido: {}", error));
+            }
+            ServerInput::Disconnected { id } => lobby.disconnect(id),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+// src/rules.rs
+//
+// Reglas de la partida. Lo que antes eran números fijos en el código
+// (10 puntos, descarte con más de 7, banco 4:1, 3 caballeros, 5 caminos,
+// piezas por jugador) vive acá, junto con las reglas de la casa más
+// comunes. Se guarda en el `Board` para que toda la lógica lo vea, y se
+// puede leer de un JSON; lo que falte en el archivo toma el valor normal.
+
+use crate::dice::DiceMode;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    /// Puntos para ganar.
+    pub vp_target: u8,
+    /// Con más de estas cartas en mano, al salir un 7 se descarta la mitad.
+    pub discard_limit: u8,
+    /// Cartas que pide el banco sin puertos.
+    pub bank_rate: u8,
+    /// Caballeros jugados para reclamar el Gran Ejército.
+    pub largest_army_min: u8,
+    /// Largo de camino para reclamar la Ruta Más Larga.
+    pub longest_road_min: u8,
+    pub settlements: u8,
+    pub cities: u8,
+    pub roads: u8,
+    /// Navegantes: barcos por jugador.
+    pub ships: u8,
+    /// Navegantes: puntos por la primera casa en cada isla que no es la de origen.
+    pub island_bonus: u8,
+    /// Ciudades y Caballeros: mercancías, mejoras, caballeros, bárbaros y cartas de progreso.
+    pub cities_and_knights: bool,
+    /// Escenarios enchufados con ganchos (Pescadores, Ríos...).
+    pub plugins: Vec<ScenarioPlugin>,
+    /// De dónde salen los dados.
+    pub dice: DiceMode,
+
+    // --- reglas de la casa ---
+    /// Ladrón amistoso: no se le roba a quien tiene menos de estos puntos visibles.
+    pub friendly_robber: Option<u8>,
+    /// En las primeras rondas no sale el 7: se vuelve a tirar.
+    pub no_sevens_rounds: u8,
+    /// Cartas de desarrollo que se pueden jugar por turno.
+    pub dev_cards_per_turn: u8,
+    /// Las dos casas de la fundación dan recursos, no sólo la segunda.
+    pub starting_resources_from_both: bool,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            vp_target: 10,
+            discard_limit: 7,
+            bank_rate: 4,
+            largest_army_min: 3,
+            longest_road_min: 5,
+            settlements: 5,
+            cities: 4,
+            roads: 15,
+            ships: 15,
+            island_bonus: 0,
+            cities_and_knights: false,
+            plugins: Vec::new(),
+            dice: DiceMode::Standard,
+            friendly_robber: None,
+            no_sevens_rounds: 0,
+            dev_cards_per_turn: 1,
+            starting_resources_from_both: false,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Lee las reglas de un JSON.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    /// Activa Ciudades y Caballeros; si la meta era la normal, pasa a 13 puntos.
+    pub fn with_cities_and_knights(mut self) -> Self {
+        if self.vp_target == RuleSet::default().vp_target {
+            self.vp_target = 13;
+        }
+        self.cities_and_knights = true;
+        self
+    }
+
+    /// Suma un escenario enchufable, si no estaba.
+    pub fn with_plugin(mut self, plugin: ScenarioPlugin) -> Self {
+        if !self.plugins.contains(&plugin) {
+            self.plugins.push(plugin);
+        }
+        self
+    }
+
+    /// ¿Vale un 7 en la ronda `round` (la primera después de la fundación es la 1)?
+    pub fn allows_seven(&self, round: u32) -> bool {
+        round > self.no_sevens_rounds as u32
+    }
+
+    /// ¿Se le puede robar a `player`? Con ladrón amistoso, no a quien va atrás.
+    pub fn can_be_robbed(&self, player: &Player) -> bool {
+        match self.friendly_robber {
+            Some(min) => public_victory_points(player) >= min,
+            None => true,
+        }
+    }
+
+    /// Las reglas de la casa activas, para mostrarlas en una línea.
+    pub fn house_rules(&self) -> Vec<String> {
+        let mut rules = Vec::new();
+        if self.cities_and_knights {
+            rules.push("Ciudades y Caballeros".to_string());
+        }
+        for plugin in &self.plugins {
+            rules.push(plugin.name().to_string());
+        }
+        if self.dice != DiceMode::Standard {
+            rules.push(self.dice.name().to_string());
+        }
+        if let Some(min) = self.friendly_robber {
+            rules.push(format!("ladrón amistoso (< {} puntos)", min));
+        }
+        if self.no_sevens_rounds > 0 {
+            rules.push(format!("sin 7 en las primeras {} rondas", self.no_sevens_rounds));
+        }
+        if self.dev_cards_per_turn != 1 {
+            rules.push(format!("{} cartas de desarrollo por turno", self.dev_cards_per_turn));
+        }
+        if self.starting_resources_from_both {
+            rules.push("recursos de las dos casas iniciales".to_string());
+        }
+        rules
+    }
+}
+
+/// Puntos que ven los demás: sin las cartas de punto de victoria ocultas.
+pub fn public_victory_points(player: &Player) -> u8 {
+    let hidden = player
+        .dev_cards
+        .iter()
+        .filter(|&&c| c == DevelopmentCard::VictoryPoint)
+        .count() as u8;
+    player.victory_points.saturating_sub(hidden)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::development_cards::dev_card_limit_reached;
+    use crate::dice::Dice;
+    use crate::engine::{Game, GameEvent, GamePhase};
+    use crate::setup::MapLayout;
+
+    /// Escribe `text` en un archivo temporal y lo lee como reglas.
+    fn load_text(name: &str, text: &str) -> Result<RuleSet, String> {
+        let path = std::env::temp_dir().join(format!("catan_reglas_{}_{}.json", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        let rules = RuleSet::load(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        rules
+    }
+
+    #[test]
+    fn partial_file_keeps_the_defaults() {
+        let rules = load_text("parcial", r#"{"vp_target": 12, "friendly_robber": 3}"#).unwrap();
+        assert_eq!(rules.vp_target, 12);
+        assert_eq!(rules.friendly_robber, Some(3));
+        assert_eq!(rules.discard_limit, RuleSet::default().discard_limit);
+        assert_eq!(rules.dice, DiceMode::Standard);
+    }
+
+    #[test]
+    fn invalid_or_missing_file_is_an_error() {
+        assert!(load_text("invalido", r#"{"vp_target": "diez"}"#).is_err());
+        assert!(load_text("roto", "{").is_err());
+        assert!(RuleSet::load("/no/existe/reglas.json").is_err());
+    }
+
+    #[test]
+    fn public_points_hide_victory_cards() {
+        let mut player = Player::new(PlayerType::Player1);
+        player.victory_points = 5;
+        player.dev_cards = vec![DevelopmentCard::VictoryPoint, DevelopmentCard::Knight, DevelopmentCard::VictoryPoint];
+        assert_eq!(public_victory_points(&player), 3);
+        player.victory_points = 1;
+        assert_eq!(public_victory_points(&player), 0);
+    }
+
+    #[test]
+    fn friendly_robber_protects_players_below_the_threshold() {
+        let rules = RuleSet { friendly_robber: Some(3), ..Default::default() };
+        let mut player = Player::new(PlayerType::Player2);
+        player.victory_points = 2;
+        assert!(!rules.can_be_robbed(&player));
+        player.victory_points = 3;
+        assert!(rules.can_be_robbed(&player));
+        // las cartas de punto ocultas no cuentan
+        player.dev_cards.push(DevelopmentCard::VictoryPoint);
+        assert!(!rules.can_be_robbed(&player));
+        assert!(RuleSet::default().can_be_robbed(&Player::new(PlayerType::Player2)));
+    }
+
+    #[test]
+    fn no_sevens_in_the_first_rounds() {
+        let rules = RuleSet { no_sevens_rounds: 2, ..Default::default() };
+        assert!(!rules.allows_seven(1));
+        assert!(!rules.allows_seven(2));
+        assert!(rules.allows_seven(3));
+
+        let mut dice = Dice::default();
+        for _ in 0..200 {
+            let (white, red) = dice.roll(&rules, 1);
+            assert_ne!(white + red, 7);
+        }
+        assert!(dice.enter(&rules, 2, 3, 4).is_err());
+        assert_eq!(dice.enter(&rules, 3, 3, 4), Ok((3, 4)));
+    }
+
+    #[test]
+    fn dev_card_limit_per_turn() {
+        let mut board = Game::new(2).board;
+        board.rules.dev_cards_per_turn = 2;
+        board.players[0].dev_cards_played_this_turn = 1;
+        assert!(!dev_card_limit_reached(&board, 0));
+        board.players[0].dev_cards_played_this_turn = 2;
+        assert!(dev_card_limit_reached(&board, 0));
+    }
+
+    /// ¿Dio recursos alguna casa de la primera ronda de la fundación?
+    fn first_round_pays(rules: RuleSet) -> bool {
+        let mut game = Game::with_options(2, MapLayout::Fixed, rules);
+        let mut paid = false;
+        while let GamePhase::Setup { round: 1, .. } = game.phase {
+            let player = game.current_player();
+            let action = game.fallback_action(player).unwrap();
+            let events = game.apply(player, action).unwrap();
+            paid |= events.iter().any(|e| matches!(e, GameEvent::StartingResources { .. }));
+        }
+        paid
+    }
+
+    #[test]
+    fn resources_from_both_setup_settlements() {
+        assert!(!first_round_pays(RuleSet::default()));
+        assert!(first_round_pays(RuleSet { starting_resources_from_both: true, ..Default::default() }));
+    }
+}
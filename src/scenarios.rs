@@ -0,0 +1,120 @@
+// src/scenarios.rs
+//
+// Escenarios de Navegantes como definiciones de tablero. Cada mapa se
+// dibuja con una letra por casilla, en filas desplazadas (las impares
+// van medio hex a la derecha), y se arma con `setup::board_from_hexes`.
+//
+//   ~ mar   d desierto   g oro
+//   m madera   l ladrillo   o oveja   t trigo   p piedra
+//
+// Los números se reparten en orden de lectura con la secuencia de
+// fichas de siempre. Estos mapas no traen puertos.
+
+use crate::setup::board_from_hexes;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scenario {
+    /// Hacia Nuevas Costas: una isla grande y dos chicas para explorar.
+    NewShores,
+    /// Las Cuatro Islas: cada uno arranca en una y sale a conquistar las demás.
+    FourIslands,
+}
+
+/// Orden de las fichas de número, como en el tablero estándar.
+const NUMBER_TOKENS: [u8; 18] = [5, 2, 6, 3, 8, 10, 9, 12, 11, 4, 8, 10, 9, 4, 5, 6, 3, 11];
+
+const NEW_SHORES: [&str; 7] = [
+    "~~~~~~~~~~",
+    "~mlot~~go~",
+    "~ptmol~~l~",
+    "~ompdtp~~~",
+    "~ltmop~~t~",
+    "~pomt~~gp~",
+    "~~~~~~~~~~",
+];
+
+const FOUR_ISLANDS: [&str; 7] = [
+    "~~~~~~~~~~",
+    "~mlo~~tpm~",
+    "~tpo~~olt~",
+    "~~~~~~~~~~",
+    "~gmp~~plo~",
+    "~ltod~mtg~",
+    "~~~~~~~~~~",
+];
+
+impl Scenario {
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::NewShores => "Hacia Nuevas Costas",
+            Scenario::FourIslands => "Las Cuatro Islas",
+        }
+    }
+
+    pub fn vp_target(self) -> u8 {
+        match self {
+            Scenario::NewShores => 14,
+            Scenario::FourIslands => 13,
+        }
+    }
+
+    /// Puntos por la primera casa en cada isla que no es la de origen.
+    pub fn island_bonus(self) -> u8 {
+        2
+    }
+
+    fn rows(self) -> &'static [&'static str] {
+        match self {
+            Scenario::NewShores => &NEW_SHORES,
+            Scenario::FourIslands => &FOUR_ISLANDS,
+        }
+    }
+
+    /// Casilla de mar donde arranca el pirata (fila, columna).
+    fn pirate_start(self) -> (usize, usize) {
+        match self {
+            Scenario::NewShores => (3, 7),
+            Scenario::FourIslands => (3, 5),
+        }
+    }
+
+    pub fn board(self) -> Board {
+        let mut hexes = Vec::new();
+        let mut pirate = None;
+        let mut tokens = NUMBER_TOKENS.iter().cycle();
+
+        for (row, line) in self.rows().iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                // filas desplazadas a coordenadas axiales
+                let r = row as i32;
+                let q = col as i32 - (r - (r & 1)) / 2;
+                let material = match c {
+                    'm' => MaterialType::Wood,
+                    'l' => MaterialType::Brick,
+                    'o' => MaterialType::Sheep,
+                    't' => MaterialType::Wheat,
+                    'p' => MaterialType::Stone,
+                    'g' => MaterialType::Gold,
+                    'd' => MaterialType::Dessert,
+                    _ => MaterialType::Sea,
+                };
+                let number = match material {
+                    MaterialType::Sea | MaterialType::Dessert => 0,
+                    _ => *tokens.next().unwrap_or(&0),
+                };
+                if (row, col) == self.pirate_start() {
+                    pirate = Some(hexes.len());
+                }
+                hexes.push((q, r, material, number));
+            }
+        }
+
+        let mut board = board_from_hexes(&hexes);
+        if let Some(tile) = pirate {
+            board.tiles[tile].has_pirate = true;
+        }
+        board
+    }
+}
@@ -1,11 +1,103 @@
 use std::collections::HashSet;
 use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
 use crate::types::*;
+use crate::rules::RuleSet;
+use crate::dice::Dice;
+use crate::scenarios::Scenario;
+use crate::game_logic::prepare_scenarios;
+use std::collections::HashMap;
 
+/// Cómo se reparten los materiales de las casillas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapLayout {
+    /// Materiales mezclados al azar (lo de siempre).
+    Random,
+    /// Materiales en el orden fijo de la lista, con el desierto en el centro.
+    Fixed,
+    /// Un escenario de Navegantes, con mar y varias islas.
+    Seafarers(Scenario),
+}
+
+impl MapLayout {
+    /// Ajusta las reglas al mapa: los escenarios traen su meta de puntos y el bonus por isla.
+    pub fn adjust_rules(self, mut rules: RuleSet) -> RuleSet {
+        if let MapLayout::Seafarers(scenario) = self {
+            if rules.vp_target == RuleSet::default().vp_target {
+                rules.vp_target = scenario.vp_target();
+            }
+            if rules.island_bonus == 0 {
+                rules.island_bonus = scenario.island_bonus();
+            }
+        }
+        rules
+    }
+}
+
+
+/// Ciudades y Caballeros: los tres mazos de progreso, mezclados.
+fn progress_decks() -> HashMap<ImprovementTrack, Vec<ProgressCard>> {
+    let decks: [(ImprovementTrack, &[(ProgressCard, usize)]); 3] = [
+        (ImprovementTrack::Science, &[
+            (ProgressCard::Irrigation, 2), (ProgressCard::Mining, 2),
+            (ProgressCard::RoadBuilding, 2), (ProgressCard::Smith, 2),
+            (ProgressCard::Printer, 1),
+        ]),
+        (ImprovementTrack::Trade, &[
+            (ProgressCard::ResourceMonopoly, 4), (ProgressCard::TradeMonopoly, 2),
+        ]),
+        (ImprovementTrack::Politics, &[
+            (ProgressCard::Warlord, 2), (ProgressCard::Bishop, 2),
+            (ProgressCard::Constitution, 1),
+        ]),
+    ];
+    let mut rng = rand::rng();
+    decks
+        .iter()
+        .map(|&(track, cards)| {
+            let mut deck: Vec<ProgressCard> = cards
+                .iter()
+                .flat_map(|&(card, copies)| std::iter::repeat_n(card, copies))
+                .collect();
+            deck.shuffle(&mut rng);
+            (track, deck)
+        })
+        .collect()
+}
+
+/// Mazo de cartas de desarrollo, mezclado.
+fn development_deck() -> Vec<DevelopmentCard> {
+    let mut development_cards = [
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Knight, DevelopmentCard::Knight,
+        DevelopmentCard::Monopoly, DevelopmentCard::Monopoly,
+        DevelopmentCard::RoadBuilding, DevelopmentCard::RoadBuilding,
+        DevelopmentCard::YearOfPlenty, DevelopmentCard::YearOfPlenty,
+        DevelopmentCard::VictoryPoint, DevelopmentCard::VictoryPoint, 
+        DevelopmentCard::VictoryPoint, DevelopmentCard::VictoryPoint, 
+        DevelopmentCard::VictoryPoint, DevelopmentCard::VictoryPoint, 
+        DevelopmentCard::VictoryPoint,
+    ];
 
+    development_cards.shuffle(&mut rand::rng());
+    development_cards.to_vec()
+}
 
 /// Construye un tablero de Catan estándar, 100% conectado.
 pub fn setup_board() -> Board {
+    setup_board_with_layout(MapLayout::Random)
+}
+
+/// Igual que `setup_board`, eligiendo cómo se reparten los materiales.
+pub fn setup_board_with_layout(layout: MapLayout) -> Board {
+    if let MapLayout::Seafarers(scenario) = layout {
+        return scenario.board();
+    }
     let mut vertices = Vec::new();
     let mut tiles = Vec::new();
     let mut edges = Vec::new();
@@ -20,6 +112,8 @@ pub fn setup_board() -> Board {
             adjacent_tiles: Vec::new(), // Se llenará después
             adjacent_edges: Vec::new(), // Se llenará después
             power_up: None,
+            knight: None,
+            pieces: Vec::new(),
         });
     }
 
@@ -62,6 +156,9 @@ pub fn setup_board() -> Board {
             number: 0,                       // Temporal
             vertices: [0; 6],                // Temporal
             has_robber: false,
+            has_pirate: false,
+            coord: None,
+            pieces: Vec::new(),
         });
     }
 
@@ -77,7 +174,7 @@ pub fn setup_board() -> Board {
         (3,  [6,  7,  8,  9,  35, 34]), // Tile 4
         (4,  [35, 9,  10, 11, 37, 36]), // Tile 5
         (5,  [37, 11, 12, 13, 14, 38]), // Tile 6
-        (6,  [39, 38, 14, 17, 16, 40]), // Tile 7
+        (6,  [39, 38, 14, 15, 16, 40]), // Tile 7 <-- tenía 17 en vez de 15
         (7,  [41, 40, 16, 17, 18, 19]), // Tile 8
         (8,  [43, 42, 41, 19, 20, 21]), // Tile 9
         (9,  [24, 44, 43, 21, 22, 23]), // Tile 10
@@ -140,6 +237,8 @@ pub fn setup_board() -> Board {
         edges.push(Edge {
             owner: None,
             vertices: (v1_id, v2_id),
+            ship: false,
+            pieces: Vec::new(),
         });
 
         // 2. Asignamos este borde a sus dos vértices
@@ -165,26 +264,10 @@ pub fn setup_board() -> Board {
         5, 2, 6, 10, 9, 4, 3, 8, 11, 5, 8, 4, 11, 12, 9, 6, 3, 10,
     ]; // Total: 18 fichas de número
     
-    let mut development_cards = [
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Knight, DevelopmentCard::Knight,
-        DevelopmentCard::Monopoly, DevelopmentCard::Monopoly,
-        DevelopmentCard::RoadBuilding, DevelopmentCard::RoadBuilding,
-        DevelopmentCard::YearOfPlenty, DevelopmentCard::YearOfPlenty,
-        DevelopmentCard::VictoryPoint, DevelopmentCard::VictoryPoint, 
-        DevelopmentCard::VictoryPoint, DevelopmentCard::VictoryPoint, 
-        DevelopmentCard::VictoryPoint, DevelopmentCard::VictoryPoint, 
-        DevelopmentCard::VictoryPoint,
-    ];
-
     let mut rng = rand::thread_rng();
-    tile_materials.shuffle(&mut rng);
-    development_cards.shuffle(&mut rng);
+    if layout == MapLayout::Random {
+        tile_materials.shuffle(&mut rng);
+    }
 
     let mut number_index = 0;
 
@@ -204,19 +287,48 @@ pub fn setup_board() -> Board {
 
     // --- ¡Listo! ---
     // Devolvemos el tablero completamente instanciado y conectado.
+    // Los mínimos de Gran Ejército y Camino Más Largo salen de las reglas
+    // (hay que superar el tamaño guardado); `set_rules` los vuelve a poner.
+    let rules = RuleSet::default();
     Board { 
     vertices, 
     tiles, 
     edges, 
     players, 
-    development_cards: development_cards.to_vec(),
+    development_cards: development_deck(),
     largest_army: None,
-    largest_army_size: 2, // Se necesita > 2 (o sea, 3) para reclamarlo
+    largest_army_size: rules.largest_army_min.saturating_sub(1),
     longest_road: None,
-    longest_road_size: 4, // Se necesita > 4 (o sea, 5) para reclamarlo
+    longest_road_size: rules.longest_road_min.saturating_sub(1),
+    rules,
+    barbarian_position: 0,
+    barbarian_attacks: 0,
+    metropolises: HashMap::new(),
+    progress_decks: HashMap::new(),
+    dice: Dice::default(),
     }
 }
 
+/// Cambia las reglas de un tablero recién armado: umbrales de los premios
+/// y piezas de los jugadores que ya estén sentados.
+pub fn set_rules(board: &mut Board, rules: RuleSet) {
+    board.largest_army_size = rules.largest_army_min.saturating_sub(1);
+    board.longest_road_size = rules.longest_road_min.saturating_sub(1);
+    for player in board.players.iter_mut() {
+        player.settlement_quantity = rules.settlements;
+        player.city_quantity = rules.cities;
+        player.road_quantity = rules.roads;
+        player.ship_quantity = rules.ships;
+    }
+    // en Ciudades y Caballeros las cartas de progreso reemplazan a las de desarrollo
+    if rules.cities_and_knights {
+        board.development_cards.clear();
+        board.progress_decks = progress_decks();
+    }
+    board.rules = rules;
+    prepare_scenarios(board);
+}
+
 pub fn add_player(board: &mut Board) -> Option<PlayerType> {
     
     let current_player_count = board.players.len();
@@ -239,7 +351,7 @@ pub fn add_player(board: &mut Board) -> Option<PlayerType> {
     };
 
     // --- 3. Crea el nuevo jugador usando el constructor ---
-    let new_player = Player::new(next_player_id);
+    let new_player = Player::with_rules(next_player_id, &board.rules);
     println!("Jugador {:?} agregado al juego.", new_player.id);
 
     // --- 4. Agrega el jugador al tablero ---
@@ -247,4 +359,134 @@ pub fn add_player(board: &mut Board) -> Option<PlayerType> {
 
     // --- 5. Devuelve el ID del jugador agregado ---
     return Some(next_player_id);
-}
\ No newline at end of file
+}
+/// Arma un tablero a partir de casillas en coordenadas axiales (q, r) con
+/// material y número. Los vértices y bordes salen de las esquinas que
+/// comparten las casillas; el ladrón empieza en el primer desierto.
+pub fn board_from_hexes(hexes: &[(i32, i32, MaterialType, u8)]) -> Board {
+    // esquinas de un hex "pointy" en una grilla entera: x en medios anchos, y en cuartos de alto,
+    // en el mismo orden que usa la ventana (arriba y en sentido horario)
+    const CORNERS: [(i32, i32); 6] = [(0, -2), (1, -1), (1, 1), (0, 2), (-1, 1), (-1, -1)];
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut tiles: Vec<Tile> = Vec::new();
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut vertex_ids: HashMap<(i32, i32), VertexId> = HashMap::new();
+    let mut edge_ids: HashMap<(VertexId, VertexId), EdgeId> = HashMap::new();
+
+    for (tile_id, &(q, r, material, number)) in hexes.iter().enumerate() {
+        let (cx, cy) = (2 * q + r, 3 * r);
+        let mut corners = [0; 6];
+        for (i, &(dx, dy)) in CORNERS.iter().enumerate() {
+            let id = *vertex_ids.entry((cx + dx, cy + dy)).or_insert_with(|| {
+                vertices.push(Vertex {
+                    owner: None,
+                    building: None,
+                    adjacent_tiles: Vec::new(),
+                    adjacent_edges: Vec::new(),
+                    power_up: None,
+                    knight: None,
+                    pieces: Vec::new(),
+                });
+                vertices.len() - 1
+            });
+            vertices[id].adjacent_tiles.push(tile_id);
+            corners[i] = id;
+        }
+        for i in 0..6 {
+            let (a, b) = (corners[i], corners[(i + 1) % 6]);
+            let key = (a.min(b), a.max(b));
+            edge_ids.entry(key).or_insert_with(|| {
+                let edge_id = edges.len();
+                edges.push(Edge { owner: None, vertices: key, ship: false, pieces: Vec::new() });
+                vertices[a].adjacent_edges.push(edge_id);
+                vertices[b].adjacent_edges.push(edge_id);
+                edge_id
+            });
+        }
+        tiles.push(Tile {
+            material,
+            number,
+            vertices: corners,
+            has_robber: false,
+            has_pirate: false,
+            coord: Some((q, r)),
+            pieces: Vec::new(),
+        });
+    }
+
+    if let Some(desert) = tiles.iter_mut().find(|t| t.material == MaterialType::Dessert) {
+        desert.has_robber = true;
+    }
+
+    let rules = RuleSet::default();
+    Board {
+        vertices,
+        tiles,
+        edges,
+        players: Vec::new(),
+        development_cards: development_deck(),
+        largest_army: None,
+        largest_army_size: rules.largest_army_min.saturating_sub(1),
+        longest_road: None,
+        longest_road_size: rules.longest_road_min.saturating_sub(1),
+        rules,
+        barbarian_position: 0,
+        barbarian_attacks: 0,
+        metropolises: HashMap::new(),
+        progress_decks: HashMap::new(),
+        dice: Dice::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cada casilla tiene que cerrar un anillo de seis bordes distintos, y
+    /// cada vértice tiene que estar en el tablero con dos o tres bordes.
+    fn assert_closed_rings(board: &Board) {
+        let has_edge = |a: VertexId, b: VertexId| {
+            board.edges.iter().any(|e| e.vertices == (a, b) || e.vertices == (b, a))
+        };
+        for (tile_id, tile) in board.tiles.iter().enumerate() {
+            let distinct: HashSet<_> = tile.vertices.iter().collect();
+            assert_eq!(distinct.len(), 6, "la casilla {} repite vértices", tile_id);
+            for i in 0..6 {
+                let (a, b) = (tile.vertices[i], tile.vertices[(i + 1) % 6]);
+                assert!(has_edge(a, b), "la casilla {} no tiene el borde {}-{}", tile_id, a, b);
+            }
+        }
+        for (vertex_id, vertex) in board.vertices.iter().enumerate() {
+            assert!(
+                (1..=3).contains(&vertex.adjacent_tiles.len()),
+                "el vértice {} toca {} casillas",
+                vertex_id,
+                vertex.adjacent_tiles.len()
+            );
+            assert!(
+                (2..=3).contains(&vertex.adjacent_edges.len()),
+                "el vértice {} tiene {} bordes",
+                vertex_id,
+                vertex.adjacent_edges.len()
+            );
+        }
+        for (edge_id, edge) in board.edges.iter().enumerate() {
+            let (a, b) = edge.vertices;
+            let tiles = board
+                .tiles
+                .iter()
+                .filter(|t| t.vertices.contains(&a) && t.vertices.contains(&b))
+                .count();
+            assert!((1..=2).contains(&tiles), "el borde {} está en {} casillas", edge_id, tiles);
+        }
+    }
+
+    #[test]
+    fn standard_board_tiles_are_closed_rings() {
+        let board = setup_board_with_layout(MapLayout::Fixed);
+        assert_eq!(board.vertices.len(), 54);
+        assert_eq!(board.edges.len(), 72);
+        assert_closed_rings(&board);
+    }
+}
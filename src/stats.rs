@@ -0,0 +1,324 @@
+// src/stats.rs
+//
+// Estadísticas de la partida. Se van acumulando durante el juego y,
+// cuando `check_for_winner` devuelve un ganador, se muestran en un
+// resumen y se exportan a JSON.
+
+use crate::types::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+/// Copia de las manos de todos los jugadores, para comparar antes/después de una acción.
+pub type HandSnapshot = HashMap<PlayerType, HashMap<MaterialType, u8>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GainSource {
+    Production,
+    Trade,
+    Robbery,
+    /// Año de la Abundancia, monopolios y cartas de progreso que dan recursos.
+    DevCard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossCause {
+    Robber,
+    Discard,
+    Monopoly,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlayerStats {
+    /// Tiradas propias: índice = suma de los dados (2-12).
+    pub dice_rolls: [u32; 13],
+    pub gained_from_production: HashMap<MaterialType, u32>,
+    pub gained_from_trades: HashMap<MaterialType, u32>,
+    pub gained_from_robbery: HashMap<MaterialType, u32>,
+    pub gained_from_dev_cards: HashMap<MaterialType, u32>,
+    pub lost_to_robber: u32,
+    pub lost_to_discards: u32,
+    pub lost_to_monopoly: u32,
+    pub settlements_built: u32,
+    pub cities_built: u32,
+    pub roads_built: u32,
+    pub dev_cards_bought: u32,
+    pub dev_cards_played: HashMap<DevelopmentCard, u32>,
+    pub turns_with_largest_army: u32,
+    pub turns_with_longest_road: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GameStats {
+    pub turns: u32,
+    pub winner: Option<PlayerType>,
+    pub players: HashMap<PlayerType, PlayerStats>,
+}
+
+impl GameStats {
+    pub fn new(player_ids: &[PlayerType]) -> Self {
+        let mut players = HashMap::new();
+        for &id in player_ids {
+            players.insert(id, PlayerStats::default());
+        }
+        GameStats { turns: 0, winner: None, players }
+    }
+
+    fn player_mut(&mut self, player_id: PlayerType) -> &mut PlayerStats {
+        self.players.entry(player_id).or_default()
+    }
+
+    pub fn record_roll(&mut self, player_id: PlayerType, roll: u8) {
+        if (2..=12).contains(&roll) {
+            self.player_mut(player_id).dice_rolls[roll as usize] += 1;
+        }
+    }
+
+    pub fn record_build(&mut self, player_id: PlayerType, building: Option<BuildingType>) {
+        let stats = self.player_mut(player_id);
+        match building {
+            Some(BuildingType::Settlement) => stats.settlements_built += 1,
+            Some(BuildingType::City) => stats.cities_built += 1,
+            None => stats.roads_built += 1, // sin edificio = camino
+        }
+    }
+
+    pub fn record_dev_card_bought(&mut self, player_id: PlayerType) {
+        self.player_mut(player_id).dev_cards_bought += 1;
+    }
+
+    pub fn record_dev_card_played(&mut self, player_id: PlayerType, card: DevelopmentCard) {
+        *self.player_mut(player_id).dev_cards_played.entry(card).or_insert(0) += 1;
+    }
+
+    /// Suma lo que cada jugador ganó desde `before` a la fuente indicada.
+    pub fn record_gains(&mut self, before: &HandSnapshot, board: &Board, source: GainSource) {
+        for player in &board.players {
+            for (material, delta) in hand_deltas(before, player) {
+                if delta <= 0 {
+                    continue;
+                }
+                let stats = self.player_mut(player.id);
+                let bucket = match source {
+                    GainSource::Production => &mut stats.gained_from_production,
+                    GainSource::Trade => &mut stats.gained_from_trades,
+                    GainSource::Robbery => &mut stats.gained_from_robbery,
+                    GainSource::DevCard => &mut stats.gained_from_dev_cards,
+                };
+                *bucket.entry(material).or_insert(0) += delta as u32;
+            }
+        }
+    }
+
+    /// Suma lo que cada jugador perdió desde `before` a la causa indicada.
+    pub fn record_losses(&mut self, before: &HandSnapshot, board: &Board, cause: LossCause) {
+        for player in &board.players {
+            let lost: i32 = hand_deltas(before, player)
+                .into_iter()
+                .filter(|&(_, delta)| delta < 0)
+                .map(|(_, delta)| -delta)
+                .sum();
+            if lost == 0 {
+                continue;
+            }
+            let stats = self.player_mut(player.id);
+            match cause {
+                LossCause::Robber => stats.lost_to_robber += lost as u32,
+                LossCause::Discard => stats.lost_to_discards += lost as u32,
+                LossCause::Monopoly => stats.lost_to_monopoly += lost as u32,
+            }
+        }
+    }
+
+    /// Cierra un turno: cuenta quién tiene Mayor Ejército y Camino Más Largo.
+    pub fn end_turn(&mut self, board: &Board) {
+        self.turns += 1;
+        if let Some(holder) = board.largest_army {
+            self.player_mut(holder).turns_with_largest_army += 1;
+        }
+        if let Some(holder) = board.longest_road {
+            self.player_mut(holder).turns_with_longest_road += 1;
+        }
+    }
+
+    pub fn finish(&mut self, board: &Board, winner: PlayerType) {
+        // el turno ganador también cuenta
+        self.end_turn(board);
+        self.winner = Some(winner);
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn export_json(&self, path: &str) -> io::Result<()> {
+        let json = self.to_json().map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// Texto del resumen de fin de partida (lo usan la consola y Bevy).
+    pub fn summary(&self, board: &Board) -> String {
+        let mut out = String::new();
+        out.push_str("=========== RESUMEN DE LA PARTIDA ===========\n");
+        match self.winner {
+            Some(w) => out.push_str(&format!("Ganador: {:?} en {} turnos\n", w, self.turns)),
+            None => out.push_str(&format!("Sin ganador ({} turnos)\n", self.turns)),
+        }
+
+        for player in &board.players {
+            let Some(stats) = self.players.get(&player.id) else {
+                continue;
+            };
+            out.push_str(&format!("\n--- {:?} ({} VP) ---\n", player.id, player.victory_points));
+
+            let rolls: Vec<String> = (2..=12)
+                .filter(|&n| stats.dice_rolls[n] > 0)
+                .map(|n| format!("{}x{}", n, stats.dice_rolls[n]))
+                .collect();
+            out.push_str(&format!(
+                "  Tiradas: {}\n",
+                if rolls.is_empty() { "Ninguna".to_string() } else { rolls.join(" ") }
+            ));
+            out.push_str(&format!("  Producción: {}\n", format_counts(&stats.gained_from_production)));
+            out.push_str(&format!("  Comercio:   {}\n", format_counts(&stats.gained_from_trades)));
+            out.push_str(&format!("  Robos:      {}\n", format_counts(&stats.gained_from_robbery)));
+            out.push_str(&format!("  Cartas:     {}\n", format_counts(&stats.gained_from_dev_cards)));
+            out.push_str(&format!(
+                "  Perdidas: {} por ladrón, {} por descarte, {} por monopolio\n",
+                stats.lost_to_robber, stats.lost_to_discards, stats.lost_to_monopoly
+            ));
+            out.push_str(&format!(
+                "  Construido: {} casas, {} ciudades, {} caminos\n",
+                stats.settlements_built, stats.cities_built, stats.roads_built
+            ));
+            let played: u32 = stats.dev_cards_played.values().sum();
+            out.push_str(&format!(
+                "  Cartas dev: {} compradas, {} jugadas\n",
+                stats.dev_cards_bought, played
+            ));
+            out.push_str(&format!(
+                "  Turnos con Mayor Ejército: {} | con Camino Más Largo: {}\n",
+                stats.turns_with_largest_army, stats.turns_with_longest_road
+            ));
+        }
+        out.push_str("=============================================\n");
+        out
+    }
+
+    pub fn print_summary(&self, board: &Board) {
+        println!("\n{}", self.summary(board));
+    }
+}
+
+// -----------------------------------------------------------------------------
+// HELPERS
+// -----------------------------------------------------------------------------
+
+pub fn snapshot_hands(board: &Board) -> HandSnapshot {
+    board
+        .players
+        .iter()
+        .map(|p| (p.id, p.resources.clone()))
+        .collect()
+}
+
+pub fn dev_card_count(board: &Board, player_id: PlayerType) -> usize {
+    board
+        .players
+        .iter()
+        .find(|p| p.id == player_id)
+        .map(|p| p.dev_cards.len())
+        .unwrap_or(0)
+}
+
+fn hand_deltas(before: &HandSnapshot, player: &Player) -> Vec<(MaterialType, i32)> {
+    let empty = HashMap::new();
+    let old = before.get(&player.id).unwrap_or(&empty);
+
+    let mut materials: Vec<MaterialType> = old.keys().copied().collect();
+    for material in player.resources.keys() {
+        if !materials.contains(material) {
+            materials.push(*material);
+        }
+    }
+
+    materials
+        .into_iter()
+        .map(|m| {
+            let now = *player.resources.get(&m).unwrap_or(&0) as i32;
+            let was = *old.get(&m).unwrap_or(&0) as i32;
+            (m, now - was)
+        })
+        .collect()
+}
+
+fn format_counts(counts: &HashMap<MaterialType, u32>) -> String {
+    let order = [
+        MaterialType::Wood, MaterialType::Brick, MaterialType::Sheep,
+        MaterialType::Wheat, MaterialType::Stone,
+    ];
+    let parts: Vec<String> = order
+        .iter()
+        .filter_map(|m| counts.get(m).filter(|&&c| c > 0).map(|c| format!("{:?}: {}", m, c)))
+        .collect();
+    if parts.is_empty() { "Nada".to_string() } else { parts.join(", ") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::development_cards::{play_monopoly_card, play_year_of_plenty_card};
+    use crate::engine::Game;
+    use crate::game_logic::steal_random_resource;
+    use PlayerType::{Player1, Player2, Player3};
+
+    fn player(board: &mut Board, id: PlayerType) -> &mut Player {
+        board.players.iter_mut().find(|p| p.id == id).unwrap()
+    }
+
+    #[test]
+    fn dev_cards_and_robbery_are_attributed_to_their_source() {
+        let mut board = Game::new(3).board;
+        for id in [Player1, Player2, Player3] {
+            player(&mut board, id).resources.clear();
+        }
+        player(&mut board, Player1).dev_cards = vec![DevelopmentCard::Monopoly, DevelopmentCard::YearOfPlenty];
+        player(&mut board, Player2).resources.insert(MaterialType::Wood, 3);
+        player(&mut board, Player3).resources.insert(MaterialType::Wood, 1);
+        player(&mut board, Player3).resources.insert(MaterialType::Stone, 2);
+        board.rules.dev_cards_per_turn = 2;
+        let mut stats = GameStats::new(&[Player1, Player2, Player3]);
+
+        // como lo registra la consola
+        let before = snapshot_hands(&board);
+        assert!(play_monopoly_card(&mut board, Player1, MaterialType::Wood));
+        stats.record_gains(&before, &board, GainSource::DevCard);
+        stats.record_losses(&before, &board, LossCause::Monopoly);
+
+        let before = snapshot_hands(&board);
+        assert!(play_year_of_plenty_card(&mut board, Player1, MaterialType::Wheat, MaterialType::Wheat));
+        stats.record_gains(&before, &board, GainSource::DevCard);
+
+        let before = snapshot_hands(&board);
+        assert_eq!(steal_random_resource(&mut board, Player2, Player3), Some(MaterialType::Stone));
+        stats.record_gains(&before, &board, GainSource::Robbery);
+        stats.record_losses(&before, &board, LossCause::Robber);
+
+        let p1 = &stats.players[&Player1];
+        assert_eq!(p1.gained_from_dev_cards, HashMap::from([(MaterialType::Wood, 4), (MaterialType::Wheat, 2)]));
+        assert!(p1.gained_from_robbery.is_empty());
+        assert_eq!(stats.players[&Player2].lost_to_monopoly, 3);
+        assert_eq!(stats.players[&Player2].gained_from_robbery, HashMap::from([(MaterialType::Stone, 1)]));
+        assert_eq!(stats.players[&Player3].lost_to_monopoly, 1);
+        assert_eq!(stats.players[&Player3].lost_to_robber, 1);
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json().unwrap()).unwrap();
+        let players = &json["players"];
+        assert_eq!(players["Player1"]["gained_from_dev_cards"]["Wood"], 4);
+        assert_eq!(players["Player1"]["gained_from_dev_cards"]["Wheat"], 2);
+        assert_eq!(players["Player2"]["gained_from_robbery"]["Stone"], 1);
+        assert_eq!(players["Player2"]["lost_to_monopoly"], 3);
+        assert_eq!(players["Player3"]["lost_to_robber"], 1);
+    }
+}
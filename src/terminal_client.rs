@@ -0,0 +1,673 @@
+// src/terminal_client.rs
+//
+// El juego de consola como cliente remoto: dibuja el estado que manda
+// el servidor con los mismos prints de `terminal_game` y, en vez de
+// tocar un `Board` local, envía `Action`s.
+
+use crate::dice::DiceMode;
+use crate::engine::{Action, CardCounter, ClockConfig, GamePhase, PlayerView};
+use crate::game_logic::{available_exchanges, cards_to_discard};
+use crate::net::{ClientState, GameId, GameOptions, ServerConnection, ServerMessage, SpectatorMode};
+use crate::rules::RuleSet;
+use crate::scenarios::Scenario;
+use crate::setup::MapLayout;
+use crate::terminal_game::*;
+use crate::types::*;
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+
+// -----------------------------------------------------------------------------
+// BUCLE DEL CLIENTE
+// -----------------------------------------------------------------------------
+
+/// Cómo entrar a una partida del servidor.
+pub enum JoinMode {
+    /// Primer asiento libre (o el pedido) de cualquier partida abierta.
+    Quick(Option<PlayerType>),
+    /// Menú de la sala: listar, crear y unirse a partidas.
+    Lobby,
+    /// Volver a un asiento con el token que dio el servidor.
+    Reconnect(String),
+    /// Mirar sin asiento; con `None` la primera partida en juego.
+    Spectate(Option<GameId>, SpectatorMode),
+}
+
+pub fn run_remote_game(addr: &str, entry: JoinMode) {
+    println!("Conectando a {}...", addr);
+    let mut conn = match ServerConnection::connect(addr) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("No se pudo conectar: {}", e);
+            return;
+        }
+    };
+
+    let mut state = ClientState::default();
+    let sent = match entry {
+        JoinMode::Quick(seat) => conn.join(seat),
+        JoinMode::Reconnect(token) => conn.reconnect(&token),
+        JoinMode::Spectate(game_id, mode) => {
+            if let Err(e) = conn.spectate(game_id, mode) {
+                println!("No se pudo pedir la partida: {}", e);
+            } else {
+                watch_game(&mut conn, &mut state);
+            }
+            return;
+        }
+        JoinMode::Lobby => {
+            if !lobby_menu(&mut conn, &mut state) {
+                return;
+            }
+            Ok(())
+        }
+    };
+    if let Err(e) = sent {
+        println!("No se pudo pedir asiento: {}", e);
+        return;
+    }
+
+    // después de mandar una acción, no volvemos a preguntar hasta la respuesta
+    let mut awaiting_reply = false;
+
+    loop {
+        // 1) procesamos todo lo que ya llegó
+        loop {
+            match conn.incoming.try_recv() {
+                Ok(message) => {
+                    if handle_server_message(&mut state, message) {
+                        awaiting_reply = false;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    println!("El servidor cerró la conexión.");
+                    return;
+                }
+            }
+        }
+
+        let started = state.room.as_ref().is_some_and(|room| room.started);
+        let (true, Some(seat), Some(snapshot)) = (started, state.seat, state.snapshot.as_ref()) else {
+            // todavía sin asiento o esperando a los demás: esperamos al servidor
+            if !wait_for_message(&mut conn, &mut state) {
+                return;
+            }
+            continue;
+        };
+
+        if let GamePhase::Finished { winner } = snapshot.phase {
+            print_remote_status(snapshot);
+            println!("¡Ganó {:?}! ¡Fin del juego!", winner);
+            return;
+        }
+
+        // 2) si nos toca decidir algo, preguntamos y mandamos la acción
+        let out_of_time = state.time_left() == Some(Duration::ZERO);
+        if !awaiting_reply && !out_of_time && snapshot.needs_decision() {
+            let deadline = state.time_left().map(|left| Instant::now() + left);
+            if let Some(action) = prompt_action(snapshot, seat, state.counter.as_ref(), deadline) {
+                if let Err(e) = conn.send_action(action) {
+                    println!("Error enviando la acción: {}", e);
+                    return;
+                }
+                awaiting_reply = true;
+            }
+            continue;
+        }
+
+        // 3) si no, esperamos a que pase algo
+        if wait_for_message(&mut conn, &mut state) {
+            awaiting_reply = false;
+        } else {
+            return;
+        }
+    }
+}
+
+/// Modo espectador: imprime los eventos y, en cada turno nuevo, el tablero.
+fn watch_game(conn: &mut ServerConnection, state: &mut ClientState) {
+    let mut last_turn = None;
+    while wait_for_message(conn, state) {
+        let Some(snapshot) = state.snapshot.as_ref() else {
+            continue;
+        };
+        if last_turn != Some(snapshot.turn_number) {
+            last_turn = Some(snapshot.turn_number);
+            print_visual_board(&snapshot.board);
+            print_remote_status(snapshot);
+            if let (false, Some(counter)) = (snapshot.omniscient, state.counter.as_ref()) {
+                print_card_counts(snapshot, counter);
+            }
+        }
+        if let GamePhase::Finished { winner } = snapshot.phase {
+            print_remote_status(snapshot);
+            println!("¡Ganó {:?}! ¡Fin del juego!", winner);
+            return;
+        }
+    }
+}
+
+/// Bloquea hasta el próximo mensaje. Devuelve `false` si se cortó la conexión.
+fn wait_for_message(conn: &mut ServerConnection, state: &mut ClientState) -> bool {
+    match conn.incoming.recv() {
+        Ok(message) => {
+            handle_server_message(state, message);
+            true
+        }
+        Err(_) => {
+            println!("El servidor cerró la conexión.");
+            false
+        }
+    }
+}
+
+/// Aplica el mensaje e imprime lo que corresponda.
+/// Devuelve `true` si el mensaje responde a una acción (estado nuevo o error).
+fn handle_server_message(state: &mut ClientState, message: ServerMessage) -> bool {
+    let answers_action = matches!(
+        message,
+        ServerMessage::Snapshot { .. } | ServerMessage::Diff { .. } | ServerMessage::Error { .. }
+    );
+    match &message {
+        ServerMessage::Welcome { seat, game_id, token } => {
+            println!("Sentado como {:?} en la partida {}.", seat, game_id);
+            println!("Para volver si se corta la conexión: --token {}", token);
+        }
+        ServerMessage::Spectating { game_id, mode } => match mode {
+            SpectatorMode::Public => println!("Mirando la partida {}.", game_id),
+            SpectatorMode::Omniscient { delay_turns } => {
+                println!("Comentando la partida {} con {} turnos de retraso.", game_id, delay_turns)
+            }
+        },
+        ServerMessage::LobbyUpdate { game } if !game.started => {
+            let taken = game.seats.iter().filter(|s| s.taken).count();
+            let ready = game.seats.iter().filter(|s| s.ready).count();
+            println!(
+                "Sala '{}': {}/{} asientos ocupados, {} listos.",
+                game.options.name,
+                taken,
+                game.seats.len(),
+                ready
+            );
+        }
+        ServerMessage::Error { message } => println!("Servidor: {}", message),
+        _ => {}
+    }
+    for event in state.apply(message) {
+        println!("{}", event);
+    }
+    answers_action
+}
+
+// -----------------------------------------------------------------------------
+// SALA DE ESPERA
+// -----------------------------------------------------------------------------
+
+/// Menú de la sala hasta sentarse en una partida y marcarse listo.
+/// Devuelve `false` si el jugador sale o se corta la conexión.
+fn lobby_menu(conn: &mut ServerConnection, state: &mut ClientState) -> bool {
+    while state.seat.is_none() {
+        if conn.list_games().is_err() || !wait_for(conn, state, |m| matches!(m, ServerMessage::GameList { .. })) {
+            return false;
+        }
+        print_game_list(state);
+
+        println!("Sala: (c)rear partida, (u)nirse, (a)ctualizar, (s)alir");
+        let sent = match read_line_prompt("Sala>").trim() {
+            "c" => conn.create_game(read_game_options()),
+            "u" => {
+                let game_id = read_u32("Número de partida:");
+                let seat = match read_line_prompt("Asiento (1-4, Enter = el primero libre):").trim() {
+                    "1" => Some(PlayerType::Player1),
+                    "2" => Some(PlayerType::Player2),
+                    "3" => Some(PlayerType::Player3),
+                    "4" => Some(PlayerType::Player4),
+                    _ => None,
+                };
+                conn.join_game(game_id, seat)
+            }
+            "s" => return false,
+            _ => continue,
+        };
+        let answered = |m: &ServerMessage| {
+            matches!(m, ServerMessage::Welcome { .. } | ServerMessage::Error { .. })
+        };
+        if sent.is_err() || !wait_for(conn, state, answered) {
+            return false;
+        }
+    }
+
+    read_line_prompt("Presiona Enter cuando estés listo para empezar...");
+    conn.set_ready(true).is_ok()
+}
+
+/// Procesa mensajes hasta que llega uno que cumpla `done`.
+fn wait_for(conn: &mut ServerConnection, state: &mut ClientState, done: impl Fn(&ServerMessage) -> bool) -> bool {
+    loop {
+        let Ok(message) = conn.incoming.recv() else {
+            println!("El servidor cerró la conexión.");
+            return false;
+        };
+        let finished = done(&message);
+        handle_server_message(state, message);
+        if finished {
+            return true;
+        }
+    }
+}
+
+fn print_game_list(state: &ClientState) {
+    println!("\n--- PARTIDAS ---");
+    if state.games.is_empty() {
+        println!("  (no hay partidas; crea una)");
+    }
+    for game in &state.games {
+        let free = game.seats.iter().filter(|s| !s.taken).count();
+        let status = if game.finished {
+            "terminada".to_string()
+        } else if game.started {
+            "en juego".to_string()
+        } else {
+            format!("{} asientos libres", free)
+        };
+        println!(
+            "  [{}] {} - {} jugadores ({} bots), a {} puntos, mapa {:?}: {}",
+            game.id,
+            game.options.name,
+            game.options.players,
+            game.options.bots,
+            game.options.rules.vp_target,
+            game.options.map,
+            status
+        );
+        let house_rules = game.options.rules.house_rules();
+        if !house_rules.is_empty() {
+            println!("      Reglas de la casa: {}", house_rules.join(", "));
+        }
+    }
+}
+
+fn read_game_options() -> GameOptions {
+    let defaults = GameOptions::default();
+    let name = read_line_prompt("Nombre de la partida:").trim().to_string();
+    let players = read_u8("Jugadores (2-4):") as usize;
+    let bots = read_u8("¿Cuántos bots?:") as usize;
+    let mut rules = read_rules_file();
+    if read_line_prompt("¿Ciudades y Caballeros? (s/n):").trim().eq_ignore_ascii_case("s") {
+        rules = rules.with_cities_and_knights();
+    }
+    match read_line_prompt("Escenario (p)escadores, (r)íos o Enter para ninguno:").trim() {
+        "p" => rules = rules.with_plugin(ScenarioPlugin::Fishermen),
+        "r" => rules = rules.with_plugin(ScenarioPlugin::Rivers),
+        _ => {}
+    }
+    let vp_target = read_u8(&format!("Puntos para ganar (0 = {}):", rules.vp_target));
+    if vp_target > 0 {
+        rules.vp_target = vp_target;
+    }
+    rules.dice = match read_line_prompt("Dados (n)ormales, (m)azo, (e)quilibrados o (f)ísicos:").trim() {
+        "m" => DiceMode::Deck,
+        "e" => DiceMode::Balanced,
+        "f" => DiceMode::Manual,
+        _ => rules.dice,
+    };
+    let map = match read_line_prompt("Mapa (a)leatorio, (f)ijo, (n)uevas costas o (c)uatro islas:").trim() {
+        "f" => MapLayout::Fixed,
+        "n" => MapLayout::Seafarers(Scenario::NewShores),
+        "c" => MapLayout::Seafarers(Scenario::FourIslands),
+        _ => MapLayout::Random,
+    };
+    let limit = |n: u8| (n > 0).then_some(n as u32);
+    let clocks = ClockConfig {
+        turn_secs: limit(read_u8("Segundos por turno (0 = sin límite):")),
+        game_secs: limit(read_u8("Reserva por jugador en minutos (0 = sin límite):")).map(|m| m * 60),
+        decision_secs: limit(read_u8("Segundos para descartar (0 = sin límite):")),
+    };
+    GameOptions {
+        name: if name.is_empty() { defaults.name } else { name },
+        players,
+        bots,
+        map,
+        rules,
+        clocks,
+    }
+}
+
+/// Reglas desde un JSON elegido por el jugador; Enter para las normales.
+fn read_rules_file() -> RuleSet {
+    loop {
+        let path = read_line_prompt("Archivo de reglas (Enter = normales):");
+        let path = path.trim();
+        if path.is_empty() {
+            return RuleSet::default();
+        }
+        match RuleSet::load(path) {
+            Ok(rules) => return rules,
+            Err(e) => println!("No se pudieron leer las reglas: {}", e),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// PREGUNTAS AL JUGADOR
+// -----------------------------------------------------------------------------
+
+/// Pregunta qué hacer según la fase. `None` = nada que mandar (p. ej. ver el tablero,
+/// o se acabó el tiempo y el servidor ya juega por nosotros).
+fn prompt_action(
+    snapshot: &PlayerView,
+    seat: PlayerType,
+    counter: Option<&CardCounter>,
+    deadline: Option<Instant>,
+) -> Option<Action> {
+    let clock = |prompt: &str| match deadline {
+        Some(d) => format!("[{}s] {}", d.saturating_duration_since(Instant::now()).as_secs(), prompt),
+        None => prompt.to_string(),
+    };
+    let board = &snapshot.board;
+    // responder una oferta abierta va antes que cualquier otra cosa
+    if let Some(trade) = snapshot.trade.as_ref().filter(|_| snapshot.is_waiting_on(seat)) {
+        println!("{:?} te ofrece {:?} a cambio de {:?}.", trade.from, trade.offer.give, trade.offer.get);
+        let input = read_line_timed(&clock("¿Aceptar? (s/n):"), deadline)?;
+        return Some(Action::RespondTrade { accept: input.trim().eq_ignore_ascii_case("s") });
+    }
+    match &snapshot.phase {
+        GamePhase::Setup { anchor_vertex: None, .. } => {
+            print_visual_board(board);
+            print_player_status(board, seat);
+            let pos = read_u8("Vértice (##) para el asentamiento:");
+            Some(Action::PlaceSettlement { vertex: pos as usize })
+        }
+        GamePhase::Setup { anchor_vertex: Some(anchor), .. } => {
+            print_visual_board(board);
+            let phase = TurnPhase::Setup { anchor_vertex: *anchor };
+            print_buildable_roads(board, seat, phase);
+            let pos = read_u8("Borde (##) para el camino (adyacente):");
+            Some(Action::PlaceRoad { edge: pos as usize })
+        }
+        GamePhase::Roll => {
+            print_remote_status(snapshot);
+            print_player_status(board, seat);
+            let input = read_line_timed(&clock("Presiona Enter para tirar los dados ((j)ugar caballero antes)..."), deadline)?;
+            if input.trim().eq_ignore_ascii_case("j") {
+                read_knight_action(board, seat)
+            } else if board.rules.dice == DiceMode::Manual {
+                read_physical_dice_action()
+            } else {
+                Some(Action::RollDice)
+            }
+        }
+        GamePhase::Discard { .. } => read_discard_action(board, seat),
+        GamePhase::GoldChoice { pending } => {
+            let &(_, amount) = pending.iter().find(|&&(p, _)| p == seat)?;
+            read_gold_action(seat, amount)
+        }
+        GamePhase::MoveRobber => {
+            print_visual_board(board);
+            println!("{:?}, debes mover al ladrón.", seat);
+            let (tile, victim) = read_robber_target(board, seat);
+            Some(Action::MoveRobber { tile, victim })
+        }
+        GamePhase::Main => {
+            print_player_status(board, seat);
+            println!("Acciones: (c)onstruir, (t)erminar, (i)ntercambiar, (j)ugar carta, (v)er tablero");
+            let input = read_line_timed(&clock(">"), deadline)?;
+            match Command::parse(&input) {
+                Some(Command::Build) => read_build_action(board, seat),
+                Some(Command::EndTurn) => Some(Action::EndTurn),
+                Some(Command::Trade) => read_trade_action(board),
+                Some(Command::PlayCard) => read_play_action(board, seat),
+                Some(Command::ShowBoard) => {
+                    print_visual_board(board);
+                    print_remote_status(snapshot);
+                    if let Some(counter) = counter {
+                        print_card_counts(snapshot, counter);
+                    }
+                    None
+                }
+                Some(Command::Undo | Command::Redo) => {
+                    println!("En red no se puede deshacer: el servidor ya aplicó la acción.");
+                    None
+                }
+                None => {
+                    println!("Comando no reconocido.");
+                    None
+                }
+            }
+        }
+        GamePhase::Finished { .. } => None,
+    }
+}
+
+fn read_build_action(board: &Board, seat: PlayerType) -> Option<Action> {
+    print_visual_board(board);
+    println!("¿Qué construir? [c]asa, [i]udad, [r]uta, [b]arco, [m]over barco, [d]esarrollo, [v]olver");
+    if board.rules.cities_and_knights {
+        println!("Ciudades y Caballeros: [k] caballero, [a]ctivar, [p]romover, [e] mejora de ciudad");
+    }
+    let cmd = read_line_prompt("Construir>");
+
+    match cmd.trim() {
+        "c" => Some(Action::PlaceSettlement { vertex: read_u8("Vértice (##) para la casa:") as usize }),
+        "b" => Some(Action::PlaceShip { edge: read_u8("Borde (##) para el barco:") as usize }),
+        "m" => {
+            let from = read_u8("Borde (##) del barco a mover:") as usize;
+            let to = read_u8("Borde (##) de destino:") as usize;
+            Some(Action::MoveShip { from, to })
+        }
+        "i" => Some(Action::PlaceCity { vertex: read_u8("Vértice (##) para la ciudad:") as usize }),
+        "r" => {
+            print_buildable_roads(board, seat, TurnPhase::Normal);
+            Some(Action::PlaceRoad { edge: read_u8("Borde (##) para la ruta:") as usize })
+        }
+        "k" => Some(Action::BuildKnight { vertex: read_u8("Vértice (##) para el caballero:") as usize }),
+        "a" => Some(Action::ActivateKnight { vertex: read_u8("Vértice (##) del caballero a activar:") as usize }),
+        "p" => Some(Action::PromoteKnight { vertex: read_u8("Vértice (##) del caballero a promover:") as usize }),
+        "e" => {
+            let track = read_track("Rama [c]omercio, [p]olítica, [i] ciencia:")?;
+            Some(Action::BuildImprovement { track })
+        }
+        "d" => Some(Action::BuyDevCard),
+        _ => None,
+    }
+}
+
+/// Dados físicos: el servidor valida lo que se carga.
+fn read_physical_dice_action() -> Option<Action> {
+    let input = read_line_prompt("Dados de la mesa (blanco y rojo, p. ej. 3 5):");
+    let values: Vec<u8> = input.split_whitespace().filter_map(|n| n.parse().ok()).collect();
+    match values[..] {
+        [first, second] => Some(Action::EnterDice { first, second }),
+        _ => {
+            println!("Entrada inválida. Escribe los dos dados separados por un espacio.");
+            None
+        }
+    }
+}
+
+fn read_trade_action(board: &Board) -> Option<Action> {
+    if !available_exchanges(board).is_empty()
+        && read_line_prompt("¿Canjear fichas del escenario? (s/n):").trim().eq_ignore_ascii_case("s")
+    {
+        let (token, reward) = read_token_exchange(board)?;
+        return Some(Action::ExchangeTokens { token, reward });
+    }
+    println!("Comercio con el Banco.");
+    let give = read_material_type("Material a entregar:");
+    let get = read_material_type("Material a recibir:");
+    match (give, get) {
+        (Some(give), Some(get)) => Some(Action::BankTrade { give, get }),
+        _ => {
+            println!("Material(es) no válidos. Cancelando.");
+            None
+        }
+    }
+}
+
+fn read_play_action(board: &Board, seat: PlayerType) -> Option<Action> {
+    if board.rules.cities_and_knights {
+        return read_progress_play(board, seat).map(|play| Action::PlayProgress { play });
+    }
+    println!("¿Qué carta jugar? [c]aballero, [r]utas, [a]bundancia, [m]onopolio, [v]olver");
+    let cmd = read_line_prompt("Jugar>");
+
+    match cmd.trim() {
+        "c" => read_knight_action(board, seat),
+        "r" => {
+            print_visual_board(board);
+            let first_edge = read_u8("Posición de la primera ruta:") as usize;
+            let second_edge = read_u8("Posición de la segunda ruta:") as usize;
+            Some(Action::PlayRoadBuilding { first_edge, second_edge })
+        }
+        "a" => {
+            let first = read_material_type("Primer recurso a tomar:")?;
+            let second = read_material_type("Segundo recurso a tomar:")?;
+            Some(Action::PlayYearOfPlenty { first, second })
+        }
+        "m" => {
+            let material = read_material_type("Recurso a monopolizar:")?;
+            Some(Action::PlayMonopoly { material })
+        }
+        _ => None,
+    }
+}
+
+fn read_knight_action(board: &Board, seat: PlayerType) -> Option<Action> {
+    print_visual_board(board);
+    println!("Mover al ladrón.");
+    let (tile, victim) = read_robber_target(board, seat);
+    Some(Action::PlayKnight { tile, victim })
+}
+
+/// Casilla para el ladrón y, si hay alguien ahí, a quién robarle.
+fn read_robber_target(board: &Board, seat: PlayerType) -> (TileId, Option<PlayerType>) {
+    let tile = read_u8("Casilla (##) a mover:") as usize;
+    if tile >= board.tiles.len() {
+        return (tile, None); // el servidor responde con el error
+    }
+    (tile, read_player_to_rob(board, tile, seat))
+}
+
+fn read_discard_action(board: &Board, seat: PlayerType) -> Option<Action> {
+    let player = board.players.iter().find(|p| p.id == seat)?;
+    let to_discard = cards_to_discard(&board.rules, player);
+    let mut remaining = player.resources.clone();
+
+    println!("\n{:?} debe descartar {} cartas.", seat, to_discard);
+    let mut materials = Vec::new();
+    while materials.len() < to_discard as usize {
+        println!("  Recursos: {}", format_resources(&remaining));
+        let prompt = format!("Material a descartar ({}/{}):", materials.len() + 1, to_discard);
+        if let Some(material) = read_material_type(&prompt) {
+            match remaining.get_mut(&material) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    materials.push(material);
+                }
+                _ => println!("No tienes ese material."),
+            }
+        }
+    }
+    Some(Action::Discard { materials })
+}
+
+fn read_gold_action(seat: PlayerType, amount: u8) -> Option<Action> {
+    println!("\n{:?} elige {} recursos del banco.", seat, amount);
+    let mut materials = Vec::new();
+    while materials.len() < amount as usize {
+        let prompt = format!("Recurso a elegir ({}/{}):", materials.len() + 1, amount);
+        if let Some(material) = read_material_type(&prompt) {
+            materials.push(material);
+        }
+    }
+    Some(Action::ChooseGold { materials })
+}
+
+// -----------------------------------------------------------------------------
+// VISTA
+// -----------------------------------------------------------------------------
+
+/// Lo que se deduce de las manos rivales contando cartas.
+pub fn print_card_counts(snapshot: &PlayerView, counter: &CardCounter) {
+    println!("  Cartas de los rivales (deducidas):");
+    for player in snapshot.turn_order.iter().filter(|&&p| Some(p) != snapshot.viewer) {
+        println!(
+            "    - {:?} ({} cartas, {} manos posibles): {}",
+            player,
+            snapshot.hand_size(*player),
+            counter.possible_hands(*player),
+            format_ranges(counter, *player)
+        );
+    }
+}
+
+/// "Wood: 1, Wheat: 0-2", sin los recursos que seguro no tiene.
+pub fn format_ranges(counter: &CardCounter, player: PlayerType) -> String {
+    let parts: Vec<String> = counter
+        .ranges(player)
+        .into_iter()
+        .filter(|(_, r)| r.max > 0)
+        .map(|(m, r)| {
+            if r.is_certain() {
+                format!("{:?}: {}", m, r.min)
+            } else {
+                format!("{:?}: {}-{}", m, r.min, r.max)
+            }
+        })
+        .collect();
+    if parts.is_empty() { "Ninguno".to_string() } else { parts.join(", ") }
+}
+
+/// Como `print_global_status`, pero con lo que el servidor deja ver de los rivales
+/// (o todas las manos, si la vista es de comentarista).
+pub fn print_remote_status(snapshot: &PlayerView) {
+    let board = &snapshot.board;
+    println!("\n=========================================");
+    println!("==       ESTADO DEL JUEGO (en red)      ==");
+    println!("=========================================");
+    println!("  Turno {}: {:?}", snapshot.turn_number, snapshot.current_player);
+    if let Some(roll) = snapshot.last_roll {
+        println!("  Última tirada: {}", roll);
+    }
+    let robber_pos = snapshot.robber_tile().unwrap_or(99);
+    println!("  Ladrón: Casilla {}", robber_pos);
+    println!("---");
+    match board.largest_army {
+        Some(p) => println!("  Mayor Ejército:   {:?} ({} caballeros)", p, board.largest_army_size),
+        None => println!("  Mayor Ejército:   Nadie (se necesita > {})", board.largest_army_size),
+    }
+    match board.longest_road {
+        Some(p) => println!("  Camino Más Largo: {:?} ({} segmentos)", p, board.longest_road_size),
+        None => println!("  Camino Más Largo: Nadie (se necesita > {})", board.longest_road_size),
+    }
+    println!("---");
+    println!("  Cartas de Desarrollo Restantes: {}", snapshot.deck_size);
+    println!("---");
+    println!("  Resumen de Jugadores:");
+    for player in &board.players {
+        println!(
+            "    - {:?}: {} VP, {} Recursos, {} Cartas Dev.",
+            player.id,
+            player.victory_points,
+            snapshot.hand_size(player.id),
+            snapshot.dev_card_count(player.id)
+        );
+        if snapshot.omniscient {
+            println!("        Recursos: {}", format_resources(&player.resources));
+            println!("        Cartas: {:?}", player.dev_cards);
+        }
+    }
+    let known: Vec<String> = snapshot
+        .known_steals
+        .iter()
+        .filter_map(|s| s.material.map(|m| format!("{:?} le robó {:?} a {:?}", s.thief, m, s.victim)))
+        .collect();
+    if !known.is_empty() {
+        println!("---");
+        println!("  Robos que conoces:");
+        for line in known {
+            println!("    - {}", line);
+        }
+    }
+    println!("=========================================\n");
+}
@@ -3,17 +3,37 @@
 use crate::types::*;
 use crate::game_logic::*;
 use crate::development_cards::*;
+use crate::engine::{BoardView, ClockConfig, Clocks};
+use crate::stats::*;
+use crate::dice::DiceMode;
+use crate::history::{reveals_hidden_info, History};
 use std::collections::HashMap;
 use std::io::{stdin, stdout, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 use rand::rng; // <-- en tu warning decía "Renamed to `rng`"
 use rand::Rng; // lo seguimos usando para random_range
+use rand::seq::IndexedRandom;
+
+const STATS_FILE: &str = "estadisticas_partida.json";
 
 // -----------------------------------------------------------------------------
 // INICIO DEL JUEGO
 // -----------------------------------------------------------------------------
 
 pub fn start_game(board: &mut Board) {
+    start_game_with_clocks(board, ClockConfig::default());
+}
+
+/// Como `start_game`, con límites de tiempo por turno, por partida y para descartar.
+pub fn start_game_with_clocks(board: &mut Board, clocks: ClockConfig) {
     println!("¡Bienvenido a Catan en Consola!");
+    let house_rules = board.rules.house_rules();
+    if !house_rules.is_empty() {
+        println!("Reglas de la casa: {}", house_rules.join(", "));
+    }
     let player_ids = setup_players(board);
     if player_ids.is_empty() {
         return;
@@ -26,7 +46,26 @@ pub fn start_game(board: &mut Board) {
     run_setup_phase(board, &player_ids);
 
     // fase normal
-    run_main_loop(board, &player_ids);
+    let mut stats = GameStats::new(&player_ids);
+    let mut clocks = Clocks::new(clocks, &player_ids);
+    run_main_loop(board, &player_ids, &mut stats, &mut clocks);
+}
+
+// -----------------------------------------------------------------------------
+// RELOJES
+// -----------------------------------------------------------------------------
+
+// Como el modo local no corre sobre un `Game`, los `Clocks` del motor se
+// cobran acá con la hora real: el turno con lo que tarda el jugador de
+// turno y la decisión con cada descarte o elección de oro.
+
+/// Pregunta mostrando el tiempo que queda y sin esperar más que eso. `None` = se acabó.
+fn prompt_clocked(left: Option<Duration>, prompt: &str) -> Option<String> {
+    match left {
+        Some(left) if left.is_zero() => None,
+        Some(left) => read_line_timed(&format!("[{}s] {}", left.as_secs(), prompt), Some(Instant::now() + left)),
+        None => read_line_timed(prompt, None),
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -46,7 +85,7 @@ fn setup_players(board: &mut Board) -> Vec<PlayerType> {
                         2 => PlayerType::Player3,
                         _ => PlayerType::Player4,
                     };
-                    board.players.push(Player::new(next_id));
+                    board.players.push(Player::with_rules(next_id, &board.rules));
                     player_ids.push(next_id);
                     println!("Jugador {:?} añadido.", next_id);
                 }
@@ -69,7 +108,10 @@ fn run_setup_phase(board: &mut Board, player_ids: &[PlayerType]) {
 
     for &player_id in player_ids {
         let house_pos = run_single_setup_turn(board, player_id);
-        // en el primer turno NO se dan recursos
+        // en el primer turno NO se dan recursos, salvo que las reglas digan otra cosa
+        if board.rules.starting_resources_from_both {
+            give_starting_resources(board, player_id, house_pos);
+        }
         _first_houses.insert(player_id, house_pos);
     }
 
@@ -77,6 +119,12 @@ fn run_setup_phase(board: &mut Board, player_ids: &[PlayerType]) {
     println!("\n--- FASE DE FUNDACIÓN (TURNO 2 - Inverso) ---");
     for &player_id in player_ids.iter().rev() {
         let house_pos = run_single_setup_turn(board, player_id);
+        // con Ciudades y Caballeros la segunda pieza es una ciudad
+        if board.rules.cities_and_knights {
+            if let Err(msg) = found_city(board, player_id, house_pos) {
+                println!("{}", msg);
+            }
+        }
         // en el segundo turno SÍ se dan recursos
         give_starting_resources(board, player_id, house_pos);
     }
@@ -129,64 +177,140 @@ fn run_single_setup_turn(board: &mut Board, player_id: PlayerType) -> usize {
 // BUCLE PRINCIPAL
 // -----------------------------------------------------------------------------
 
-fn run_main_loop(board: &mut Board, player_ids: &[PlayerType]) {
+fn run_main_loop(board: &mut Board, player_ids: &[PlayerType], stats: &mut GameStats, clocks: &mut Clocks) {
+    let mut round: u32 = 0;
     'game_loop: loop {
+        round += 1;
         for &player_id in player_ids {
             // al inicio del turno del jugador
             if let Some(p) = board.players.iter_mut().find(|p| p.id == player_id) {
-                p.played_dev_card_this_turn = false;
+                p.dev_cards_played_this_turn = 0;
+                reset_ship_turn(p);
             }
 
-            print_global_status(board);
+            print_global_status(&BoardView::new(board, Some(player_id)));
             print_player_status(board, player_id);
 
-            read_line_prompt("Presiona Enter para tirar los dados...");
-            let roll = roll_dice();
+            clocks.start_turn();
+            let physical = board.rules.dice == DiceMode::Manual;
+            let prompt = if physical { "Tira los dados de la mesa y presiona Enter..." } else { "Presiona Enter para tirar los dados..." };
+            let asked = Instant::now();
+            let timed_out = prompt_clocked(clocks.time_left(player_id), prompt).is_none();
+            clocks.charge_turn(player_id, asked.elapsed());
+            if timed_out {
+                println!("¡Se acabó el tiempo! Los dados se tiran solos.");
+            }
+            let (roll, red) = roll_dice(board, round, physical && !timed_out);
             println!("¡Has sacado un {}!", roll);
+            stats.record_roll(player_id, roll);
+
+            // el dado de eventos se resuelve antes que la producción
+            let event = board.rules.cities_and_knights.then(roll_event_die);
+            if let Some(face) = event {
+                println!("Dado de eventos: {:?} (dado rojo: {})", face, red);
+                if face == EventDie::Barbarians {
+                    handle_barbarians(board);
+                }
+            }
 
             if roll == 7 {
-                handle_seven_roll(board, player_id);
+                handle_seven_roll(board, player_id, stats, clocks);
             } else {
-                give_materials_on_roll(board, roll);
+                let before = snapshot_hands(board);
+                let payouts = give_materials_on_roll(board, roll);
+                handle_gold(board, &choices_owed(board, &payouts), clocks);
+                stats.record_gains(&before, board, GainSource::Production);
+            }
+
+            if let Some(EventDie::Gate(track)) = event {
+                for (player, card) in draw_progress_cards(board, track, red) {
+                    println!("{:?} saca una carta de progreso de {:?}.", player, track);
+                    if player == player_id {
+                        println!("  Es {:?}.", card);
+                    }
+                }
+            }
+            if let Some(winner) = check_for_winner(board) {
+                finish_game(board, stats, winner);
+                break 'game_loop;
             }
 
-            // ahora el jugador puede hacer acciones
+            // ahora el jugador puede hacer acciones; lo de antes de los dados no se deshace
+            let mut history: TurnHistory = History::new();
+            let mut last = Instant::now();
             loop {
                 print_player_status(board, player_id);
-                println!("Acciones: (c)onstruir, (t)erminar, (i)ntercambiar, (j)ugar carta, (v)er tablero");
-
-                let input = read_line_prompt(">");
+                println!("Acciones: (c)onstruir, (t)erminar, (i)ntercambiar, (j)ugar carta, (v)er tablero, (d)eshacer, (r)ehacer");
+
+                // se cobra lo de la pregunta anterior y sus submenús
+                clocks.charge_turn(player_id, last.elapsed());
+                let answer = prompt_clocked(clocks.time_left(player_id), ">");
+                last = Instant::now();
+                let Some(input) = answer else {
+                    println!("¡Se acabó el tiempo de {:?}! Termina el turno.", player_id);
+                    run_turn_end_hooks(board, player_id);
+                    stats.end_turn(board);
+                    break;
+                };
                 let command = Command::parse(&input);
+                let before = (board.clone(), stats.clone());
 
                 match command {
                     Some(Command::Build) => {
-                        if let Some(winner) = handle_build_cmd(board, player_id) {
-                            print_global_status(board);
-                            println!("¡Ganó {:?}!", winner);
+                        if let Some(winner) = handle_build_cmd(board, player_id, stats) {
+                            finish_game(board, stats, winner);
                             break 'game_loop;
                         }
                     }
                     Some(Command::EndTurn) => {
                         // pasa al siguiente jugador
+                        run_turn_end_hooks(board, player_id);
+                        stats.end_turn(board);
                         break;
                     }
                     Some(Command::Trade) => {
-                        handle_trade_cmd(board, player_id);
+                        if let Some(winner) = handle_trade_cmd(board, player_id, stats) {
+                            finish_game(board, stats, winner);
+                            break 'game_loop;
+                        }
                     }
                     Some(Command::PlayCard) => {
-                        if let Some(winner) = handle_play_cmd(board, player_id) {
-                            print_global_status(board);
-                            println!("¡Ganó {:?}!", winner);
+                        if let Some(winner) = handle_play_cmd(board, player_id, stats) {
+                            finish_game(board, stats, winner);
                             break 'game_loop;
                         }
                     }
                     Some(Command::ShowBoard) => {
                         print_visual_board(board);
                     }
+                    Some(Command::Undo) => {
+                        if step_history(&mut history, board, stats, History::undo) {
+                            println!("Acción deshecha.");
+                        } else {
+                            println!("No hay nada que deshacer en este turno.");
+                        }
+                        continue;
+                    }
+                    Some(Command::Redo) => {
+                        if step_history(&mut history, board, stats, History::redo) {
+                            println!("Acción rehecha.");
+                        } else {
+                            println!("No hay nada que rehacer.");
+                        }
+                        continue;
+                    }
                     None => {
                         println!("Comando no reconocido.");
                     }
                 }
+
+                if *board != before.0 {
+                    if reveals_hidden_info(&before.0, board, player_id) {
+                        history.seal();
+                    } else {
+                        history.record(before);
+                    }
+                }
             }
         }
     }
@@ -194,27 +318,62 @@ fn run_main_loop(board: &mut Board, player_ids: &[PlayerType]) {
     println!("¡Fin del juego!");
 }
 
+/// Tablero y estadísticas de antes de cada acción del turno.
+type TurnHistory = History<(Board, GameStats)>;
+
+/// Deshace o rehace un paso de `history` sobre el tablero y las estadísticas.
+fn step_history(
+    history: &mut TurnHistory,
+    board: &mut Board,
+    stats: &mut GameStats,
+    step: fn(&mut TurnHistory, &mut (Board, GameStats)) -> bool,
+) -> bool {
+    let mut state = (board.clone(), stats.clone());
+    if !step(history, &mut state) {
+        return false;
+    }
+    (*board, *stats) = state;
+    true
+}
+
+/// Muestra el estado final y el resumen de estadísticas, y las exporta a JSON.
+fn finish_game(board: &Board, stats: &mut GameStats, winner: PlayerType) {
+    print_global_status(&BoardView::revealed(board));
+    println!("¡Ganó {:?}!", winner);
+
+    stats.finish(board, winner);
+    stats.print_summary(board);
+    match stats.export_json(STATS_FILE) {
+        Ok(()) => println!("Estadísticas guardadas en {}", STATS_FILE),
+        Err(e) => println!("No se pudieron guardar las estadísticas: {}", e),
+    }
+}
+
 // -----------------------------------------------------------------------------
 // COMANDOS
 // -----------------------------------------------------------------------------
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Command {
+pub(crate) enum Command {
     Build,
     EndTurn,
     Trade,
     PlayCard,
     ShowBoard,
+    Undo,
+    Redo,
 }
 
 impl Command {
-    fn parse(s: &str) -> Option<Self> {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
         match s.trim() {
             "c" | "C" => Some(Command::Build),
             "t" | "T" => Some(Command::EndTurn),
             "i" | "I" => Some(Command::Trade),
             "j" | "J" => Some(Command::PlayCard),
             "v" | "V" => Some(Command::ShowBoard),
+            "d" | "D" | "deshacer" => Some(Command::Undo),
+            "r" | "R" | "rehacer" => Some(Command::Redo),
             _ => None,
         }
     }
@@ -224,23 +383,36 @@ impl Command {
 // HANDLERS (los tuyos, acomodados al loop nuevo)
 // -----------------------------------------------------------------------------
 
-fn handle_build_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerType> {
+fn handle_build_cmd(board: &mut Board, player_id: PlayerType, stats: &mut GameStats) -> Option<PlayerType> {
     print_visual_board(board);
-    println!("¿Qué construir? [c]asa, [i]udad, [r]uta, [d]esarrollo, [v]olver");
+    if board.edges.iter().any(|e| e.ship) || board.tiles.iter().any(|t| t.material == MaterialType::Sea) {
+        println!("¿Qué construir? [c]asa, [i]udad, [r]uta, [b]arco, [m]over barco, [d]esarrollo, [v]olver");
+    } else {
+        println!("¿Qué construir? [c]asa, [i]udad, [r]uta, [d]esarrollo, [v]olver");
+    }
+    if board.rules.cities_and_knights {
+        println!("Ciudades y Caballeros: [k] caballero, [a]ctivar, [p]romover, [e] mejora de ciudad");
+    }
     let cmd = read_line_prompt("Construir>");
 
     match cmd.trim() {
         "c" => {
             let pos = read_u8("Vértice (##) para la casa:");
             match place_house(board, player_id, pos as usize, false) {
-                Ok(winner) => return winner,
+                Ok(winner) => {
+                    stats.record_build(player_id, Some(BuildingType::Settlement));
+                    return winner;
+                }
                 Err(msg) => println!("{}", msg),
             }
         }
         "i" => {
             let pos = read_u8("Vértice (##) para la ciudad:");
             match place_city(board, player_id, pos as usize) {
-                Ok(winner) => return winner,
+                Ok(winner) => {
+                    stats.record_build(player_id, Some(BuildingType::City));
+                    return winner;
+                }
                 Err(msg) => println!("{}", msg),
             }
         }
@@ -250,13 +422,67 @@ fn handle_build_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTy
 
             let pos = read_u8("Borde (##) para la ruta:");
             match place_road(board, player_id, pos as usize, phase) {
+                Ok(winner) => {
+                    stats.record_build(player_id, None);
+                    return winner;
+                }
+                Err(msg) => println!("{}", msg),
+            }
+        }
+        "b" => {
+            let pos = read_u8("Borde (##) para el barco:");
+            match place_ship(board, player_id, pos as usize, false) {
+                Ok(winner) => return winner,
+                Err(msg) => println!("{}", msg),
+            }
+        }
+        "m" => {
+            let movable = movable_ships(board, player_id);
+            if movable.is_empty() {
+                println!("No tienes barcos para mover este turno.");
+                return None;
+            }
+            println!("Barcos que se pueden mover: {:?}", movable);
+            let from = read_u8("Borde (##) del barco a mover:");
+            let to = read_u8("Borde (##) de destino:");
+            match move_ship(board, player_id, from as usize, to as usize) {
                 Ok(winner) => return winner,
                 Err(msg) => println!("{}", msg),
             }
         }
+        "k" => {
+            let pos = read_u8("Vértice (##) para el caballero:");
+            if let Err(msg) = place_knight(board, player_id, pos as usize) {
+                println!("{}", msg);
+            }
+        }
+        "a" => {
+            let pos = read_u8("Vértice (##) del caballero a activar:");
+            match activate_knight(board, player_id, pos as usize, false) {
+                Ok(()) => println!("Caballero activado."),
+                Err(msg) => println!("{}", msg),
+            }
+        }
+        "p" => {
+            let pos = read_u8("Vértice (##) del caballero a promover:");
+            match promote_knight(board, player_id, pos as usize, false) {
+                Ok(level) => println!("Caballero ascendido a nivel {}.", level),
+                Err(msg) => println!("{}", msg),
+            }
+        }
+        "e" => {
+            let track = read_track("Rama [c]omercio, [p]olítica, [i] ciencia:")?;
+            match build_improvement(board, player_id, track) {
+                Ok(_) => return check_for_winner(board),
+                Err(msg) => println!("{}", msg),
+            }
+        }
         "d" => {
             match buy_development_card(board, player_id) {
-                Ok(winner) => return winner,
+                Ok(winner) => {
+                    stats.record_dev_card_bought(player_id);
+                    return winner;
+                }
                 Err(msg) => println!("{}", msg),
             }
         }
@@ -266,26 +492,65 @@ fn handle_build_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTy
     None
 }
 
-fn handle_trade_cmd(board: &mut Board, player_id: PlayerType) {
-    println!("¿Comerciar con quién? [b]anco, [j]ugador");
+fn handle_trade_cmd(board: &mut Board, player_id: PlayerType, stats: &mut GameStats) -> Option<PlayerType> {
+    if available_exchanges(board).is_empty() {
+        println!("¿Comerciar con quién? [b]anco, [j]ugador");
+    } else {
+        println!("¿Comerciar con quién? [b]anco, [j]ugador, [f]ichas del escenario");
+    }
     let cmd = read_line_prompt("Comercio>");
 
-    if cmd.trim() == "b" {
+    if cmd.trim() == "f" {
+        let (token, reward) = read_token_exchange(board)?;
+        let before = snapshot_hands(board);
+        match exchange_tokens(board, player_id, &token, reward) {
+            Ok(winner) => {
+                stats.record_gains(&before, board, GainSource::Trade);
+                return winner;
+            }
+            Err(msg) => println!("{}", msg),
+        }
+    } else if cmd.trim() == "b" {
         println!("Comercio con el Banco.");
         let give = read_material_type("Material a entregar:");
         let get = read_material_type("Material a recibir:");
 
         if let (Some(mat_give), Some(mat_get)) = (give, get) {
-            trade_with_bank(board, player_id, mat_give, mat_get);
+            let before = snapshot_hands(board);
+            if trade_with_bank(board, player_id, mat_give, mat_get) {
+                stats.record_gains(&before, board, GainSource::Trade);
+            }
         } else {
             println!("Material(es) no válidos. Cancelando.");
         }
     } else {
         println!("Comercio con jugadores (TODO: no implementado).");
     }
+    None
+}
+
+/// Elige uno de los canjes de fichas que ofrecen los escenarios de la partida.
+pub(crate) fn read_token_exchange(board: &Board) -> Option<(String, TokenReward)> {
+    let exchanges = available_exchanges(board);
+    for (i, exchange) in exchanges.iter().enumerate() {
+        println!("  [{}] {} de {} -> {:?}", i, exchange.cost, exchange.token, exchange.reward);
+    }
+    let Some(exchange) = exchanges.get(read_u8("Canje (#):") as usize) else {
+        println!("Canje inválido. Cancelando.");
+        return None;
+    };
+    let reward = match exchange.reward {
+        RewardKind::Resource => TokenReward::Resource(read_material_type("Recurso a recibir:")?),
+        RewardKind::Road => TokenReward::Road(read_u8("Borde (##) para el camino:") as usize),
+        RewardKind::RemoveRobber => TokenReward::RemoveRobber,
+    };
+    Some((exchange.token.to_string(), reward))
 }
 
-fn handle_play_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerType> {
+fn handle_play_cmd(board: &mut Board, player_id: PlayerType, stats: &mut GameStats) -> Option<PlayerType> {
+    if board.rules.cities_and_knights {
+        return handle_progress_cmd(board, player_id, stats);
+    }
     println!("¿Qué carta jugar? [c]aballero, [r]utas, [a]bundancia, [m]onopolio, [v]olver");
     let cmd = read_line_prompt("Jugar>");
 
@@ -297,7 +562,15 @@ fn handle_play_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTyp
             let target_player = read_player_to_rob(board, tile_pos as usize, player_id);
 
             if let Some(target) = target_player {
-                play_knight_card(board, player_id, tile_pos as usize, target)
+                let before = snapshot_hands(board);
+                let cards_before = dev_card_count(board, player_id);
+                let winner = play_knight_card(board, player_id, tile_pos as usize, Some(target));
+                if dev_card_count(board, player_id) < cards_before {
+                    stats.record_dev_card_played(player_id, DevelopmentCard::Knight);
+                    stats.record_gains(&before, board, GainSource::Robbery);
+                    stats.record_losses(&before, board, LossCause::Robber);
+                }
+                winner
             } else {
                 println!("Robo cancelado.");
                 None
@@ -309,7 +582,12 @@ fn handle_play_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTyp
             let edge1 = read_u8("Posición de la primera ruta:");
             let edge2 = read_u8("Posición de la segunda ruta:");
             match play_road_building_card(board, player_id, edge1 as usize, edge2 as usize) {
-                Ok(winner) => return winner,
+                Ok(winner) => {
+                    stats.record_dev_card_played(player_id, DevelopmentCard::RoadBuilding);
+                    stats.record_build(player_id, None);
+                    stats.record_build(player_id, None);
+                    return winner;
+                }
                 Err(msg) => println!("{}", msg),
             }
             None
@@ -319,7 +597,11 @@ fn handle_play_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTyp
             let mat1 = read_material_type("Primer recurso a tomar:");
             let mat2 = read_material_type("Segundo recurso a tomar:");
             if let (Some(m1), Some(m2)) = (mat1, mat2) {
-                play_year_of_plenty_card(board, player_id, m1, m2);
+                let before = snapshot_hands(board);
+                if play_year_of_plenty_card(board, player_id, m1, m2) {
+                    stats.record_dev_card_played(player_id, DevelopmentCard::YearOfPlenty);
+                    stats.record_gains(&before, board, GainSource::DevCard);
+                }
             } else {
                 println!("Material(es) no válidos. Cancelando.");
             }
@@ -329,7 +611,12 @@ fn handle_play_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTyp
             println!("Jugar 'Monopolio'.");
             let mat = read_material_type("Recurso a monopolizar:");
             if let Some(m) = mat {
-                play_monopoly_card(board, player_id, m);
+                let before = snapshot_hands(board);
+                if play_monopoly_card(board, player_id, m) {
+                    stats.record_dev_card_played(player_id, DevelopmentCard::Monopoly);
+                    stats.record_gains(&before, board, GainSource::DevCard);
+                    stats.record_losses(&before, board, LossCause::Monopoly);
+                }
             } else {
                 println!("Material no válido. Cancelando.");
             }
@@ -339,22 +626,246 @@ fn handle_play_cmd(board: &mut Board, player_id: PlayerType) -> Option<PlayerTyp
     }
 }
 
-fn handle_seven_roll(board: &mut Board, player_id: PlayerType) {
+/// Con Ciudades y Caballeros se juegan cartas de progreso en vez de desarrollo.
+fn handle_progress_cmd(board: &mut Board, player_id: PlayerType, stats: &mut GameStats) -> Option<PlayerType> {
+    let play = read_progress_play(board, player_id)?;
+    let before = snapshot_hands(board);
+    match play_progress_card(board, player_id, play) {
+        Ok(outcome) => {
+            match play {
+                ProgressPlay::Bishop { .. } => {
+                    stats.record_gains(&before, board, GainSource::Robbery);
+                    stats.record_losses(&before, board, LossCause::Robber);
+                }
+                ProgressPlay::ResourceMonopoly { .. } | ProgressPlay::TradeMonopoly { .. } => {
+                    stats.record_gains(&before, board, GainSource::DevCard);
+                    stats.record_losses(&before, board, LossCause::Monopoly);
+                }
+                _ => stats.record_gains(&before, board, GainSource::DevCard),
+            }
+            for (victim, material, amount) in outcome.taken {
+                println!("  {:?} entrega {} de {:?}.", victim, amount, material);
+            }
+            check_for_winner(board)
+        }
+        Err(msg) => {
+            println!("{}", msg);
+            None
+        }
+    }
+}
+
+/// Elige una carta de progreso de la mano y pregunta lo que necesita.
+pub(crate) fn read_progress_play(board: &Board, player_id: PlayerType) -> Option<ProgressPlay> {
+    let cards = board
+        .players
+        .iter()
+        .find(|p| p.id == player_id)
+        .map(|p| p.progress_cards.clone())
+        .unwrap_or_default();
+    let playable: Vec<ProgressCard> = cards.into_iter().filter(|c| !c.is_victory_point()).collect();
+    if playable.is_empty() {
+        println!("No tienes cartas de progreso para jugar.");
+        return None;
+    }
+    for (i, card) in playable.iter().enumerate() {
+        println!("  [{}] {:?}", i, card);
+    }
+    let Some(&card) = playable.get(read_u8("Carta (#) a jugar:") as usize) else {
+        println!("Carta inválida. Cancelando.");
+        return None;
+    };
+
+    let play = match card {
+        ProgressCard::Irrigation => ProgressPlay::Irrigation,
+        ProgressCard::Mining => ProgressPlay::Mining,
+        ProgressCard::Warlord => ProgressPlay::Warlord,
+        ProgressCard::RoadBuilding => {
+            print_visual_board(board);
+            let first_edge = read_u8("Posición de la primera ruta:") as usize;
+            let second_edge = read_u8("Posición de la segunda ruta:") as usize;
+            ProgressPlay::RoadBuilding { first_edge, second_edge }
+        }
+        ProgressCard::Smith => {
+            let first = read_u8("Vértice (##) del primer caballero:") as usize;
+            let second = read_u8("Vértice (##) del segundo caballero:") as usize;
+            ProgressPlay::Smith { first, second }
+        }
+        ProgressCard::ResourceMonopoly | ProgressCard::TradeMonopoly => {
+            let material = read_material_type("Material a monopolizar:")?;
+            match card {
+                ProgressCard::ResourceMonopoly => ProgressPlay::ResourceMonopoly { material },
+                _ => ProgressPlay::TradeMonopoly { material },
+            }
+        }
+        ProgressCard::Bishop => {
+            print_visual_board(board);
+            let tile = read_u8("Casilla (##) a mover:") as usize;
+            ProgressPlay::Bishop { tile }
+        }
+        ProgressCard::Printer | ProgressCard::Constitution => return None,
+    };
+    Some(play)
+}
+
+fn handle_seven_roll(board: &mut Board, player_id: PlayerType, stats: &mut GameStats, clocks: &mut Clocks) {
     println!("¡TODOS CON MÁS DE 7 CARTAS DEBEN DESCARTAR LA MITAD!");
-    // TODO: Implementar la lógica de descarte forzado.
+    let before = snapshot_hands(board);
+    handle_discards(board, clocks);
+    stats.record_losses(&before, board, LossCause::Discard);
+
+    if board.rules.cities_and_knights && board.barbarian_attacks == 0 {
+        println!("Los bárbaros todavía no llegaron: el ladrón se queda donde está.");
+        return;
+    }
+
+    if clocks.time_left(player_id).is_some_and(|left| left.is_zero()) {
+        auto_move_robber(board, player_id, stats);
+        return;
+    }
 
     print_visual_board(board);
     println!("\n{:?}, debes mover al ladrón.", player_id);
+    let asked = Instant::now();
     let tile_pos = read_u8("Casilla (##) a mover:");
     let target_player = read_player_to_rob(board, tile_pos as usize, player_id);
+    clocks.charge_turn(player_id, asked.elapsed());
 
     if let Some(target) = target_player {
+        let before = snapshot_hands(board);
         place_robber(board, player_id, tile_pos as usize, target);
+        stats.record_gains(&before, board, GainSource::Robbery);
+        stats.record_losses(&before, board, LossCause::Robber);
     } else {
         println!("Movimiento de ladrón cancelado o inválido.");
     }
 }
 
+/// Sin tiempo: el ladrón va a una casilla al azar donde pueda ir, como en el
+/// motor, y roba a alguno de los que se pueda robar ahí.
+fn auto_move_robber(board: &mut Board, player_id: PlayerType, stats: &mut GameStats) {
+    let candidates: Vec<TileId> = (0..board.tiles.len()).filter(|&t| is_new_robber_tile(board, t)).collect();
+    let Some(&tile) = candidates.choose(&mut rng()) else {
+        return;
+    };
+    println!("¡Se acabó el tiempo! El ladrón va solo a la casilla {}.", tile);
+
+    let victim = robbable_players(board, tile, player_id).choose(&mut rng()).copied();
+    let before = snapshot_hands(board);
+    match victim {
+        Some(victim) => place_robber(board, player_id, tile, victim),
+        None => {
+            let _ = move_robber(board, tile);
+        }
+    }
+    stats.record_gains(&before, board, GainSource::Robbery);
+    stats.record_losses(&before, board, LossCause::Robber);
+}
+
+/// Cada jugador con más cartas que el límite de las reglas elige, una por una, las que descarta.
+/// Si se le acaba el tiempo para decidir, el resto se descarta al azar.
+fn handle_discards(board: &mut Board, clocks: &mut Clocks) {
+    let player_ids: Vec<PlayerType> = board.players.iter().map(|p| p.id).collect();
+
+    for player_id in player_ids {
+        let to_discard = match board.players.iter().find(|p| p.id == player_id) {
+            Some(p) => cards_to_discard(&board.rules, p),
+            None => continue,
+        };
+        if to_discard == 0 {
+            continue;
+        }
+
+        println!("\n{:?} debe descartar {} cartas.", player_id, to_discard);
+        clocks.start_decision();
+        let mut discarded = 0;
+        while discarded < to_discard {
+            if let Some(p) = board.players.iter().find(|p| p.id == player_id) {
+                println!("  Recursos: {}", format_resources(&p.resources));
+            }
+            let prompt = format!("Material a descartar ({}/{}):", discarded + 1, to_discard);
+            let asked = Instant::now();
+            let answer = prompt_clocked(clocks.decision_left(), &prompt);
+            clocks.charge_decision(asked.elapsed());
+            let Some(input) = answer else {
+                println!("¡Se acabó el tiempo de {:?}! Descarta al azar.", player_id);
+                discard_randomly(board, player_id, to_discard - discarded);
+                break;
+            };
+            if let Some(material) = parse_material_type(&input) {
+                match discard_resource(board, player_id, material) {
+                    Ok(()) => discarded += 1,
+                    Err(msg) => println!("{}", msg),
+                }
+            }
+        }
+    }
+}
+
+/// Quienes produjeron oro (o cobran el acueducto) eligen, uno por uno, qué recursos llevarse.
+fn handle_gold(board: &mut Board, owed: &[(PlayerType, u8)], clocks: &mut Clocks) {
+    for &(player_id, amount) in owed {
+        println!("\n{:?} elige {} recursos del banco.", player_id, amount);
+        clocks.start_decision();
+        let mut materials = Vec::new();
+        while materials.len() < amount as usize {
+            let prompt = format!("Recurso a elegir ({}/{}):", materials.len() + 1, amount);
+            let asked = Instant::now();
+            let answer = prompt_clocked(clocks.decision_left(), &prompt);
+            clocks.charge_decision(asked.elapsed());
+            let Some(input) = answer else {
+                println!("¡Se acabó el tiempo de {:?}! Se elige al azar.", player_id);
+                while materials.len() < amount as usize {
+                    let resources = MaterialType::RESOURCES;
+                    materials.push(resources[rng().random_range(0..resources.len())]);
+                }
+                break;
+            };
+            if let Some(material) = parse_material_type(&input) {
+                materials.push(material);
+            }
+        }
+        if let Err(msg) = take_gold(board, player_id, &materials) {
+            println!("{}", msg);
+        }
+    }
+}
+
+fn discard_randomly(board: &mut Board, player_id: PlayerType, amount: u8) {
+    for _ in 0..amount {
+        let Some(player) = board.players.iter().find(|p| p.id == player_id) else {
+            return;
+        };
+        let held: Vec<MaterialType> = player.resources.iter().filter(|(_, &c)| c > 0).map(|(&m, _)| m).collect();
+        if held.is_empty() {
+            return;
+        }
+        let material = held[rng().random_range(0..held.len())];
+        let _ = discard_resource(board, player_id, material);
+    }
+}
+
+/// Los bárbaros avanzan un paso; si llegan, se cuenta cómo terminó el ataque.
+fn handle_barbarians(board: &mut Board) {
+    let Some(attack) = advance_barbarians(board) else {
+        println!("Los bárbaros avanzan: {}/{}.", board.barbarian_position, BARBARIAN_TRACK_LENGTH);
+        return;
+    };
+    println!("\n¡ATACAN LOS BÁRBAROS! Fuerza {} contra {} de los caballeros.", attack.strength, attack.defense);
+    if let Some(defender) = attack.defender {
+        println!("¡{:?} es Defensor de Catán (+1 VP)!", defender);
+    }
+    for (player, card) in &attack.rewarded {
+        match card {
+            Some(_) => println!("{:?} saca una carta de progreso por defender.", player),
+            None => println!("{:?} defendió, pero no quedaban cartas.", player),
+        }
+    }
+    for (player, vertex) in &attack.pillaged {
+        println!("¡Los bárbaros saquean la ciudad de {:?} en {}!", player, vertex);
+    }
+}
+
 // -----------------------------------------------------------------------------
 // VISTA / PRINTS (los que te faltaban)
 // -----------------------------------------------------------------------------
@@ -389,6 +900,9 @@ pub fn print_visual_board(board: &Board) {
         if tile.has_robber {
             return "ROBBER".to_string(); // 6 chars
         }
+        if tile.has_pirate {
+            return "PIRATE".to_string(); // 6 chars
+        }
         match tile.material {
             MaterialType::Wood   => "Wood  ".to_string(), // 6
             MaterialType::Brick  => "Brick ".to_string(), // 6
@@ -396,11 +910,15 @@ pub fn print_visual_board(board: &Board) {
             MaterialType::Wheat  => "Wheat ".to_string(), // 6
             MaterialType::Stone  => "Stone ".to_string(), // 6
             MaterialType::Dessert => "Desert".to_string(), // 6
+            MaterialType::Gold   => "Gold  ".to_string(), // 6
+            MaterialType::Sea    => " ~~~~ ".to_string(), // 6
+            // las mercancías no son casillas
+            MaterialType::Paper | MaterialType::Cloth | MaterialType::Coin => "  ??  ".to_string(),
         }
     }
 
     fn num(tile: &Tile) -> String {
-        if tile.has_robber || tile.material == MaterialType::Dessert {
+        if tile.has_robber || tile.number == 0 {
             "  --  ".to_string()
         } else {
             format!("  {:02}  ", tile.number)
@@ -444,6 +962,12 @@ pub fn print_visual_board(board: &Board) {
         [line1, line2, line3, line4, line5]
     }
 
+    // los mapas de escenario no entran en el dibujo fijo de 19 casillas
+    if board.tiles.len() != 19 {
+        print_tile_list(board);
+        return;
+    }
+
     println!();
     println!("======================== MAPA DEL TABLERO ========================");
     println!("Leyenda: P# = jugador | ## = vértice libre | número = ficha");
@@ -471,6 +995,48 @@ pub fn print_visual_board(board: &Board) {
 
 
 
+/// El tablero como lista de casillas, para mapas de cualquier forma.
+fn print_tile_list(board: &Board) {
+    println!();
+    println!("======================== MAPA DEL TABLERO ========================");
+    for (id, tile) in board.tiles.iter().enumerate() {
+        if tile.material == MaterialType::Sea && !tile.has_pirate {
+            continue;
+        }
+        let mut line = format!("  Casilla {:02}: {:?}", id, tile.material);
+        if tile.number > 0 {
+            line.push_str(&format!(" ({})", tile.number));
+        }
+        if tile.has_robber {
+            line.push_str(" [LADRÓN]");
+        }
+        if tile.has_pirate {
+            line.push_str(" [PIRATA]");
+        }
+        let owners: Vec<String> = tile
+            .vertices
+            .iter()
+            .map(|&v| match board.vertices[v].owner {
+                Some(owner) => format!("{:?}@{:02}", owner, v),
+                None => format!("{:02}", v),
+            })
+            .collect();
+        println!("{} | vértices: {}", line, owners.join(" "));
+    }
+    let ships: Vec<String> = board
+        .edges
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.ship)
+        .filter_map(|(id, e)| e.owner.map(|o| format!("{:?}@{}", o, id)))
+        .collect();
+    if !ships.is_empty() {
+        println!("  Barcos: {}", ships.join(", "));
+    }
+    println!("==================================================================");
+    println!();
+}
+
 pub fn print_buildable_roads(board: &Board, player_id: PlayerType, phase: TurnPhase) {
     println!("\n--- Caminos Disponibles ---");
     let mut found = false;
@@ -533,16 +1099,51 @@ pub fn print_player_status(board: &Board, player_id: PlayerType) {
     println!("---");
     println!("  Cartas de Desarrollo: {}", format_dev_cards(&player.dev_cards));
     println!("---");
+    if board.rules.cities_and_knights {
+        let levels: Vec<String> = ImprovementTrack::ALL
+            .iter()
+            .map(|&t| format!("{:?} {}", t, improvement_level(player, t)))
+            .collect();
+        println!("  Mejoras: {}", levels.join(", "));
+        println!("  Cartas de Progreso: {:?}", player.progress_cards);
+        let knights: Vec<String> = board
+            .vertices
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.knight.filter(|k| k.owner == player_id).map(|k| (i, k)))
+            .map(|(i, k)| format!("{} (nivel {}{})", i, k.level, if k.active { ", activo" } else { "" }))
+            .collect();
+        println!("  Caballeros: {}", if knights.is_empty() { "Ninguno".to_string() } else { knights.join(", ") });
+        println!("---");
+    }
     println!("  Puertos: {}", format_ports(&player.power_ups));
+    if !board.rules.plugins.is_empty() {
+        println!("---");
+        let mut tokens: Vec<String> = player.tokens.iter().map(|(t, n)| format!("{}: {}", t, n)).collect();
+        tokens.sort();
+        println!("  Fichas: {}", if tokens.is_empty() { "Ninguna".to_string() } else { tokens.join(", ") });
+        let points = scenario_points(board, player_id);
+        if points != 0 {
+            println!("  > (El escenario le da {:+} VP)", points);
+        }
+    }
     println!("+---------------------------------------+\n");
 }
 
-pub fn print_global_status(board: &Board) {
+/// Lo que cualquiera puede ver en la mesa, desde el asiento de `view`.
+pub fn print_global_status(view: &BoardView) {
+    let board = &view.board;
     println!("\n=========================================");
     println!("==          ESTADO DEL JUEGO         ==");
     println!("=========================================");
     let robber_pos = board.tiles.iter().position(|t| t.has_robber).unwrap_or(99);
     println!("  Ladrón: Casilla {}", robber_pos);
+    if board.rules.cities_and_knights {
+        println!("  Bárbaros: {}/{} ({} ataques)", board.barbarian_position, BARBARIAN_TRACK_LENGTH, board.barbarian_attacks);
+        for (track, vertex) in &board.metropolises {
+            println!("  Metrópolis de {:?}: vértice {}", track, vertex);
+        }
+    }
     println!("---");
     match board.largest_army {
         Some(p) => println!("  Mayor Ejército:   {:?} ({} caballeros)", p, board.largest_army_size),
@@ -553,26 +1154,26 @@ pub fn print_global_status(board: &Board) {
         None => println!("  Camino Más Largo: Nadie (se necesita > {})", board.longest_road_size),
     }
     println!("---");
-    println!("  Cartas de Desarrollo Restantes: {}", board.development_cards.len());
+    println!("  Cartas de Desarrollo Restantes: {}", view.deck_size);
     println!("---");
     println!("  Resumen de Jugadores:");
     for player in &board.players {
-        let resource_total: u8 = player.resources.values().sum();
         println!(
             "    - {:?}: {} VP, {} Recursos, {} Cartas Dev.",
             player.id,
             player.victory_points,
-            resource_total,
-            player.dev_cards.len()
+            view.hand_size(player.id),
+            view.dev_card_count(player.id)
         );
     }
     println!("=========================================\n");
 }
 
-fn format_resources(resources: &HashMap<MaterialType, u8>) -> String {
+pub(crate) fn format_resources(resources: &HashMap<MaterialType, u8>) -> String {
     let order = [
         MaterialType::Wood, MaterialType::Brick, MaterialType::Sheep,
         MaterialType::Wheat, MaterialType::Stone,
+        MaterialType::Paper, MaterialType::Cloth, MaterialType::Coin,
     ];
     let mut parts = Vec::new();
     for material in order {
@@ -607,15 +1208,48 @@ fn format_ports(ports: &Vec<PowerUp>) -> String {
 // INPUT HELPERS
 // -----------------------------------------------------------------------------
 
-fn read_line_prompt(prompt: &str) -> String {
+pub(crate) fn read_line_prompt(prompt: &str) -> String {
+    read_line_timed(prompt, None).unwrap_or_default()
+}
+
+/// Como `read_line_prompt`, pero se rinde en `deadline`. `None` = se acabó el tiempo.
+pub(crate) fn read_line_timed(prompt: &str, deadline: Option<Instant>) -> Option<String> {
     print!("{} ", prompt);
     stdout().flush().unwrap();
-    let mut input = String::new();
-    stdin().read_line(&mut input).expect("Error al leer la línea");
-    input.trim().to_string()
+    let lines = stdin_lines().lock().unwrap();
+    let line = match deadline {
+        // si stdin se cerró, una línea vacía como antes
+        None => lines.recv().unwrap_or_default(),
+        Some(deadline) => match lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => {
+                println!();
+                return None;
+            }
+            Err(RecvTimeoutError::Disconnected) => String::new(),
+        },
+    };
+    Some(line.trim().to_string())
+}
+
+/// Las líneas de stdin llegan desde otro hilo, para poder esperarlas con límite de tiempo.
+fn stdin_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    LINES.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut line = String::new();
+            while stdin().read_line(&mut line).is_ok_and(|n| n > 0) {
+                if tx.send(std::mem::take(&mut line)).is_err() {
+                    break;
+                }
+            }
+        });
+        Mutex::new(rx)
+    })
 }
 
-fn read_u8(prompt: &str) -> u8 {
+pub(crate) fn read_u8(prompt: &str) -> u8 {
     loop {
         let input_str = read_line_prompt(prompt);
         match input_str.parse::<u8>() {
@@ -625,24 +1259,71 @@ fn read_u8(prompt: &str) -> u8 {
     }
 }
 
-fn roll_dice() -> u8 {
-    // según tu warning, estas son las nuevas APIs
-    let mut r = rng();
-    let die1: u8 = r.random_range(1..=6);
-    let die2: u8 = r.random_range(1..=6);
-    die1 + die2
+pub(crate) fn read_u32(prompt: &str) -> u32 {
+    loop {
+        let input_str = read_line_prompt(prompt);
+        match input_str.parse::<u32>() {
+            Ok(num) => return num,
+            Err(_) => println!("Entrada inválida. Introduce un número."),
+        }
+    }
+}
+
+/// Tira los dados con el modo de las reglas (en las rondas sin 7, se vuelve
+/// a tirar), o pregunta lo que salió en la mesa si `physical`.
+/// Devuelve la suma y el dado rojo, que usan las cartas de progreso.
+fn roll_dice(board: &mut Board, round: u32, physical: bool) -> (u8, u8) {
+    let (white, red) = if physical {
+        read_physical_dice(board, round)
+    } else {
+        board.dice.roll(&board.rules, round)
+    };
+    (white + red, red)
+}
+
+fn read_physical_dice(board: &mut Board, round: u32) -> (u8, u8) {
+    loop {
+        let input = read_line_prompt("Dados de la mesa (blanco y rojo, p. ej. 3 5):");
+        let values: Vec<u8> = input.split_whitespace().filter_map(|n| n.parse().ok()).collect();
+        let [first, second] = values[..] else {
+            println!("Entrada inválida. Escribe los dos dados separados por un espacio.");
+            continue;
+        };
+        match board.dice.enter(&board.rules, round, first, second) {
+            Ok(dice) => return dice,
+            Err(msg) => println!("{}", msg),
+        }
+    }
+}
+
+pub(crate) fn read_material_type(prompt: &str) -> Option<MaterialType> {
+    parse_material_type(&read_line_prompt(prompt))
+}
+
+pub(crate) fn read_track(prompt: &str) -> Option<ImprovementTrack> {
+    match read_line_prompt(prompt).to_lowercase().as_str() {
+        "c" | "comercio" => Some(ImprovementTrack::Trade),
+        "p" | "politica" | "política" => Some(ImprovementTrack::Politics),
+        "i" | "ciencia" => Some(ImprovementTrack::Science),
+        _ => {
+            println!("Rama no reconocida. Cancelando.");
+            None
+        }
+    }
 }
 
-fn read_material_type(prompt: &str) -> Option<MaterialType> {
-    let input = read_line_prompt(prompt);
+fn parse_material_type(input: &str) -> Option<MaterialType> {
     match input.to_lowercase().as_str() {
         "m" | "madera" | "wood" => Some(MaterialType::Wood),
         "l" | "ladrillo" | "brick" => Some(MaterialType::Brick),
         "o" | "oveja" | "sheep" => Some(MaterialType::Sheep),
         "t" | "trigo" | "wheat" => Some(MaterialType::Wheat),
         "p" | "piedra" | "stone" => Some(MaterialType::Stone),
+        "papel" | "paper" => Some(MaterialType::Paper),
+        "tela" | "cloth" => Some(MaterialType::Cloth),
+        "moneda" | "coin" => Some(MaterialType::Coin),
         _ => {
-            println!("Material no reconocido. (madera, ladrillo, oveja, trigo, piedra)");
+            println!("Material no reconocido. (madera, ladrillo, oveja, trigo, piedra, papel, tela, moneda)");
             None
         }
     }
@@ -652,32 +1333,25 @@ fn read_material_type(prompt: &str) -> Option<MaterialType> {
 // ROBO DE JUGADOR (te faltaba en el scope del refactor)
 // -----------------------------------------------------------------------------
 
-fn read_player_to_rob(board: &Board, tile_id: usize, self_id: PlayerType) -> Option<PlayerType> {
+pub(crate) fn read_player_to_rob(board: &Board, tile_id: usize, self_id: PlayerType) -> Option<PlayerType> {
     // esta función la tenías en tu versión anterior
-    use crate::game_logic::get_players_adjacent_to_tile;
-
-    let adjacent_players = get_players_adjacent_to_tile(board, tile_id);
-
-    let robbable_players: Vec<PlayerType> = adjacent_players
-        .into_iter()
-        .filter(|&p| p != self_id)
-        .collect();
+    let robbable = robbable_players(board, tile_id, self_id);
 
-    if robbable_players.is_empty() {
+    if robbable.is_empty() {
         println!("No hay jugadores a quienes robar en esa casilla.");
         return None;
     }
 
     println!("Jugadores disponibles para robar:");
-    for (i, player) in robbable_players.iter().enumerate() {
+    for (i, player) in robbable.iter().enumerate() {
         println!("[{}] {:?}", i, player);
     }
 
     loop {
         let choice_str = read_line_prompt("Elige un número de jugador:");
         match choice_str.parse::<usize>() {
-            Ok(index) if index < robbable_players.len() => {
-                return Some(robbable_players[index]);
+            Ok(index) if index < robbable.len() => {
+                return Some(robbable[index]);
             }
             _ => println!("Número inválido."),
         }
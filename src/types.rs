@@ -4,8 +4,11 @@ pub type TileId = usize;
 pub type EdgeId = usize;
 
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::rules::RuleSet;
+use crate::dice::Dice;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PlayerType {
     Player1,
     Player2,
@@ -13,7 +16,7 @@ pub enum PlayerType {
     Player4,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TurnPhase {
 
     Setup { anchor_vertex: VertexId },
@@ -21,13 +24,13 @@ pub enum TurnPhase {
     FreeRoad,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BuildingType {
     Settlement,
     City,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PowerUp {
     Wheat2,
     Brick2,
@@ -37,7 +40,7 @@ pub enum PowerUp {
     Any3,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MaterialType {
     Wheat,
     Brick,
@@ -45,8 +48,192 @@ pub enum MaterialType {
     Sheep,
     Wood, 
     Dessert,
+    /// Navegantes: casilla de mar, no produce.
+    Sea,
+    /// Navegantes: campo de oro, cada carta producida es un recurso a elección.
+    Gold,
+    /// Ciudades y Caballeros: mercancías que dan las ciudades (bosque, pasto, montaña).
+    Paper,
+    Cloth,
+    Coin,
 }
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl MaterialType {
+    /// Las cinco cartas de recurso.
+    pub const RESOURCES: [MaterialType; 5] = [
+        MaterialType::Wood, MaterialType::Brick, MaterialType::Sheep,
+        MaterialType::Wheat, MaterialType::Stone,
+    ];
+
+    /// ¿Es una carta que se puede tener en la mano? (no desierto, mar ni oro)
+    pub fn is_resource(self) -> bool {
+        matches!(
+            self,
+            MaterialType::Wheat | MaterialType::Brick | MaterialType::Stone | MaterialType::Sheep | MaterialType::Wood
+        )
+    }
+
+    /// Ciudades y Caballeros: papel, tela y moneda.
+    pub fn is_commodity(self) -> bool {
+        matches!(self, MaterialType::Paper | MaterialType::Cloth | MaterialType::Coin)
+    }
+
+    /// La mercancía que da una ciudad sobre esta casilla, si da alguna.
+    pub fn commodity(self) -> Option<MaterialType> {
+        match self {
+            MaterialType::Wood => Some(MaterialType::Paper),
+            MaterialType::Sheep => Some(MaterialType::Cloth),
+            MaterialType::Stone => Some(MaterialType::Coin),
+            _ => None,
+        }
+    }
+}
+
+/// Ciudades y Caballeros: las tres ramas de mejoras de ciudad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImprovementTrack {
+    /// Comercio (amarillo), se paga con tela.
+    Trade,
+    /// Política (azul), se paga con moneda.
+    Politics,
+    /// Ciencia (verde), se paga con papel.
+    Science,
+}
+
+impl ImprovementTrack {
+    pub const ALL: [ImprovementTrack; 3] =
+        [ImprovementTrack::Trade, ImprovementTrack::Politics, ImprovementTrack::Science];
+
+    pub fn commodity(self) -> MaterialType {
+        match self {
+            ImprovementTrack::Trade => MaterialType::Cloth,
+            ImprovementTrack::Politics => MaterialType::Coin,
+            ImprovementTrack::Science => MaterialType::Paper,
+        }
+    }
+}
+
+/// Ciudades y Caballeros: caballero sobre un vértice (nivel 1 a 3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Knight {
+    pub owner: PlayerType,
+    pub level: u8,
+    pub active: bool,
+}
+
+/// Ciudades y Caballeros: cartas de progreso, en lugar de las de desarrollo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProgressCard {
+    // ciencia
+    /// Dos trigos por cada casilla de trigo con un edificio propio.
+    Irrigation,
+    /// Dos piedras por cada casilla de piedra con un edificio propio.
+    Mining,
+    /// Dos caminos gratis.
+    RoadBuilding,
+    /// Asciende dos caballeros gratis.
+    Smith,
+    /// Punto de victoria.
+    Printer,
+    // comercio
+    /// Hasta dos cartas de un recurso de cada rival.
+    ResourceMonopoly,
+    /// Una mercancía de cada rival.
+    TradeMonopoly,
+    // política
+    /// Activa todos los caballeros gratis.
+    Warlord,
+    /// Mueve el ladrón y roba a todos los de la casilla.
+    Bishop,
+    /// Punto de victoria.
+    Constitution,
+}
+
+impl ProgressCard {
+    /// Los puntos de victoria se juegan solos apenas se reciben.
+    pub fn is_victory_point(self) -> bool {
+        matches!(self, ProgressCard::Printer | ProgressCard::Constitution)
+    }
+}
+
+/// Oferta de comercio entre jugadores, una entrada por carta.
+/// `to` es `None` si cualquiera de los rivales la puede aceptar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeOffer {
+    pub to: Option<PlayerType>,
+    pub give: Vec<MaterialType>,
+    pub get: Vec<MaterialType>,
+}
+
+/// Una carta de progreso jugada, con lo que eligió quien la juega.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProgressPlay {
+    Irrigation,
+    Mining,
+    RoadBuilding { first_edge: EdgeId, second_edge: EdgeId },
+    Smith { first: VertexId, second: VertexId },
+    ResourceMonopoly { material: MaterialType },
+    TradeMonopoly { material: MaterialType },
+    Warlord,
+    Bishop { tile: TileId },
+}
+
+impl ProgressPlay {
+    pub fn card(self) -> ProgressCard {
+        match self {
+            ProgressPlay::Irrigation => ProgressCard::Irrigation,
+            ProgressPlay::Mining => ProgressCard::Mining,
+            ProgressPlay::RoadBuilding { .. } => ProgressCard::RoadBuilding,
+            ProgressPlay::Smith { .. } => ProgressCard::Smith,
+            ProgressPlay::ResourceMonopoly { .. } => ProgressCard::ResourceMonopoly,
+            ProgressPlay::TradeMonopoly { .. } => ProgressCard::TradeMonopoly,
+            ProgressPlay::Warlord => ProgressCard::Warlord,
+            ProgressPlay::Bishop { .. } => ProgressCard::Bishop,
+        }
+    }
+}
+
+/// Ciudades y Caballeros: cara del dado de eventos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventDie {
+    /// El barco bárbaro avanza (tres de las seis caras).
+    Barbarians,
+    /// Puerta de una rama: reparte cartas de progreso según el dado rojo.
+    Gate(ImprovementTrack),
+}
+
+/// Escenarios que se enchufan al motor con ganchos (ver `game_logic::hooks`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScenarioPlugin {
+    /// Los Pescadores de Catán: zonas de pesca y lago que dan pescado.
+    Fishermen,
+    /// Los Ríos de Catán: puentes y casas junto al río dan monedas de oro.
+    Rivers,
+}
+
+/// Pieza que pone un escenario sobre un vértice, borde o casilla. Qué es y
+/// qué hace lo decide el escenario según `kind`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioPiece {
+    pub kind: String,
+    #[serde(default)]
+    pub owner: Option<PlayerType>,
+    /// Número de dado, valor o lo que el escenario necesite.
+    #[serde(default)]
+    pub number: u8,
+}
+
+/// Lo que se recibe al canjear fichas de un escenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenReward {
+    /// Un recurso del banco.
+    Resource(MaterialType),
+    /// Un camino gratis.
+    Road(EdgeId),
+    /// Saca al ladrón del tablero hasta el próximo 7.
+    RemoveRobber,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DevelopmentCard {
     Knight,
     RoadBuilding,
@@ -54,29 +241,48 @@ pub enum DevelopmentCard {
     Monopoly,
     VictoryPoint,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Vertex {
     pub owner: Option<PlayerType>,
     pub building: Option<BuildingType>,
     pub adjacent_tiles: Vec<TileId>, 
     pub adjacent_edges: Vec<EdgeId>,
     pub power_up: Option<PowerUp>,
+    /// Ciudades y Caballeros: caballero parado en el vértice.
+    #[serde(default)]
+    pub knight: Option<Knight>,
+    /// Piezas de escenario en el vértice.
+    #[serde(default)]
+    pub pieces: Vec<ScenarioPiece>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tile {
     pub material: MaterialType,
     pub number: u8, 
     pub vertices: [VertexId; 6], 
     pub has_robber: bool,
+    /// Navegantes: el pirata sólo va sobre el mar.
+    #[serde(default)]
+    pub has_pirate: bool,
+    /// Coordenada axial (q, r) en los tableros armados desde un escenario.
+    #[serde(default)]
+    pub coord: Option<(i32, i32)>,
+    #[serde(default)]
+    pub pieces: Vec<ScenarioPiece>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Edge {
     pub owner: Option<PlayerType>, 
     pub vertices: (VertexId, VertexId),
+    /// Navegantes: la pieza del borde es un barco y no un camino.
+    #[serde(default)]
+    pub ship: bool,
+    #[serde(default)]
+    pub pieces: Vec<ScenarioPiece>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Player {
     pub id: PlayerType,
     pub resources: HashMap<MaterialType, u8>,
@@ -87,10 +293,31 @@ pub struct Player {
     pub victory_points: u8,
     pub dev_cards: Vec<DevelopmentCard>,
     pub knights_played: u8,
-    pub played_dev_card_this_turn: bool,
+    pub dev_cards_played_this_turn: u8,
+    #[serde(default)]
+    pub ship_quantity: u8,
+    /// Barcos puestos este turno: no se pueden mover hasta el próximo.
+    #[serde(default)]
+    pub new_ships: Vec<EdgeId>,
+    #[serde(default)]
+    pub moved_ship_this_turn: bool,
+    /// Islas donde fundó al empezar (no dan bonus).
+    #[serde(default)]
+    pub home_islands: Vec<TileId>,
+    /// Islas que ya le dieron puntos extra.
+    #[serde(default)]
+    pub bonus_islands: Vec<TileId>,
+    /// Ciudades y Caballeros: nivel en cada rama de mejoras (0 a 5).
+    #[serde(default)]
+    pub improvements: HashMap<ImprovementTrack, u8>,
+    #[serde(default)]
+    pub progress_cards: Vec<ProgressCard>,
+    /// Fichas de los escenarios (pescado, monedas...), por nombre.
+    #[serde(default)]
+    pub tokens: HashMap<String, u8>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Board {
     pub vertices: Vec<Vertex>,
     pub tiles: Vec<Tile>,
@@ -101,20 +328,49 @@ pub struct Board {
     pub largest_army_size: u8,
     pub longest_road: Option<PlayerType>,
     pub longest_road_size: u8,
+    #[serde(default)]
+    pub rules: RuleSet,
+    /// Ciudades y Caballeros: casilleros que avanzó el barco bárbaro.
+    #[serde(default)]
+    pub barbarian_position: u8,
+    #[serde(default)]
+    pub barbarian_attacks: u8,
+    /// Ciudad que tiene la metrópolis de cada rama.
+    #[serde(default)]
+    pub metropolises: HashMap<ImprovementTrack, VertexId>,
+    /// Mazos de cartas de progreso, uno por rama.
+    #[serde(default)]
+    pub progress_decks: HashMap<ImprovementTrack, Vec<ProgressCard>>,
+    /// Mazo de dados y últimas tiradas, según el modo de dados de las reglas.
+    #[serde(default)]
+    pub dice: Dice,
 }
 impl Player {
     pub fn new(id: PlayerType) -> Self {
+        Self::with_rules(id, &RuleSet::default())
+    }
+
+    /// Jugador con las piezas que dan las reglas.
+    pub fn with_rules(id: PlayerType, rules: &RuleSet) -> Self {
         Player {
             id,
             power_ups: Vec::new(),
-            settlement_quantity: 5,
-            city_quantity: 4, 
+            settlement_quantity: rules.settlements,
+            city_quantity: rules.cities,
             resources: HashMap::new(), 
-            road_quantity: 15,
+            road_quantity: rules.roads,
             victory_points: 0,
             dev_cards: Vec::new(),
             knights_played: 0,
-            played_dev_card_this_turn: false,
+            dev_cards_played_this_turn: 0,
+            ship_quantity: rules.ships,
+            new_ships: Vec::new(),
+            moved_ship_this_turn: false,
+            home_islands: Vec::new(),
+            bonus_islands: Vec::new(),
+            improvements: HashMap::new(),
+            progress_cards: Vec::new(),
+            tokens: HashMap::new(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,204 @@
+// src/visual_camera.rs
+//
+// La cámara del tablero. Al entrar a la partida y cada vez que cambia el
+// tamaño de la ventana, el tablero se encuadra en el lugar que dejan
+// libre los paneles (F lo vuelve a encuadrar). La rueda del mouse acerca
+// y aleja alrededor del cursor y arrastrar con el botón derecho mueve la
+// vista. La interfaz se agranda o se achica con la ventana (`UiScale`),
+// tomando 1280x720 como tamaño de referencia. Como `update_hover` pasa
+// el cursor por la cámara, los clicks siguen cayendo donde corresponde
+// con cualquier zoom.
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::hex_layout::BoardGeometry;
+use crate::visual_game::{AppState, VisualBoard};
+
+/// Tamaño de ventana para el que está pensada la interfaz.
+const BASE_WINDOW: Vec2 = Vec2::new(1280.0, 720.0);
+const MIN_UI_SCALE: f32 = 0.7;
+const MAX_UI_SCALE: f32 = 1.6;
+/// Lo que tapan los paneles en cada borde, en unidades de la interfaz:
+/// a la derecha los jugadores, abajo la mano y la barra de acciones.
+const PANEL_LEFT: f32 = 20.0;
+const PANEL_RIGHT: f32 = 260.0;
+const PANEL_TOP: f32 = 70.0;
+const PANEL_BOTTOM: f32 = 160.0;
+/// Cuánto se puede acercar y alejar, respecto del encuadre.
+const MIN_ZOOM: f32 = 0.3;
+const MAX_ZOOM: f32 = 2.5;
+/// Cuánto cambia el zoom por cada paso de la rueda.
+const ZOOM_STEP: f32 = 1.1;
+/// Píxeles de scroll de un touchpad que cuentan como un paso de la rueda.
+const PIXELS_PER_LINE: f32 = 40.0;
+
+// =====================================================
+// PLUGIN
+// =====================================================
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<CameraFit>()
+            .add_systems(OnEnter(AppState::Playing), fit_on_enter)
+            .add_systems(Update, (
+                refit_camera,
+                zoom_camera,
+                pan_camera,
+            ).run_if(in_state(AppState::Playing)));
+    }
+}
+
+// =====================================================
+// RECURSOS
+// =====================================================
+
+/// La escala con la que el tablero entra justo; el zoom se mide contra ella.
+#[derive(Resource)]
+struct CameraFit(f32);
+
+impl Default for CameraFit {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+// =====================================================
+// ENCUADRE
+// =====================================================
+
+/// Escala de la interfaz para ese tamaño de ventana.
+fn ui_scale_for(window: &Window) -> f32 {
+    let ratio = window.size() / BASE_WINDOW;
+    ratio.x.min(ratio.y).clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+}
+
+/// Centra el tablero en lo que dejan libre los paneles y devuelve la escala usada.
+fn fit_board(
+    window: &Window,
+    geometry: &BoardGeometry,
+    ui_scale: f32,
+    transform: &mut Transform,
+    projection: &mut OrthographicProjection,
+) -> f32 {
+    // el tablero, con lugar para los puertos
+    let pad = Vec2::splat(geometry.layout.size * 1.8);
+    let (min, max) = geometry
+        .tile_centers
+        .iter()
+        .fold((Vec2::MAX, Vec2::MIN), |(min, max), &c| (min.min(c), max.max(c)));
+    let (min, max) = (min - pad, max + pad);
+
+    // el hueco libre, en píxeles de la ventana
+    let free = Vec2::new(
+        window.width() - (PANEL_LEFT + PANEL_RIGHT) * ui_scale,
+        window.height() - (PANEL_TOP + PANEL_BOTTOM) * ui_scale,
+    )
+    .max(Vec2::splat(100.0));
+    let scale = ((max - min) / free).max_element();
+
+    // el centro del hueco está corrido respecto del centro de la ventana (y hacia arriba)
+    let offset = Vec2::new(PANEL_LEFT - PANEL_RIGHT, PANEL_BOTTOM - PANEL_TOP) * ui_scale / 2.0;
+    let center = (min + max) / 2.0 - offset * scale;
+    transform.translation = center.extend(transform.translation.z);
+    projection.scale = scale;
+    scale
+}
+
+fn fit_on_enter(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    vis_board: Res<VisualBoard>,
+    mut ui_scale: ResMut<UiScale>,
+    mut fit: ResMut<CameraFit>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let (Ok(window), Ok((mut transform, mut projection))) = (windows.get_single(), camera.get_single_mut()) else {
+        return;
+    };
+    ui_scale.0 = ui_scale_for(window);
+    fit.0 = fit_board(window, &vis_board.geometry, ui_scale.0, &mut transform, &mut projection);
+}
+
+/// Al cambiar el tamaño de la ventana, o con F, se vuelve a encuadrar todo.
+fn refit_camera(
+    mut resized: EventReader<WindowResized>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    vis_board: Res<VisualBoard>,
+    mut ui_scale: ResMut<UiScale>,
+    mut fit: ResMut<CameraFit>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let was_resized = resized.read().count() > 0;
+    if !was_resized && !keys.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let (Ok(window), Ok((mut transform, mut projection))) = (windows.get_single(), camera.get_single_mut()) else {
+        return;
+    };
+    if was_resized {
+        ui_scale.0 = ui_scale_for(window);
+    }
+    fit.0 = fit_board(window, &vis_board.geometry, ui_scale.0, &mut transform, &mut projection);
+}
+
+// =====================================================
+// ZOOM Y PANEO
+// =====================================================
+
+/// La rueda acerca o aleja dejando quieto lo que está bajo el cursor.
+fn zoom_camera(
+    mut wheel: EventReader<MouseWheel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    fit: Res<CameraFit>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let steps: f32 = wheel
+        .read()
+        .map(|e| match e.unit {
+            MouseScrollUnit::Line => e.y,
+            MouseScrollUnit::Pixel => e.y / PIXELS_PER_LINE,
+        })
+        .sum();
+    if steps == 0.0 {
+        return;
+    }
+    let (Ok(window), Ok((mut transform, mut projection))) = (windows.get_single(), camera.get_single_mut()) else {
+        return;
+    };
+    let old = projection.scale;
+    let new = (old * ZOOM_STEP.powf(-steps)).clamp(fit.0 * MIN_ZOOM, fit.0 * MAX_ZOOM);
+
+    // el cursor respecto del centro de la ventana, con la y para arriba
+    if let Some(cursor) = window.cursor_position() {
+        let from_center = (cursor - window.size() / 2.0) * Vec2::new(1.0, -1.0);
+        let shift = from_center * (old - new);
+        transform.translation += shift.extend(0.0);
+    }
+    projection.scale = new;
+}
+
+/// Arrastrar con el botón derecho mueve el tablero con el cursor.
+fn pan_camera(
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut last_cursor: Local<Option<Vec2>>,
+    mut camera: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
+) {
+    let cursor = windows.get_single().ok().and_then(|w| w.cursor_position());
+    if !buttons.pressed(MouseButton::Right) {
+        *last_cursor = None;
+        return;
+    }
+    let (Some(cursor), Ok((mut transform, projection))) = (cursor, camera.get_single_mut()) else {
+        return;
+    };
+    if let Some(last) = last_cursor.replace(cursor) {
+        let delta = (cursor - last) * Vec2::new(-1.0, 1.0) * projection.scale;
+        transform.translation += delta.extend(0.0);
+    }
+}
@@ -0,0 +1,426 @@
+// src/visual_fx.rs
+//
+// Animaciones y avisos. Todo sale de los `PlayedEvent`, no de comparar el
+// tablero: al tirar los dados laten las casillas del número y las cartas
+// que producen vuelan desde la casilla hasta el panel de su dueño; los
+// robos (y el monopolio) vuelan de panel a panel; el ladrón y el pirata
+// se deslizan hasta su casilla nueva. Arriba, al centro, aparecen avisos
+// cuando cambian el Gran Ejército o el Camino Más Largo y cuando alguien
+// gana. `repaint_from_board` sigue dejando el tablero al día; esto es
+// sólo lo que se ve mientras tanto.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::engine::GameEvent;
+use crate::types::*;
+use crate::visual_game::{robber_spot, terrain_color, AppState, BoardFlow, PlayedEvent, TilePiece, VisualBoard};
+use crate::visual_hud::PlayerPanel;
+
+const CARD_SIZE: Vec2 = Vec2::new(18.0, 26.0);
+const CARD_FLIGHT_SECS: f32 = 0.7;
+/// Entre una carta y la siguiente del mismo vuelo.
+const CARD_STAGGER_SECS: f32 = 0.12;
+/// Dorso de una carta que no se sabe cuál es.
+const CARD_BACK_COLOR: Color = Color::srgb(0.35, 0.25, 0.45);
+const PULSE_SECS: f32 = 1.2;
+const PULSE_COLOR: Color = Color::srgb(1.0, 0.97, 0.7);
+const SLIDE_SECS: f32 = 0.6;
+/// Cuánto sube el ladrón a mitad de camino.
+const SLIDE_HOP: f32 = 14.0;
+const TOAST_SECS: f32 = 3.5;
+const TOAST_FADE_SECS: f32 = 0.6;
+const TOAST_COLOR: Color = Color::srgba(0.1, 0.1, 0.14, 0.9);
+
+// =====================================================
+// PLUGIN
+// =====================================================
+
+pub struct FxPlugin;
+
+impl Plugin for FxPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::Playing), spawn_fx)
+            .add_systems(Update, (
+                slide_tile_pieces.in_set(BoardFlow::React),
+                pulse_rolled_tiles,
+                fly_cards,
+                show_toasts,
+                animate_slides,
+                animate_pulses,
+                animate_cards,
+                animate_toasts,
+            ).run_if(in_state(AppState::Playing)));
+    }
+}
+
+// =====================================================
+// RECURSOS Y COMPONENTES
+// =====================================================
+
+#[derive(Resource)]
+struct FxAssets {
+    hex: Mesh2dHandle,
+    font: Handle<Font>,
+}
+
+/// El ladrón o el pirata yendo de una casilla a otra. Mientras lo tenga,
+/// `repaint_from_board` no lo toca.
+#[derive(Component)]
+pub(crate) struct Slide {
+    from: Vec2,
+    to: Vec2,
+    timer: Timer,
+}
+
+/// Casilla que salió en los dados; cada una tiene su material para apagarse sola.
+#[derive(Component)]
+struct TilePulse(Timer);
+
+/// Carta volando por la pantalla, en coordenadas de la ventana.
+#[derive(Component)]
+struct FlyingCard {
+    from: Vec2,
+    to: Vec2,
+    /// Lo que falta para que salga.
+    delay: f32,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct ToastStack;
+
+#[derive(Component)]
+struct Toast(Timer);
+
+// =====================================================
+// HELPERS
+// =====================================================
+
+/// Va lento al salir y al llegar.
+fn ease(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Casillas que le dieron ese material a ese jugador con esta tirada.
+fn producing_tiles(board: &Board, roll: u8, player: PlayerType, material: MaterialType) -> Vec<TileId> {
+    (0..board.tiles.len())
+        .filter(|&t| {
+            let tile = &board.tiles[t];
+            tile.number == roll
+                && tile.material == material
+                && !tile.has_robber
+                && board.vertices.iter().any(|v| v.owner == Some(player) && v.adjacent_tiles.contains(&t))
+        })
+        .collect()
+}
+
+/// Centro del panel del jugador en la ventana.
+fn panel_spot(panels: &Query<(&PlayerPanel, &GlobalTransform)>, player: PlayerType) -> Option<Vec2> {
+    panels.iter().find(|(panel, _)| panel.0 == player).map(|(_, tf)| tf.translation().truncate())
+}
+
+fn spawn_card(commands: &mut Commands, color: Color, from: Vec2, to: Vec2, delay: f32) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(from.x - CARD_SIZE.x / 2.0),
+                top: Val::Px(from.y - CARD_SIZE.y / 2.0),
+                width: Val::Px(CARD_SIZE.x),
+                height: Val::Px(CARD_SIZE.y),
+                border: UiRect::all(Val::Px(1.0)),
+                ..Default::default()
+            },
+            background_color: color.into(),
+            border_color: Color::srgb(0.95, 0.92, 0.85).into(),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(20),
+            ..Default::default()
+        },
+        FlyingCard { from, to, delay, timer: Timer::from_seconds(CARD_FLIGHT_SECS, TimerMode::Once) },
+    ));
+}
+
+fn toast_text(event: &GameEvent) -> Option<String> {
+    match *event {
+        GameEvent::LargestArmyChanged { player, knights } => {
+            Some(format!("¡{:?} tiene el Gran Ejército ({} caballeros)!", player, knights))
+        }
+        GameEvent::LongestRoadChanged { player, length } => {
+            Some(format!("¡{:?} tiene el Camino Más Largo ({} tramos)!", player, length))
+        }
+        GameEvent::GameOver { winner } => Some(format!("¡{:?} ganó la partida!", winner)),
+        _ => None,
+    }
+}
+
+// =====================================================
+// STARTUP
+// =====================================================
+
+fn spawn_fx(
+    mut commands: Commands,
+    vis_board: Res<VisualBoard>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let size = vis_board.geometry.layout.size;
+    commands.insert_resource(FxAssets {
+        hex: Mesh2dHandle(meshes.add(RegularPolygon::new(size * 0.92, 6))),
+        font: asset_server.load("FiraSans-Bold.ttf"),
+    });
+
+    // arriba, al centro: los avisos, uno debajo del otro
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(60.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..Default::default()
+            },
+            z_index: ZIndex::Global(15),
+            ..Default::default()
+        },
+        ToastStack,
+    ));
+}
+
+// =====================================================
+// EVENTOS
+// =====================================================
+
+/// El ladrón y el pirata arrancan a deslizarse desde donde se los ve ahora.
+/// Corre antes del repintado para que éste no los salte a la casilla nueva.
+fn slide_tile_pieces(
+    mut played: EventReader<PlayedEvent>,
+    vis_board: Res<VisualBoard>,
+    pieces: Query<(Entity, &TilePiece, &Transform, &Visibility)>,
+    mut commands: Commands,
+) {
+    for PlayedEvent(event) in played.read() {
+        let (moved, tile) = match *event {
+            GameEvent::RobberMoved { tile, .. } => (TilePiece::Robber, tile),
+            GameEvent::PirateMoved { tile, .. } => (TilePiece::Pirate, tile),
+            _ => continue,
+        };
+        // si no estaba en el tablero, aparece directamente donde va
+        let Some((entity, _, transform, _)) =
+            pieces.iter().find(|&(_, &piece, _, visibility)| piece == moved && visibility != Visibility::Hidden)
+        else {
+            continue;
+        };
+        commands.entity(entity).insert(Slide {
+            from: transform.translation.truncate(),
+            to: robber_spot(&vis_board.geometry, tile),
+            timer: Timer::from_seconds(SLIDE_SECS, TimerMode::Once),
+        });
+    }
+}
+
+/// Laten las casillas del número que salió.
+fn pulse_rolled_tiles(
+    mut played: EventReader<PlayedEvent>,
+    vis_board: Res<VisualBoard>,
+    fx: Res<FxAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    for PlayedEvent(event) in played.read() {
+        let GameEvent::DiceRolled { roll, .. } = *event else {
+            continue;
+        };
+        for (t, tile) in vis_board.board.tiles.iter().enumerate() {
+            if tile.number != roll || tile.material == MaterialType::Sea {
+                continue;
+            }
+            let center = vis_board.geometry.tile_centers[t];
+            commands.spawn((
+                MaterialMesh2dBundle {
+                    mesh: fx.hex.clone(),
+                    material: materials.add(PULSE_COLOR.with_alpha(0.0)),
+                    transform: Transform::from_xyz(center.x, center.y, 0.25),
+                    ..Default::default()
+                },
+                TilePulse(Timer::from_seconds(PULSE_SECS, TimerMode::Once)),
+            ));
+        }
+    }
+}
+
+/// Cartas que cambian de mano: de la casilla al panel al producir, de panel
+/// a panel al robar o con el monopolio.
+fn fly_cards(
+    mut played: EventReader<PlayedEvent>,
+    vis_board: Res<VisualBoard>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    panels: Query<(&PlayerPanel, &GlobalTransform)>,
+    ui_scale: Res<UiScale>,
+    mut last_roll: Local<u8>,
+    mut commands: Commands,
+) {
+    let Ok((camera, cam_tf)) = camera_q.get_single() else {
+        return;
+    };
+    let board = &vis_board.board;
+    for PlayedEvent(event) in played.read() {
+        match *event {
+            GameEvent::DiceRolled { roll, .. } => *last_roll = roll,
+            GameEvent::ResourcesProduced { player, material, amount } => {
+                let Some(to) = panel_spot(&panels, player) else {
+                    continue;
+                };
+                // la ventana está en píxeles; la interfaz, en píxeles por `UiScale`
+                let tiles = producing_tiles(board, *last_roll, player, material);
+                for i in 0..amount as usize {
+                    let from = tiles
+                        .get(i % tiles.len().max(1))
+                        .and_then(|&t| camera.world_to_viewport(cam_tf, vis_board.geometry.tile_centers[t].extend(0.0)))
+                        .map(|p| p / ui_scale.0);
+                    let Some(from) = from else {
+                        continue;
+                    };
+                    spawn_card(&mut commands, terrain_color(material), from, to, i as f32 * CARD_STAGGER_SECS);
+                }
+            }
+            GameEvent::ResourceStolen { thief, victim, material } => {
+                // en red, lo robado entre otros dos se ve de dorso
+                let color = material.map_or(CARD_BACK_COLOR, terrain_color);
+                if let (Some(from), Some(to)) = (panel_spot(&panels, victim), panel_spot(&panels, thief)) {
+                    spawn_card(&mut commands, color, from, to, 0.0);
+                }
+            }
+            GameEvent::MonopolyPlayed { player, material, ref stolen } => {
+                let Some(to) = panel_spot(&panels, player) else {
+                    continue;
+                };
+                for &(victim, count) in stolen {
+                    let Some(from) = panel_spot(&panels, victim) else {
+                        continue;
+                    };
+                    for i in 0..count {
+                        spawn_card(&mut commands, terrain_color(material), from, to, i as f32 * CARD_STAGGER_SECS);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn show_toasts(
+    mut played: EventReader<PlayedEvent>,
+    fx: Res<FxAssets>,
+    stack: Query<Entity, With<ToastStack>>,
+    mut commands: Commands,
+) {
+    let Ok(stack) = stack.get_single() else {
+        return;
+    };
+    for text in played.read().filter_map(|e| toast_text(&e.0)) {
+        let style = TextStyle { font: fx.font.clone(), font_size: 22.0, color: Color::WHITE };
+        commands.entity(stack).with_children(|stack| {
+            stack
+                .spawn((
+                    NodeBundle {
+                        style: Style { padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)), ..Default::default() },
+                        background_color: TOAST_COLOR.into(),
+                        ..Default::default()
+                    },
+                    Toast(Timer::from_seconds(TOAST_SECS, TimerMode::Once)),
+                ))
+                .with_children(|toast| {
+                    toast.spawn(TextBundle::from_section(text, style));
+                });
+        });
+    }
+}
+
+// =====================================================
+// ANIMACIONES
+// =====================================================
+
+fn animate_slides(time: Res<Time>, mut commands: Commands, mut slides: Query<(Entity, &mut Slide, &mut Transform)>) {
+    for (entity, mut slide, mut transform) in &mut slides {
+        slide.timer.tick(time.delta());
+        let t = slide.timer.fraction();
+        let pos = slide.from.lerp(slide.to, ease(t)) + Vec2::Y * (t * PI).sin() * SLIDE_HOP;
+        transform.translation = pos.extend(transform.translation.z);
+        if slide.timer.finished() {
+            transform.translation = slide.to.extend(transform.translation.z);
+            commands.entity(entity).remove::<Slide>();
+        }
+    }
+}
+
+/// Dos latidos y se apaga.
+fn animate_pulses(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pulses: Query<(Entity, &mut TilePulse, &Handle<ColorMaterial>)>,
+) {
+    for (entity, mut pulse, material) in &mut pulses {
+        pulse.0.tick(time.delta());
+        if pulse.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(material) = materials.get_mut(material) {
+            let alpha = (pulse.0.fraction() * 2.0 * PI).sin().abs() * 0.45;
+            material.color = PULSE_COLOR.with_alpha(alpha);
+        }
+    }
+}
+
+fn animate_cards(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut cards: Query<(Entity, &mut FlyingCard, &mut Style, &mut Visibility)>,
+) {
+    for (entity, mut card, mut style, mut visibility) in &mut cards {
+        if card.delay > 0.0 {
+            card.delay -= time.delta_seconds();
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        card.timer.tick(time.delta());
+        if card.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let pos = card.from.lerp(card.to, ease(card.timer.fraction()));
+        style.left = Val::Px(pos.x - CARD_SIZE.x / 2.0);
+        style.top = Val::Px(pos.y - CARD_SIZE.y / 2.0);
+    }
+}
+
+/// Los avisos se desvanecen al final y se van.
+fn animate_toasts(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast, &mut BackgroundColor, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (entity, mut toast, mut background, children) in &mut toasts {
+        toast.0.tick(time.delta());
+        if toast.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = (toast.0.remaining_secs() / TOAST_FADE_SECS).min(1.0);
+        *background = TOAST_COLOR.with_alpha(TOAST_COLOR.alpha() * alpha).into();
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.sections[0].style.color = Color::WHITE.with_alpha(alpha);
+            }
+        }
+    }
+}
@@ -1,31 +1,111 @@
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
 
 use crate::types::*;
+use crate::engine::{Action, BoardView, ClockConfig, Game, GameEvent};
 use crate::game_logic::*;
+use crate::hex_layout::BoardGeometry;
+use crate::history::History;
+use crate::rules::RuleSet;
 use crate::setup::*;
+use crate::stats::GameStats;
+use crate::terminal_client::format_ranges;
+use crate::visual_camera::CameraPlugin;
+use crate::visual_fx::{FxPlugin, Slide};
+use crate::visual_hud::HudPlugin;
+use crate::visual_net::{NetworkClientPlugin, RemoteSession};
+use crate::visual_trade::TradePlugin;
+use crate::visual_turns::{can_afford, phase_label, turn_status, LegalMoves, LocalGame, TurnFlowPlugin};
+
+const STATS_FILE: &str = "estadisticas_partida.json";
+/// Distancia (en píxeles del mundo) a la que el cursor agarra un vértice o un borde.
+const VERTEX_PICK_RADIUS: f32 = 18.0;
+const EDGE_PICK_DISTANCE: f32 = 10.0;
+/// Grosor de un camino construido.
+const ROAD_WIDTH: f32 = 8.0;
+/// Lo que se recorta a cada punta del camino para que no tape los vértices.
+const ROAD_INSET: f32 = 9.0;
+const EMPTY_SPOT_COLOR: Color = Color::srgb(0.12, 0.12, 0.12);
+const EMPTY_ROAD_COLOR: Color = Color::srgba(0.45, 0.45, 0.45, 0.6);
+/// Jugadores de la partida local si no se eligen otros.
+pub const DEFAULT_LOCAL_PLAYERS: usize = 2;
 
 // =====================================================
 // PLUGIN
 // =====================================================
 
-pub struct VisualGamePlugin;
+/// `networked: true` abre primero la pantalla de conexión y juega contra un servidor.
+/// `local_players` (2 a 4), `rules` y `clocks` son los de la partida local.
+#[derive(Default)]
+pub struct VisualGamePlugin {
+    pub networked: bool,
+    pub local_players: usize,
+    pub rules: RuleSet,
+    pub clocks: ClockConfig,
+}
 
 impl Plugin for VisualGamePlugin {
     fn build(&self, app: &mut App) {
+        let initial_state = if self.networked {
+            AppState::Connecting
+        } else {
+            AppState::Playing
+        };
+
         app
             .insert_resource(ClearColor(Color::srgb(0.12, 0.14, 0.18)))
-            .init_resource::<VisualBoard>()
             .init_resource::<GameUiState>()
-            .add_systems(Startup, setup_visual_board)
+            .init_resource::<LocalHistory>()
+            .add_event::<ActionRequest>()
+            .add_event::<PlayedEvent>()
+            .insert_state(initial_state)
+            .configure_sets(Update, (BoardFlow::Apply, BoardFlow::React, BoardFlow::Repaint).chain())
+            .add_systems(Startup, spawn_camera)
+            .add_systems(OnEnter(AppState::Playing), setup_visual_board)
             .add_systems(Update, (
-                update_hover_vertex,
+                update_hover,
+                update_hover_markers,
+                update_legal_highlights,
+                update_ghost_piece,
+                handle_tool_keys,
+                handle_undo_keys,
                 handle_clicks,
-                repaint_from_board,
+                repaint_from_board.in_set(BoardFlow::Repaint),
                 update_ui_text,
-            ));
+                show_summary_screen,
+            ).run_if(in_state(AppState::Playing)));
+
+        app.add_plugins((TurnFlowPlugin, HudPlugin, TradePlugin, FxPlugin, CameraPlugin));
+        if self.networked {
+            app.init_resource::<VisualBoard>().add_plugins(NetworkClientPlugin);
+        } else {
+            // partida local: el motor corre acá mismo, con todos en la misma pantalla
+            let players = Some(self.local_players).filter(|n| (2..=4).contains(n)).unwrap_or(DEFAULT_LOCAL_PLAYERS);
+            let game = Game::with_options(players, MapLayout::Random, self.rules.clone());
+            let local = LocalGame::new(game.with_clocks(self.clocks));
+            app.insert_resource(VisualBoard::new(local.game.board.clone())).insert_resource(local);
+        }
     }
 }
 
+/// Orden dentro de un frame: primero entran las jugadas (motor local o
+/// servidor), después reaccionan las animaciones y al final se repinta.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoardFlow {
+    Apply,
+    React,
+    Repaint,
+}
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppState {
+    /// Sólo en red: pantalla para elegir servidor y asiento.
+    Connecting,
+    Playing,
+}
+
 // =====================================================
 // RECURSOS
 // =====================================================
@@ -33,8 +113,9 @@ impl Plugin for VisualGamePlugin {
 #[derive(Resource)]
 pub struct VisualBoard {
     pub board: Board,
-    /// centros de tiles precalculados: index = tile_id
-    pub tile_centers: Vec<Vec2>,
+    /// centros de tiles y posiciones de vértices, precalculados
+    pub geometry: BoardGeometry,
+    pub stats: GameStats,
 }
 
 impl Default for VisualBoard {
@@ -44,20 +125,38 @@ impl Default for VisualBoard {
         // 💡 agregamos al menos 2 jugadores para que place_house no falle
         add_player(&mut board);
         add_player(&mut board);
-        // si querés los 4:
-        // add_player(&mut board);
-        // add_player(&mut board);
 
-        let tile_centers = generate_tile_centers();
-        Self { board, tile_centers }
+        Self::new(board)
     }
 }
 
+impl VisualBoard {
+    pub fn new(board: Board) -> Self {
+        let geometry = BoardGeometry::new(&board);
+        let player_ids: Vec<PlayerType> = board.players.iter().map(|p| p.id).collect();
+        let stats = GameStats::new(&player_ids);
+        Self { board, geometry, stats }
+    }
+}
+
+/// Deshacer/rehacer de la partida local: partida y estadísticas de antes de cada jugada.
+#[derive(Resource, Default)]
+pub struct LocalHistory(pub History<(Game, GameStats)>);
+
 #[derive(Resource)]
 pub struct GameUiState {
+    /// Quien está frente a la pantalla: el asiento en red, el que decide en la partida local.
     pub current_player: PlayerType,
     pub current_tool: CurrentTool,
     pub hovered_vertex: Option<usize>,
+    pub hovered_edge: Option<usize>,
+    pub hovered_tile: Option<usize>,
+    /// Casilla elegida para el ladrón, esperando a que se elija a quién robar.
+    pub robber_tile: Option<TileId>,
+    /// Carta de desarrollo elegida en la mano.
+    pub selected_dev_card: Option<DevelopmentCard>,
+    /// Construcción de caminos: el primer camino ya elegido.
+    pub free_roads: Vec<EdgeId>,
 }
 
 impl Default for GameUiState {
@@ -66,17 +165,74 @@ impl Default for GameUiState {
             current_player: PlayerType::Player1,
             current_tool: CurrentTool::PlaceSettlement,
             hovered_vertex: None,
+            hovered_edge: None,
+            hovered_tile: None,
+            robber_tile: None,
+            selected_dev_card: None,
+            free_roads: Vec::new(),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+impl GameUiState {
+    /// Lo que hace un click en el tablero: el caballero mueve el ladrón y
+    /// la construcción de caminos pone caminos, sea cual sea la herramienta.
+    pub fn board_tool(&self) -> CurrentTool {
+        match self.selected_dev_card {
+            Some(DevelopmentCard::Knight) => CurrentTool::MoveRobber,
+            Some(DevelopmentCard::RoadBuilding) => CurrentTool::PlaceRoad,
+            _ => self.current_tool,
+        }
+    }
+
+    /// Mover el ladrón, o jugar el caballero si está elegido.
+    pub fn robber_action(&self, tile: TileId, victim: Option<PlayerType>) -> Action {
+        match self.selected_dev_card {
+            Some(DevelopmentCard::Knight) => Action::PlayKnight { tile, victim },
+            _ => Action::MoveRobber { tile, victim },
+        }
+    }
+
+    /// Suelta la carta de desarrollo elegida y lo que se llevaba elegido con ella.
+    pub fn drop_dev_card(&mut self) {
+        self.selected_dev_card = None;
+        self.free_roads.clear();
+        self.robber_tile = None;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum CurrentTool {
     PlaceSettlement,
     PlaceRoad,
     MoveRobber,
+    PlaceCity,
 }
 
+impl CurrentTool {
+    pub fn label(self) -> &'static str {
+        match self {
+            CurrentTool::PlaceSettlement => "Asentamiento",
+            CurrentTool::PlaceRoad => "Camino",
+            CurrentTool::MoveRobber => "Ladrón",
+            CurrentTool::PlaceCity => "Ciudad",
+        }
+    }
+}
+
+// =====================================================
+// EVENTOS
+// =====================================================
+
+/// Algo que quiere hacer quien está frente a la pantalla. En red lo manda
+/// `visual_net` al servidor; en la partida local se aplica al `Game`.
+#[derive(Event, Debug, Clone)]
+pub struct ActionRequest(pub Action);
+
+/// Lo que pasó en la partida, venga del motor local o del servidor.
+#[derive(Event, Debug, Clone)]
+pub struct PlayedEvent(pub GameEvent);
+
 // =====================================================
 // COMPONENTES
 // =====================================================
@@ -96,22 +252,68 @@ struct EdgeViz {
     pub edge_id: usize,
 }
 
+/// Resalta lo que está bajo el cursor según la herramienta.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum HoverMarker {
+    Vertex,
+    Edge,
+    Tile,
+}
+
+/// Resalta un lugar donde vale la herramienta actual.
+#[derive(Component, Clone, Copy)]
+enum LegalHighlight {
+    Vertex(VertexId),
+    Edge(EdgeId),
+    Tile(TileId),
+}
+
+/// La pieza translúcida que sigue al cursor sobre los lugares válidos.
+#[derive(Component)]
+struct GhostPiece;
+
+/// Ladrón o pirata: se para en la casilla que lo tenga.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TilePiece {
+    Robber,
+    Pirate,
+}
+
+/// Mallas de las piezas, para cambiarlas cuando cambia el tablero.
+#[derive(Resource)]
+struct PieceMeshes {
+    spot: Mesh2dHandle,
+    settlement: Mesh2dHandle,
+    city: Mesh2dHandle,
+    /// Un cuadrado de 1x1; el camino es este cuadrado estirado.
+    road: Mesh2dHandle,
+    robber: Mesh2dHandle,
+}
+
 #[derive(Component)]
 struct UiTextTag;
 
+#[derive(Component)]
+struct SummaryTag;
+
 // =====================================================
 // STARTUP
 // =====================================================
 
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
 fn setup_visual_board(
     mut commands: Commands,
     vis_board: Res<VisualBoard>,
     asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    // cámara
-    commands.spawn(Camera2dBundle::default());
-
     let font = asset_server.load("FiraSans-Bold.ttf");;
+    let geometry = &vis_board.geometry;
+    let size = geometry.layout.size;
 
     // HUD
     commands.spawn((
@@ -132,246 +334,609 @@ fn setup_visual_board(
         UiTextTag,
     ));
 
+    // las mallas se comparten entre todas las casillas
+    let hex_mesh = Mesh2dHandle(meshes.add(RegularPolygon::new(size, 6)));
+    let inner_hex_mesh = Mesh2dHandle(meshes.add(RegularPolygon::new(size * 0.92, 6)));
+    let token_mesh = Mesh2dHandle(meshes.add(Circle::new(size * 0.3)));
+    let pip_mesh = Mesh2dHandle(meshes.add(Circle::new(2.2)));
+    let harbor_mesh = Mesh2dHandle(meshes.add(Circle::new(size * 0.27)));
+    let outline = materials.add(Color::srgb(0.85, 0.78, 0.55));
+    let token_color = materials.add(Color::srgb(0.96, 0.92, 0.8));
+    let pip_color = materials.add(Color::BLACK);
+    let hot_pip_color = materials.add(HOT_NUMBER_COLOR);
+    let harbor_color = materials.add(Color::srgb(0.55, 0.4, 0.25));
+    let pieces = PieceMeshes {
+        spot: Mesh2dHandle(meshes.add(Circle::new(4.0))),
+        settlement: Mesh2dHandle(meshes.add(polygon_mesh(&SETTLEMENT_SHAPE, Vec2::ZERO))),
+        city: Mesh2dHandle(meshes.add(polygon_mesh(&CITY_SHAPE, Vec2::new(-4.0, -2.0)))),
+        road: Mesh2dHandle(meshes.add(Rectangle::new(1.0, 1.0))),
+        robber: Mesh2dHandle(meshes.add(Capsule2d::new(7.0, 14.0))),
+    };
+
     // ------------------ TILES ------------------
     for (i, tile) in vis_board.board.tiles.iter().enumerate() {
-        let center = vis_board.tile_centers[i];
-
-        let color = match tile.material {
-            MaterialType::Wood   => Color::srgb(0.22, 0.65, 0.25),
-            MaterialType::Brick  => Color::srgb(0.70, 0.35, 0.25),
-            MaterialType::Sheep  => Color::srgb(0.75, 0.9, 0.6),
-            MaterialType::Wheat  => Color::srgb(0.9, 0.8, 0.5),
-            MaterialType::Stone  => Color::srgb(0.6, 0.6, 0.7),
-            MaterialType::Dessert => Color::srgb(0.9, 0.85, 0.65),
-        };
+        let center = geometry.tile_centers[i];
 
+        // borde de arena y encima el terreno, un poco más chico
         commands.spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    color,
-                    custom_size: Some(Vec2::new(95., 95.)), // un “hex” cuadrado
-                    ..Default::default()
-                },
+            MaterialMesh2dBundle {
+                mesh: hex_mesh.clone(),
+                material: outline.clone(),
                 transform: Transform::from_xyz(center.x, center.y, 0.0),
                 ..Default::default()
             },
             TileViz { tile_id: i },
         ));
+        commands.spawn(MaterialMesh2dBundle {
+            mesh: inner_hex_mesh.clone(),
+            material: materials.add(terrain_color(tile.material)),
+            transform: Transform::from_xyz(center.x, center.y, 0.1),
+            ..Default::default()
+        });
 
-        // número / ladrón
-        let text = if tile.has_robber || tile.material == MaterialType::Dessert {
-            "R".to_string()
-        } else {
-            format!("{}", tile.number)
+        // ficha de número: disco, número y puntitos según la probabilidad
+        if tile.number > 0 && tile.material != MaterialType::Sea && tile.material != MaterialType::Dessert {
+            let hot = tile.number == 6 || tile.number == 8;
+            commands.spawn(MaterialMesh2dBundle {
+                mesh: token_mesh.clone(),
+                material: token_color.clone(),
+                transform: Transform::from_xyz(center.x, center.y, 1.0),
+                ..Default::default()
+            });
+            commands.spawn(Text2dBundle {
+                text: Text::from_section(
+                    tile.number.to_string(),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 22.0,
+                        color: if hot { HOT_NUMBER_COLOR } else { Color::BLACK },
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_xyz(center.x, center.y + 3.0, 1.2),
+                ..Default::default()
+            });
+            let pips = pips(tile.number);
+            for p in 0..pips {
+                let x = center.x + (p as f32 - (pips as f32 - 1.0) / 2.0) * 5.5;
+                commands.spawn(MaterialMesh2dBundle {
+                    mesh: pip_mesh.clone(),
+                    material: if hot { hot_pip_color.clone() } else { pip_color.clone() },
+                    transform: Transform::from_xyz(x, center.y - size * 0.18, 1.2),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    // ------------------ PUERTOS ------------------
+    // un puerto es un borde de la costa con el mismo puerto en sus dos vértices
+    for (i, edge) in vis_board.board.edges.iter().enumerate() {
+        let (v1, v2) = edge.vertices;
+        let port = vis_board.board.vertices[v1].power_up;
+        let (Some(port), Some(out)) = (port, geometry.outward(&vis_board.board, i)) else {
+            continue;
         };
+        if vis_board.board.vertices[v2].power_up != Some(port) {
+            continue;
+        }
+        let pos = geometry.edge_midpoint(&vis_board.board, i) + out * size * 0.6;
 
+        // muelles desde cada vértice hasta el puerto
+        for v in [v1, v2] {
+            let from = geometry.vertex(v);
+            let dir = pos - from;
+            commands.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.55, 0.4, 0.25),
+                    custom_size: Some(Vec2::new(dir.length(), 4.0)),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: ((from + pos) / 2.0).extend(0.5),
+                    rotation: Quat::from_rotation_z(dir.y.atan2(dir.x)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        }
+        commands.spawn(MaterialMesh2dBundle {
+            mesh: harbor_mesh.clone(),
+            material: harbor_color.clone(),
+            transform: Transform::from_translation(pos.extend(0.6)),
+            ..Default::default()
+        });
         commands.spawn(Text2dBundle {
             text: Text::from_section(
-                text,
+                harbor_label(port),
                 TextStyle {
                     font: font.clone(),
-                    font_size: 26.0,
-                    color: Color::BLACK,
+                    font_size: 12.0,
+                    color: Color::WHITE,
                 },
             )
             .with_justify(JustifyText::Center),
-            transform: Transform::from_xyz(center.x, center.y + 2.0, 1.0),
+            transform: Transform::from_translation(pos.extend(0.7)),
             ..Default::default()
         });
     }
 
     // ------------------ VÉRTICES ------------------
     for vid in 0..vis_board.board.vertices.len() {
-        if let Some((tile_idx, corner_idx)) =
-            find_tile_and_corner_for_vertex(&vis_board.board, vid)
-        {
-            let tile_center = vis_board.tile_centers[tile_idx];
-            let off = corner_offset_pointy(corner_idx, 55.0);
-            let pos = tile_center + off;
-
-            commands.spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::srgb(0.12, 0.12, 0.12),
-                        custom_size: Some(Vec2::new(16., 16.)),
-                        ..Default::default()
-                    },
-                    transform: Transform::from_xyz(pos.x, pos.y, 5.0),
+        let pos = geometry.vertex(vid);
+
+        // cada vértice tiene su propio color: cambia cuando alguien construye
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: pieces.spot.clone(),
+                material: materials.add(EMPTY_SPOT_COLOR),
+                transform: Transform::from_xyz(pos.x, pos.y, 5.0),
+                ..Default::default()
+            },
+            VertexViz { vertex_id: vid },
+        ));
+    }
+
+    // ------------------ EDGES ------------------
+    for i in 0..vis_board.board.edges.len() {
+        let (p1, p2) = geometry.edge_ends(&vis_board.board, i);
+        let mid = (p1 + p2) / 2.0;
+        let dir = p2 - p1;
+        let len = dir.length();
+        let angle = dir.y.atan2(dir.x);
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: EMPTY_ROAD_COLOR,
+                    custom_size: Some(Vec2::new(len - ROAD_INSET, 3.0)),
                     ..Default::default()
                 },
-                VertexViz { vertex_id: vid },
-            ));
-        }
+                transform: Transform {
+                    translation: Vec3::new(mid.x, mid.y, 2.0),
+                    rotation: Quat::from_rotation_z(angle),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            EdgeViz { edge_id: i },
+        ));
     }
 
-    // ------------------ EDGES ------------------
-    for (i, edge) in vis_board.board.edges.iter().enumerate() {
-        let v1 = edge.vertices.0;
-        let v2 = edge.vertices.1;
-
-        if let (Some(p1), Some(p2)) =
-            (vertex_world_pos(&vis_board, v1), vertex_world_pos(&vis_board, v2))
-        {
-            let mid = (p1 + p2) / 2.0;
-            let dir = p2 - p1;
-            let len = dir.length();
-            let angle = dir.y.atan2(dir.x);
-
-            commands.spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::srgb(0.45, 0.45, 0.45),
-                        custom_size: Some(Vec2::new(len, 5.0)),
-                        ..Default::default()
-                    },
-                    transform: Transform {
-                        translation: Vec3::new(mid.x, mid.y, 2.0),
-                        rotation: Quat::from_rotation_z(angle),
-                        ..Default::default()
-                    },
+    // ------------------ LADRÓN Y PIRATA ------------------
+    // cuerpo y cabeza; `repaint_from_board` los lleva a su casilla
+    let head_mesh = Mesh2dHandle(meshes.add(Circle::new(6.0)));
+    for piece in [TilePiece::Robber, TilePiece::Pirate] {
+        let color = materials.add(piece.color());
+        commands
+            .spawn((
+                MaterialMesh2dBundle {
+                    mesh: pieces.robber.clone(),
+                    material: color.clone(),
+                    transform: Transform::from_xyz(0.0, 0.0, 7.0),
+                    visibility: Visibility::Hidden,
                     ..Default::default()
                 },
-                EdgeViz { edge_id: i },
-            ));
-        }
+                piece,
+            ))
+            .with_children(|body| {
+                body.spawn(MaterialMesh2dBundle {
+                    mesh: head_mesh.clone(),
+                    material: color,
+                    transform: Transform::from_xyz(0.0, 16.0, 0.0),
+                    ..Default::default()
+                });
+            });
+    }
+
+    // ------------------ HOVER ------------------
+    let highlight = Color::srgba(1.0, 1.0, 1.0, 0.35);
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: hex_mesh.clone(),
+            material: materials.add(highlight),
+            transform: Transform::from_xyz(0.0, 0.0, 0.3),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        HoverMarker::Tile,
+    ));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color: highlight, custom_size: Some(Vec2::new(size, 11.0)), ..Default::default() },
+            transform: Transform::from_xyz(0.0, 0.0, 4.0),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        HoverMarker::Edge,
+    ));
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(Circle::new(13.0))),
+            material: materials.add(highlight),
+            transform: Transform::from_xyz(0.0, 0.0, 4.5),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        HoverMarker::Vertex,
+    ));
+
+    // ------------------ JUGADAS VÁLIDAS ------------------
+    let legal_color = materials.add(Color::srgba(1.0, 0.95, 0.4, 0.3));
+    let legal_vertex_mesh = Mesh2dHandle(meshes.add(Circle::new(11.0)));
+    for (tile_id, &center) in geometry.tile_centers.iter().enumerate() {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: inner_hex_mesh.clone(),
+                material: legal_color.clone(),
+                transform: Transform::from_xyz(center.x, center.y, 0.2),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            LegalHighlight::Tile(tile_id),
+        ));
     }
+    for edge_id in 0..vis_board.board.edges.len() {
+        let (a, b) = geometry.edge_ends(&vis_board.board, edge_id);
+        let dir = b - a;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 0.95, 0.4, 0.45),
+                    custom_size: Some(Vec2::new(dir.length(), 9.0)),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: ((a + b) / 2.0).extend(3.0),
+                    rotation: Quat::from_rotation_z(dir.y.atan2(dir.x)),
+                    ..Default::default()
+                },
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            LegalHighlight::Edge(edge_id),
+        ));
+    }
+    for vertex_id in 0..vis_board.board.vertices.len() {
+        let pos = geometry.vertex(vertex_id);
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: legal_vertex_mesh.clone(),
+                material: legal_color.clone(),
+                transform: Transform::from_xyz(pos.x, pos.y, 4.2),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            LegalHighlight::Vertex(vertex_id),
+        ));
+    }
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: pieces.settlement.clone(),
+            material: materials.add(Color::NONE),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        GhostPiece,
+    ));
+    commands.insert_resource(pieces);
 }
 
 // =====================================================
 // SISTEMAS
 // =====================================================
 
-fn update_hover_vertex(
+/// Vértice, borde y casilla bajo el cursor.
+fn update_hover(
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     vis_board: Res<VisualBoard>,
     mut ui_state: ResMut<GameUiState>,
 ) {
     let window = windows.single();
-    if let Some(cursor) = window.cursor_position() {
-        let (camera, cam_tf) = camera_q.single();
-        if let Some(ray) = camera.viewport_to_world(cam_tf, cursor) {
-            let world = ray.origin.truncate();
-            let mut best: Option<(usize, f32)> = None;
-
-            for vid in 0..vis_board.board.vertices.len() {
-                if let Some(vpos) = vertex_world_pos(&vis_board, vid) {
-                    let d = vpos.distance(world);
-                    if d < 18.0 {
-                        match best {
-                            None => best = Some((vid, d)),
-                            Some((_, bd)) if d < bd => best = Some((vid, d)),
-                            _ => {}
-                        }
-                    }
-                }
+    let (camera, cam_tf) = camera_q.single();
+    let world = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(cam_tf, cursor))
+        .map(|ray| ray.origin.truncate());
+
+    let geometry = &vis_board.geometry;
+    ui_state.hovered_vertex = world.and_then(|w| geometry.vertex_at(w, VERTEX_PICK_RADIUS));
+    ui_state.hovered_edge = world.and_then(|w| geometry.edge_at(&vis_board.board, w, EDGE_PICK_DISTANCE));
+    ui_state.hovered_tile = world.and_then(|w| geometry.tile_at(w));
+}
+
+/// Muestra el marcador que corresponde a la herramienta sobre lo que está bajo el cursor.
+fn update_hover_markers(
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    mut markers: Query<(&HoverMarker, &mut Transform, &mut Visibility)>,
+) {
+    let geometry = &vis_board.geometry;
+    let wanted = match ui_state.board_tool() {
+        CurrentTool::PlaceSettlement | CurrentTool::PlaceCity => HoverMarker::Vertex,
+        CurrentTool::PlaceRoad => HoverMarker::Edge,
+        CurrentTool::MoveRobber => HoverMarker::Tile,
+    };
+    for (&marker, mut transform, mut visibility) in &mut markers {
+        let target = match marker {
+            HoverMarker::Vertex => ui_state.hovered_vertex.map(|v| (geometry.vertex(v), 0.0)),
+            HoverMarker::Edge => ui_state.hovered_edge.map(|e| {
+                let (a, b) = geometry.edge_ends(&vis_board.board, e);
+                ((a + b) / 2.0, (b - a).y.atan2((b - a).x))
+            }),
+            HoverMarker::Tile => ui_state.hovered_tile.map(|t| (geometry.tile_centers[t], 0.0)),
+        };
+        match target.filter(|_| marker == wanted) {
+            Some((pos, angle)) => {
+                transform.translation = pos.extend(transform.translation.z);
+                transform.rotation = Quat::from_rotation_z(angle);
+                *visibility = Visibility::Visible;
             }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
 
-            ui_state.hovered_vertex = best.map(|(id, _)| id);
-        } else {
-            ui_state.hovered_vertex = None;
+/// Prende los lugares donde vale la herramienta actual.
+fn update_legal_highlights(legal: Res<LegalMoves>, mut highlights: Query<(&LegalHighlight, &mut Visibility)>) {
+    if !legal.is_changed() {
+        return;
+    }
+    for (&highlight, mut visibility) in &mut highlights {
+        let shown = match highlight {
+            LegalHighlight::Vertex(v) => legal.vertices.contains(&v),
+            LegalHighlight::Edge(e) => legal.edges.contains(&e),
+            LegalHighlight::Tile(t) => legal.tiles.contains(&t),
+        };
+        *visibility = if shown { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+/// La pieza de la herramienta, translúcida, donde quedaría si se hace click.
+fn update_ghost_piece(
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    legal: Res<LegalMoves>,
+    pieces: Res<PieceMeshes>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut ghost: Query<(&mut Mesh2dHandle, &Handle<ColorMaterial>, &mut Transform, &mut Visibility), With<GhostPiece>>,
+) {
+    let Ok((mut mesh, material, mut transform, mut visibility)) = ghost.get_single_mut() else {
+        return;
+    };
+    let geometry = &vis_board.geometry;
+    let legal_vertex = ui_state.hovered_vertex.filter(|v| legal.vertices.contains(v));
+    let legal_edge = ui_state.hovered_edge.filter(|e| legal.edges.contains(e));
+    let legal_tile = ui_state.hovered_tile.filter(|t| legal.tiles.contains(t));
+
+    // la misma malla que la pieza de verdad, en el mismo lugar
+    let tool = ui_state.board_tool();
+    let piece = match tool {
+        CurrentTool::PlaceSettlement => legal_vertex.map(|v| (&pieces.settlement, geometry.vertex(v), 0.0, Vec2::ONE)),
+        CurrentTool::PlaceCity => legal_vertex.map(|v| (&pieces.city, geometry.vertex(v), 0.0, Vec2::ONE)),
+        CurrentTool::PlaceRoad => legal_edge.map(|e| {
+            let (pos, angle, size) = road_placement(geometry, &vis_board.board, e, true);
+            (&pieces.road, pos, angle, size)
+        }),
+        CurrentTool::MoveRobber => legal_tile.map(|t| (&pieces.robber, robber_spot(geometry, t), 0.0, Vec2::ONE)),
+    };
+    let Some((piece_mesh, pos, angle, scale)) = piece else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    if let Some(material) = materials.get_mut(material) {
+        material.color = match tool {
+            CurrentTool::MoveRobber => TilePiece::Robber.color().with_alpha(0.6),
+            _ => player_color(ui_state.current_player).with_alpha(0.55),
+        };
+    }
+    if *mesh != *piece_mesh {
+        *mesh = piece_mesh.clone();
+    }
+    *transform = Transform {
+        translation: pos.extend(8.0),
+        rotation: Quat::from_rotation_z(angle),
+        scale: scale.extend(1.0),
+    };
+    *visibility = Visibility::Visible;
+}
+
+/// 1 = asentamiento, 2 = camino, 3 = ladrón, 4 = ciudad. Sólo las que permite
+/// la fase y se pueden pagar.
+fn handle_tool_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    vis_board: Res<VisualBoard>,
+    mut ui_state: ResMut<GameUiState>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let pressed = [
+        (KeyCode::Digit1, CurrentTool::PlaceSettlement),
+        (KeyCode::Digit2, CurrentTool::PlaceRoad),
+        (KeyCode::Digit3, CurrentTool::MoveRobber),
+        (KeyCode::Digit4, CurrentTool::PlaceCity),
+    ]
+    .into_iter()
+    .find(|&(key, _)| keys.just_pressed(key));
+    if let Some((_, tool)) = pressed {
+        if status.allowed_tools().contains(&tool) && can_afford(&status, &vis_board.board, tool) {
+            ui_state.current_tool = tool;
         }
     }
 }
 
+/// Ctrl+Z deshace y Ctrl+Y (o Ctrl+Shift+Z) rehace. Sólo en partidas locales.
+fn handle_undo_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut vis_board: ResMut<VisualBoard>,
+    mut history: ResMut<LocalHistory>,
+    local: Option<ResMut<LocalGame>>,
+) {
+    let Some(mut local) = local else {
+        return;
+    };
+    if vis_board.stats.winner.is_some() {
+        return;
+    }
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !ctrl {
+        return;
+    }
+    let undo = keys.just_pressed(KeyCode::KeyZ) && !shift;
+    let redo = keys.just_pressed(KeyCode::KeyY) || (keys.just_pressed(KeyCode::KeyZ) && shift);
+    if !undo && !redo {
+        return;
+    }
+
+    let mut state = (local.game.clone(), vis_board.stats.clone());
+    let done = if undo {
+        history.0.undo(&mut state)
+    } else {
+        history.0.redo(&mut state)
+    };
+    if done {
+        (local.game, vis_board.stats) = state;
+        vis_board.board = local.game.board.clone();
+    } else if undo {
+        println!("No hay nada que deshacer.");
+    } else {
+        println!("No hay nada que rehacer.");
+    }
+}
+
+/// Click en el tablero: arma la acción de la herramienta actual, sólo sobre
+/// un lugar válido. La aplica el motor local o el servidor.
 fn handle_clicks(
     buttons: Res<ButtonInput<MouseButton>>,
-    mut vis_board: ResMut<VisualBoard>,
+    vis_board: Res<VisualBoard>,
+    legal: Res<LegalMoves>,
     mut ui_state: ResMut<GameUiState>,
-    // ParamSet: 0 = vértices, 1 = edges
-    mut q: ParamSet<(
-        Query<(&VertexViz, &mut Sprite)>,
-        Query<(&EdgeViz, &mut Sprite)>,
-    )>,
+    ui_buttons: Query<&Interaction, With<Button>>,
+    mut requests: EventWriter<ActionRequest>,
 ) {
     if !buttons.just_pressed(MouseButton::Left) {
         return;
     }
-
-    let Some(vertex_id) = ui_state.hovered_vertex else {
+    // partida terminada: no se construye más
+    if vis_board.stats.winner.is_some() {
         return;
-    };
+    }
+    // el click fue en un botón de la interfaz, no en el tablero
+    if ui_buttons.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
 
-    match ui_state.current_tool {
-        CurrentTool::PlaceSettlement => {
-            match place_house(&mut vis_board.board, ui_state.current_player, vertex_id, false) {
-                Ok(_) => {
-                    // pintamos vértice
-                    let mut vertex_query = q.p0();
-                    for (vv, mut sprite) in vertex_query.iter_mut() {
-                        if vv.vertex_id == vertex_id {
-                            sprite.color = player_color(ui_state.current_player);
-                        }
-                    }
+    // `LegalMoves` ya viene vacío si no le toca o la herramienta no vale en esta fase
+    let vertex = ui_state.hovered_vertex.filter(|v| legal.vertices.contains(v));
+    let edge = ui_state.hovered_edge.filter(|e| legal.edges.contains(e));
+    let action = match ui_state.board_tool() {
+        CurrentTool::PlaceSettlement => vertex.map(|vertex| Action::PlaceSettlement { vertex }),
+        CurrentTool::PlaceCity => vertex.map(|vertex| Action::PlaceCity { vertex }),
+        CurrentTool::PlaceRoad if ui_state.selected_dev_card == Some(DevelopmentCard::RoadBuilding) => {
+            let Some(edge) = edge else {
+                return;
+            };
+            // el primer click sólo guarda el camino; el segundo juega la carta
+            match ui_state.free_roads.first().copied() {
+                None => {
+                    ui_state.free_roads.push(edge);
+                    None
                 }
-                Err(msg) => {
-                    println!("Error: {}", msg);
+                Some(first_edge) => {
+                    ui_state.free_roads.clear();
+                    Some(Action::PlayRoadBuilding { first_edge, second_edge: edge })
                 }
             }
         }
-        CurrentTool::PlaceRoad => {
-            if let Some(edge_id) = first_free_edge_adjacent_to(&vis_board.board, vertex_id) {
-                match place_road(
-                    &mut vis_board.board,
-                    ui_state.current_player,
-                    edge_id,
-                    TurnPhase::Normal,
-                ) {
-                    Ok(_) => {
-                        // pintamos edge
-                        let mut edge_query = q.p1();
-                        for (ev, mut sprite) in edge_query.iter_mut() {
-                            if ev.edge_id == edge_id {
-                                sprite.color = player_color(ui_state.current_player);
-                            }
-                        }
-                    }
-                    Err(e) => println!("Error: {}", e),
+        CurrentTool::PlaceRoad => edge.map(|edge| Action::PlaceRoad { edge }),
+        CurrentTool::MoveRobber => {
+            let Some(tile) = ui_state.hovered_tile.filter(|t| legal.tiles.contains(t)) else {
+                return;
+            };
+            // con más de una víctima posible, se elige en el panel de robo
+            match robbable_players(&vis_board.board, tile, ui_state.current_player).as_slice() {
+                [] => Some(ui_state.robber_action(tile, None)),
+                &[victim] => Some(ui_state.robber_action(tile, Some(victim))),
+                _ => {
+                    ui_state.robber_tile = Some(tile);
+                    None
                 }
-            } else {
-                println!("No hay edge libre adyacente.");
             }
         }
-        CurrentTool::MoveRobber => {
-            println!("Mover ladrón: lo hacemos con click en tile más adelante.");
-        }
+    };
+    if let Some(action) = action {
+        requests.send(ActionRequest(action));
     }
 }
 
+/// Cierra las estadísticas, las imprime y las exporta a JSON.
+pub(crate) fn finish_visual_game(vis_board: &mut VisualBoard, winner: PlayerType) {
+    let VisualBoard { board, stats, .. } = vis_board;
+    stats.finish(board, winner);
+    stats.print_summary(board);
+    if let Err(e) = stats.export_json(STATS_FILE) {
+        println!("No se pudieron guardar las estadísticas: {}", e);
+    }
+}
 
+/// Las piezas según el tablero: forma y color de cada vértice, caminos
+/// construidos y dónde están el ladrón y el pirata.
 fn repaint_from_board(
     vis_board: Res<VisualBoard>,
-    mut q: ParamSet<(
-        Query<(&VertexViz, &mut Sprite)>,
-        Query<(&EdgeViz, &mut Sprite)>,
-    )>,
+    pieces: Res<PieceMeshes>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut vertices: Query<(&VertexViz, &mut Mesh2dHandle, &Handle<ColorMaterial>)>,
+    mut edges: Query<(&EdgeViz, &mut Sprite)>,
+    mut tile_pieces: Query<(&TilePiece, &mut Transform, &mut Visibility), Without<Slide>>,
 ) {
     // si el Board no cambió, no hacemos nada
     if !vis_board.is_changed() {
         return;
     }
+    let board = &vis_board.board;
+    let geometry = &vis_board.geometry;
 
-    // 1) vértices
-    {
-        let mut vertex_q = q.p0();
-        for (vv, mut sprite) in vertex_q.iter_mut() {
-            let v = &vis_board.board.vertices[vv.vertex_id];
-            sprite.color = match v.owner {
-                Some(p) => player_color(p),
-                None => Color::srgb(0.12, 0.12, 0.12),
-            };
+    // 1) vértices: punto vacío, asentamiento o ciudad
+    for (vv, mut mesh, material) in &mut vertices {
+        let v = &board.vertices[vv.vertex_id];
+        let (shape, color) = match (v.building, v.owner) {
+            (Some(BuildingType::Settlement), Some(p)) => (&pieces.settlement, player_color(p)),
+            (Some(BuildingType::City), Some(p)) => (&pieces.city, player_color(p)),
+            _ => (&pieces.spot, EMPTY_SPOT_COLOR),
+        };
+        if *mesh != *shape {
+            *mesh = shape.clone();
+        }
+        if let Some(material) = materials.get_mut(material) {
+            material.color = color;
         }
     }
 
-    // 2) edges
-    {
-        let mut edge_q = q.p1();
-        for (ev, mut sprite) in edge_q.iter_mut() {
-            let e = &vis_board.board.edges[ev.edge_id];
-            sprite.color = match e.owner {
-                Some(p) => player_color(p),
-                None => Color::srgb(0.45, 0.45, 0.45),
-            };
+    // 2) edges: los caminos más gruesos y del color del dueño
+    for (ev, mut sprite) in &mut edges {
+        let built = board.edges[ev.edge_id].owner;
+        let (_, _, size) = road_placement(geometry, board, ev.edge_id, built.is_some());
+        sprite.custom_size = Some(size);
+        sprite.color = built.map_or(EMPTY_ROAD_COLOR, player_color);
+    }
+
+    // 3) ladrón y pirata; los que se están deslizando los termina `visual_fx`
+    for (piece, mut transform, mut visibility) in &mut tile_pieces {
+        let tile = board.tiles.iter().position(|t| match piece {
+            TilePiece::Robber => t.has_robber,
+            TilePiece::Pirate => t.has_pirate,
+        });
+        match tile {
+            Some(tile) => {
+                transform.translation = robber_spot(geometry, tile).extend(transform.translation.z);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
         }
     }
 }
@@ -379,34 +944,130 @@ fn repaint_from_board(
 fn update_ui_text(
     vis_board: Res<VisualBoard>,
     ui_state: Res<GameUiState>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
     mut q: Query<&mut Text, With<UiTextTag>>,
 ) {
     let mut text = q.single_mut();
-    let hovered = ui_state
-        .hovered_vertex
-        .map(|v| v.to_string())
-        .unwrap_or_else(|| "-".to_string());
-
-    let tool = match ui_state.current_tool {
-        CurrentTool::PlaceSettlement => "Asentamiento",
-        CurrentTool::PlaceRoad => "Camino",
-        CurrentTool::MoveRobber => "Ladrón",
-    };
+    let show = |id: Option<usize>| id.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+    let hovered = format!(
+        "vértice {} | borde {} | casilla {}",
+        show(ui_state.hovered_vertex),
+        show(ui_state.hovered_edge),
+        show(ui_state.hovered_tile)
+    );
+
+    let tool = ui_state.current_tool.label();
 
+    // la mano y los puntos están en los paneles de `visual_hud`
+    let view = BoardView::new(&vis_board.board, Some(ui_state.current_player));
+    let phase = local
+        .as_deref()
+        .map(|l| {
+            let clock = l.time_left().map(|left| format!(" ({}s)", left.as_secs())).unwrap_or_default();
+            format!("Turno de: {:?} | Fase: {}{}\n", l.game.current_player(), phase_label(&l.game.phase), clock)
+        })
+        .unwrap_or_default();
     text.sections[0].value = format!(
-        "Jugador: {:?}\nHerramienta: {}\nHover vértice: {}\nCartas dev: {}\n",
+        "Jugador: {:?}\n{}Herramienta: {}\nHover: {}\nCartas dev: {}\n",
         ui_state.current_player,
+        phase,
         tool,
         hovered,
-        vis_board.board.development_cards.len()
+        view.deck_size
     );
+
+    if let Some(session) = remote.as_deref() {
+        if let Some(snapshot) = session.state.snapshot.as_ref() {
+            let phase = phase_label(&snapshot.phase);
+            let who = match session.state.spectating {
+                Some(_) => "Espectador".to_string(),
+                None => format!("{:?}", ui_state.current_player),
+            };
+            // el comentarista ve todas las manos
+            // el comentarista ve todas las manos; los demás, lo que se deduce contando cartas
+            let hands: String = match (&session.state.counter, snapshot.omniscient) {
+                (_, true) => snapshot
+                    .board
+                    .players
+                    .iter()
+                    .map(|p| format!("{:?}: {:?}\n", p.id, p.resources))
+                    .collect(),
+                (Some(counter), false) => snapshot
+                    .turn_order
+                    .iter()
+                    .filter(|&&p| Some(p) != snapshot.viewer)
+                    .map(|&p| format!("{:?} ~ {}\n", p, format_ranges(counter, p)))
+                    .collect(),
+                (None, false) => String::new(),
+            };
+            let clock = session
+                .state
+                .time_left()
+                .map(|left| format!(" ({}s)", left.as_secs()))
+                .unwrap_or_default();
+            text.sections[0].value = format!(
+                "Jugador: {} | Turno de: {:?}\nFase: {}{}\nHerramienta: {}\nHover: {}\nCartas dev: {}\n{}{}\n",
+                who,
+                snapshot.current_player,
+                phase,
+                clock,
+                tool,
+                hovered,
+                snapshot.deck_size,
+                hands,
+                session.log.join("\n")
+            );
+        }
+    }
+}
+
+/// Cuando hay ganador, muestra el resumen de estadísticas encima del tablero.
+fn show_summary_screen(
+    mut commands: Commands,
+    vis_board: Res<VisualBoard>,
+    asset_server: Res<AssetServer>,
+    existing: Query<Entity, With<SummaryTag>>,
+) {
+    if vis_board.stats.winner.is_none() || !existing.is_empty() {
+        return;
+    }
+
+    let font = asset_server.load("FiraSans-Bold.ttf");
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
+                z_index: ZIndex::Global(10),
+                ..Default::default()
+            },
+            SummaryTag,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                vis_board.stats.summary(&vis_board.board),
+                TextStyle {
+                    font,
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
 }
 
 // =====================================================
 // HELPERS DE POSICIÓN
 // =====================================================
 
-fn player_color(p: PlayerType) -> Color {
+pub(crate) fn player_color(p: PlayerType) -> Color {
     match p {
         PlayerType::Player1 => Color::srgb(0.9, 0.25, 0.25),
         PlayerType::Player2 => Color::srgb(0.25, 0.9, 0.25),
@@ -415,98 +1076,94 @@ fn player_color(p: PlayerType) -> Color {
     }
 }
 
-/// Genera los centros de los 19 tiles usando TU orden de setup.rs
-fn generate_tile_centers() -> Vec<Vec2> {
-    // parámetros de layout
-    let tile_w: f32 = 110.0;
-    let tile_h: f32 = 95.0;
-    let start_y: f32 = 200.0;
-    let center_x: f32 = 0.0;
+impl TilePiece {
+    pub(crate) fn color(self) -> Color {
+        match self {
+            TilePiece::Robber => Color::srgb(0.18, 0.18, 0.2),
+            TilePiece::Pirate => Color::srgb(0.08, 0.1, 0.28),
+        }
+    }
+}
 
-    let rows: Vec<Vec<usize>> = vec![
-        vec![0, 1, 2],
-        vec![11, 12, 13, 3],
-        vec![10, 17, 18, 14, 4],
-        vec![9, 16, 15, 5],
-        vec![8, 7, 6],
-    ];
+/// Dónde se para el ladrón: al costado de la ficha, para que se siga leyendo el número.
+pub(crate) fn robber_spot(geometry: &BoardGeometry, tile: TileId) -> Vec2 {
+    geometry.tile_centers[tile] + Vec2::new(geometry.layout.size * 0.5, -geometry.layout.size * 0.1)
+}
 
-    let mut centers = vec![Vec2::ZERO; 19];
+/// Centro, ángulo y tamaño del camino de un borde; vacío es una línea fina.
+fn road_placement(geometry: &BoardGeometry, board: &Board, edge: EdgeId, built: bool) -> (Vec2, f32, Vec2) {
+    let (a, b) = geometry.edge_ends(board, edge);
+    let dir = b - a;
+    let size = if built {
+        Vec2::new(dir.length() - 2.0 * ROAD_INSET, ROAD_WIDTH)
+    } else {
+        Vec2::new(dir.length() - ROAD_INSET, 3.0)
+    };
+    ((a + b) / 2.0, dir.y.atan2(dir.x), size)
+}
 
-    for (row_idx, row) in rows.iter().enumerate() {
-        let y = start_y - (row_idx as f32) * tile_h;
-        let row_len = row.len() as f32;
-        let total_w = (row_len - 1.0) * tile_w;
-        let start_x = center_x - total_w / 2.0;
+/// Casita: base cuadrada y techo a dos aguas.
+const SETTLEMENT_SHAPE: [Vec2; 5] = [
+    Vec2::new(-8.0, -7.0),
+    Vec2::new(8.0, -7.0),
+    Vec2::new(8.0, 3.0),
+    Vec2::new(0.0, 11.0),
+    Vec2::new(-8.0, 3.0),
+];
 
-        for (col_idx, &tile_id) in row.iter().enumerate() {
-            let x = start_x + (col_idx as f32) * tile_w;
-            centers[tile_id] = Vec2::new(x, y);
-        }
-    }
+/// Ciudad: más ancha, con una torre a la izquierda.
+const CITY_SHAPE: [Vec2; 7] = [
+    Vec2::new(-12.0, -8.0),
+    Vec2::new(12.0, -8.0),
+    Vec2::new(12.0, 3.0),
+    Vec2::new(0.0, 3.0),
+    Vec2::new(0.0, 9.0),
+    Vec2::new(-6.0, 15.0),
+    Vec2::new(-12.0, 9.0),
+];
 
-    centers
+/// Malla de un polígono armada en abanico desde `center`, que tiene que ver todas las esquinas.
+fn polygon_mesh(points: &[Vec2], center: Vec2) -> Mesh {
+    let n = points.len() as u32;
+    let positions: Vec<[f32; 3]> = std::iter::once(center).chain(points.iter().copied()).map(|p| [p.x, p.y, 0.0]).collect();
+    let indices = (0..n).flat_map(|i| [0, i + 1, (i + 1) % n + 1]).collect();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; positions.len()])
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; positions.len()])
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
 }
 
-/// busca una tile que use ese vértice y devuelve (tile_idx, corner_idx)
-fn find_tile_and_corner_for_vertex(board: &Board, vertex_id: usize) -> Option<(usize, usize)> {
-    for (ti, tile) in board.tiles.iter().enumerate() {
-        for (ci, &v) in tile.vertices.iter().enumerate() {
-            if v == vertex_id {
-                return Some((ti, ci));
-            }
-        }
-    }
-    None
-}
+/// Rojo de las fichas 6 y 8.
+const HOT_NUMBER_COLOR: Color = Color::srgb(0.8, 0.1, 0.1);
 
-// dibujamos los 6 vértices de un hex pointy (arriba) con radio dado
-fn corner_offset_pointy(corner_idx: usize, radius: f32) -> Vec2 {
-    let angle_deg: f32 = match corner_idx {
-        0 => 90.0,
-        1 => 30.0,
-        2 => -30.0,
-        3 => -90.0,
-        4 => -150.0,
-        5 => 150.0,
-        _ => 0.0,
-    };
-    let rad = angle_deg.to_radians();
-    Vec2::new(rad.cos() * radius, rad.sin() * radius)
-}
-
-/// posición world de un vértice
-fn vertex_world_pos(vis_board: &VisualBoard, vertex_id: usize) -> Option<Vec2> {
-    let mut acc = Vec2::ZERO;
-    let mut count = 0.0_f32;
-
-    for (tile_idx, tile) in vis_board.board.tiles.iter().enumerate() {
-        for (corner_idx, &v) in tile.vertices.iter().enumerate() {
-            if v == vertex_id {
-                let center = vis_board.tile_centers[tile_idx];
-                let off = corner_offset_pointy(corner_idx, 55.0);
-                acc += center + off;
-                count += 1.0;
-            }
-        }
+pub(crate) fn terrain_color(material: MaterialType) -> Color {
+    match material {
+        MaterialType::Wood   => Color::srgb(0.13, 0.45, 0.18),
+        MaterialType::Brick  => Color::srgb(0.72, 0.36, 0.22),
+        MaterialType::Sheep  => Color::srgb(0.6, 0.82, 0.4),
+        MaterialType::Wheat  => Color::srgb(0.93, 0.78, 0.3),
+        MaterialType::Stone  => Color::srgb(0.55, 0.56, 0.62),
+        MaterialType::Dessert => Color::srgb(0.9, 0.83, 0.6),
+        MaterialType::Gold   => Color::srgb(0.95, 0.75, 0.15),
+        MaterialType::Sea    => Color::srgb(0.2, 0.45, 0.8),
+        // las mercancías no son casillas
+        MaterialType::Paper | MaterialType::Cloth | MaterialType::Coin => Color::srgb(0.5, 0.5, 0.5),
     }
+}
 
-    if count > 0.0 {
-        Some(acc / count)
-    } else {
-        None
-    }
+/// Puntitos de la ficha: las formas de sacar ese número con dos dados.
+fn pips(number: u8) -> u8 {
+    6 - (7 - number as i8).unsigned_abs()
 }
 
-/// edge libre adyacente a un vértice
-fn first_free_edge_adjacent_to(board: &Board, vertex_id: usize) -> Option<usize> {
-    for (i, e) in board.edges.iter().enumerate() {
-        if e.owner.is_some() {
-            continue;
-        }
-        if e.vertices.0 == vertex_id || e.vertices.1 == vertex_id {
-            return Some(i);
-        }
+fn harbor_label(port: PowerUp) -> &'static str {
+    match port {
+        PowerUp::Any3 => "3:1",
+        PowerUp::Wheat2 => "2:1\ntrigo",
+        PowerUp::Brick2 => "2:1\nladrillo",
+        PowerUp::Stone2 => "2:1\npiedra",
+        PowerUp::Sheep2 => "2:1\noveja",
+        PowerUp::Wood2 => "2:1\nmadera",
     }
-    None
 }
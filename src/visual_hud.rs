@@ -0,0 +1,560 @@
+// src/visual_hud.rs
+//
+// El tablero de puntos y la mano. A la derecha, un panel por jugador con
+// su color, puntos, cartas, caballeros, largo de su camino y las
+// insignias de Gran Ejército y Camino Más Largo; debajo, la tarjeta de
+// costos. Abajo, la mano de quien está frente a la pantalla como cartas,
+// sus cartas de desarrollo (se eligen con un click) y las piezas que le
+// quedan. Con una carta elegida, encima de la mano aparece qué falta
+// para jugarla y un botón para soltarla (también con Escape). Todo se lee
+// de una `BoardView`: de los rivales sólo se ve lo que se vería en la mesa.
+
+use bevy::prelude::*;
+
+use crate::development_cards::dev_card_limit_reached;
+use crate::engine::{BoardView, GamePhase};
+use crate::game_logic::{calculate_player_longest_road, CITY_COST, DEVELOPMENT_CARD_COST, ROAD_COST, SETTLEMENT_COST};
+use crate::types::*;
+use crate::visual_game::{player_color, terrain_color, AppState, GameUiState, VisualBoard};
+use crate::visual_net::RemoteSession;
+use crate::visual_turns::{material_label, turn_status, LocalGame, TurnStatus};
+
+const PANEL_COLOR: Color = Color::srgba(0.08, 0.09, 0.12, 0.85);
+const ACTIVE_PANEL_COLOR: Color = Color::srgba(0.2, 0.23, 0.3, 0.95);
+const CARD_ENABLED: Color = Color::srgb(0.45, 0.3, 0.55);
+const CARD_DISABLED: Color = Color::srgb(0.22, 0.2, 0.25);
+const CARD_SELECTED: Color = Color::srgb(0.7, 0.45, 0.85);
+const ALL_PLAYERS: [PlayerType; 4] = [PlayerType::Player1, PlayerType::Player2, PlayerType::Player3, PlayerType::Player4];
+const DEV_CARDS: [DevelopmentCard; 5] = [
+    DevelopmentCard::Knight,
+    DevelopmentCard::RoadBuilding,
+    DevelopmentCard::YearOfPlenty,
+    DevelopmentCard::Monopoly,
+    DevelopmentCard::VictoryPoint,
+];
+
+// =====================================================
+// PLUGIN
+// =====================================================
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::Playing), spawn_hud)
+            .add_systems(Update, (
+                update_player_panels,
+                update_hand,
+                dev_card_buttons,
+                update_dev_cards,
+                dev_card_cancel,
+                update_dev_card_hint,
+            ).run_if(in_state(AppState::Playing)));
+    }
+}
+
+// =====================================================
+// COMPONENTES
+// =====================================================
+
+#[derive(Component)]
+pub(crate) struct PlayerPanel(pub(crate) PlayerType);
+
+#[derive(Component)]
+struct PlayerPanelText(PlayerType);
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum Badge {
+    LargestArmy,
+    LongestRoad,
+}
+
+/// Insignia de un jugador.
+#[derive(Component)]
+struct PlayerBadge(PlayerType, Badge);
+
+#[derive(Component)]
+struct HandRow;
+
+#[derive(Component)]
+struct ResourceCard(MaterialType);
+
+#[derive(Component)]
+struct ResourceCardText(MaterialType);
+
+#[derive(Component)]
+struct DevCardButton(DevelopmentCard);
+
+#[derive(Component)]
+struct DevCardText(DevelopmentCard);
+
+#[derive(Component)]
+struct PiecesText;
+
+#[derive(Component)]
+struct DevCardHint;
+
+#[derive(Component)]
+struct DevCardHintText;
+
+#[derive(Component)]
+struct DevCardCancel;
+
+// =====================================================
+// TEXTOS
+// =====================================================
+
+pub(crate) fn dev_card_label(card: DevelopmentCard) -> &'static str {
+    match card {
+        DevelopmentCard::Knight => "Caballero",
+        DevelopmentCard::RoadBuilding => "Construcción de caminos",
+        DevelopmentCard::YearOfPlenty => "Año de la Abundancia",
+        DevelopmentCard::Monopoly => "Monopolio",
+        DevelopmentCard::VictoryPoint => "Punto de victoria",
+    }
+}
+
+fn cost_line(name: &str, cost: &[(MaterialType, u8)]) -> String {
+    let parts: Vec<String> = cost.iter().map(|&(m, n)| format!("{} {}", n, material_label(m))).collect();
+    format!("{}: {}", name, parts.join(", "))
+}
+
+/// ¿Puede jugar esa carta ahora? El caballero vale también antes de tirar.
+pub(crate) fn dev_card_playable(status: &TurnStatus, board: &Board, card: DevelopmentCard) -> bool {
+    let Some(me) = status.me else {
+        return false;
+    };
+    let Some(index) = board.players.iter().position(|p| p.id == me) else {
+        return false;
+    };
+    let phase_ok = match card {
+        DevelopmentCard::VictoryPoint => false,
+        DevelopmentCard::Knight => matches!(status.phase, GamePhase::Roll | GamePhase::Main),
+        _ => status.phase == GamePhase::Main,
+    };
+    phase_ok
+        && status.can_act()
+        && board.players[index].dev_cards.contains(&card)
+        && !dev_card_limit_reached(board, index)
+}
+
+/// Qué falta para jugar la carta elegida.
+fn dev_card_hint(ui_state: &GameUiState) -> Option<String> {
+    let text = match ui_state.selected_dev_card? {
+        DevelopmentCard::Knight if ui_state.robber_tile.is_some() => "Caballero: elige a quién robar".to_string(),
+        DevelopmentCard::Knight => "Caballero: elige la casilla del ladrón".to_string(),
+        DevelopmentCard::RoadBuilding => {
+            format!("Construcción de caminos: camino {}/2", ui_state.free_roads.len() + 1)
+        }
+        DevelopmentCard::YearOfPlenty => "Año de la Abundancia: elige 2 cartas del banco".to_string(),
+        DevelopmentCard::Monopoly => "Monopolio: elige el recurso que te darán todos".to_string(),
+        DevelopmentCard::VictoryPoint => return None,
+    };
+    Some(text)
+}
+
+/// La mesa vista desde quien está frente a la pantalla.
+fn table_view(local: Option<&LocalGame>, remote: Option<&RemoteSession>, viewer: PlayerType) -> Option<BoardView> {
+    if let Some(local) = local {
+        return Some(BoardView::new(&local.game.board, Some(viewer)));
+    }
+    let snapshot = remote?.state.snapshot.as_ref()?;
+    Some(BoardView {
+        viewer: snapshot.viewer,
+        omniscient: snapshot.omniscient,
+        board: snapshot.board.clone(),
+        hand_sizes: snapshot.hand_sizes.clone(),
+        dev_card_counts: snapshot.dev_card_counts.clone(),
+        deck_size: snapshot.deck_size,
+    })
+}
+
+// =====================================================
+// STARTUP
+// =====================================================
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("FiraSans-Bold.ttf");
+    let text_style = TextStyle { font: font.clone(), font_size: 15.0, color: Color::WHITE };
+    let small_style = TextStyle { font: font.clone(), font_size: 12.0, color: Color::WHITE };
+
+    // a la derecha, debajo de los dados: los jugadores y los costos
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(70.0),
+                right: Val::Px(10.0),
+                width: Val::Px(240.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|column| {
+            for player in ALL_PLAYERS {
+                column
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                padding: UiRect::all(Val::Px(6.0)),
+                                border: UiRect::left(Val::Px(6.0)),
+                                row_gap: Val::Px(4.0),
+                                display: Display::None,
+                                ..Default::default()
+                            },
+                            background_color: PANEL_COLOR.into(),
+                            border_color: player_color(player).into(),
+                            ..Default::default()
+                        },
+                        PlayerPanel(player),
+                    ))
+                    .with_children(|panel| {
+                        panel.spawn((TextBundle::from_section("", text_style.clone()), PlayerPanelText(player)));
+                        panel
+                            .spawn(NodeBundle {
+                                style: Style { column_gap: Val::Px(4.0), ..Default::default() },
+                                ..Default::default()
+                            })
+                            .with_children(|badges| {
+                                for (badge, label) in [(Badge::LargestArmy, "Gran Ejército"), (Badge::LongestRoad, "Camino Más Largo")] {
+                                    badges
+                                        .spawn((
+                                            NodeBundle {
+                                                style: Style {
+                                                    padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+                                                    display: Display::None,
+                                                    ..Default::default()
+                                                },
+                                                background_color: Color::srgb(0.75, 0.6, 0.15).into(),
+                                                ..Default::default()
+                                            },
+                                            PlayerBadge(player, badge),
+                                        ))
+                                        .with_children(|b| {
+                                            b.spawn(TextBundle::from_section(label, small_style.clone()));
+                                        });
+                                }
+                            });
+                    });
+            }
+
+            // tarjeta de costos
+            let costs = [
+                cost_line("Camino", ROAD_COST),
+                cost_line("Asentamiento", SETTLEMENT_COST),
+                cost_line("Ciudad", CITY_COST),
+                cost_line("Desarrollo", DEVELOPMENT_CARD_COST),
+            ];
+            column
+                .spawn(NodeBundle {
+                    style: Style { padding: UiRect::all(Val::Px(6.0)), ..Default::default() },
+                    background_color: PANEL_COLOR.into(),
+                    ..Default::default()
+                })
+                .with_children(|card| {
+                    card.spawn(TextBundle::from_section(
+                        format!("Costos\n{}", costs.join("\n")),
+                        small_style.clone(),
+                    ));
+                });
+        });
+
+    // abajo, encima de la barra de acciones: la mano, las cartas de desarrollo y las piezas
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(60.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::End,
+                    column_gap: Val::Px(6.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            HandRow,
+        ))
+        .with_children(|row| {
+            for material in MaterialType::RESOURCES {
+                row.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(62.0),
+                            height: Val::Px(84.0),
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            border: UiRect::all(Val::Px(2.0)),
+                            ..Default::default()
+                        },
+                        background_color: terrain_color(material).into(),
+                        border_color: Color::srgb(0.95, 0.92, 0.85).into(),
+                        ..Default::default()
+                    },
+                    ResourceCard(material),
+                ))
+                .with_children(|card| {
+                    card.spawn((
+                        TextBundle::from_section("", text_style.clone()).with_text_justify(JustifyText::Center),
+                        ResourceCardText(material),
+                    ));
+                });
+            }
+
+            for card in DEV_CARDS {
+                row.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(80.0),
+                            height: Val::Px(84.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(4.0)),
+                            display: Display::None,
+                            ..Default::default()
+                        },
+                        background_color: CARD_DISABLED.into(),
+                        ..Default::default()
+                    },
+                    DevCardButton(card),
+                ))
+                .with_children(|b| {
+                    b.spawn((
+                        TextBundle::from_section("", small_style.clone()).with_text_justify(JustifyText::Center),
+                        DevCardText(card),
+                    ));
+                });
+            }
+
+            row.spawn(NodeBundle {
+                style: Style { padding: UiRect::all(Val::Px(6.0)), ..Default::default() },
+                background_color: PANEL_COLOR.into(),
+                ..Default::default()
+            })
+            .with_children(|pieces| {
+                pieces.spawn((TextBundle::from_section("", small_style.clone()), PiecesText));
+            });
+        });
+
+    // encima de la mano: la carta elegida, qué falta y cómo soltarla
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(152.0),
+                    left: Val::Percent(30.0),
+                    width: Val::Percent(40.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(6.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..Default::default()
+            },
+            DevCardHint,
+        ))
+        .with_children(|hint| {
+            hint.spawn((TextBundle::from_section("", text_style.clone()), DevCardHintText));
+            hint.spawn((
+                ButtonBundle {
+                    style: Style { padding: UiRect::all(Val::Px(6.0)), ..Default::default() },
+                    background_color: CARD_ENABLED.into(),
+                    ..Default::default()
+                },
+                DevCardCancel,
+            ))
+            .with_children(|b| {
+                b.spawn(TextBundle::from_section("Cancelar (Esc)", small_style.clone()));
+            });
+        });
+}
+
+// =====================================================
+// SISTEMAS
+// =====================================================
+
+/// Un panel por jugador; resaltado el de turno.
+fn update_player_panels(
+    ui_state: Res<GameUiState>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut panels: Query<(&PlayerPanel, &mut Style, &mut BackgroundColor), Without<PlayerBadge>>,
+    mut texts: Query<(&PlayerPanelText, &mut Text)>,
+    mut badges: Query<(&PlayerBadge, &mut Style), Without<PlayerPanel>>,
+) {
+    let Some(view) = table_view(local.as_deref(), remote.as_deref(), ui_state.current_player) else {
+        return;
+    };
+    let current = turn_status(local.as_deref(), remote.as_deref()).map(|s| s.current);
+    let board = &view.board;
+
+    for (&PlayerPanel(id), mut style, mut color) in &mut panels {
+        let seated = board.players.iter().any(|p| p.id == id);
+        style.display = if seated { Display::Flex } else { Display::None };
+        *color = if current == Some(id) { ACTIVE_PANEL_COLOR } else { PANEL_COLOR }.into();
+    }
+    for (&PlayerPanelText(id), mut text) in &mut texts {
+        let Some(player) = board.players.iter().find(|p| p.id == id) else {
+            continue;
+        };
+        text.sections[0].value = format!(
+            "{:?}: {} PV\nCartas: {} | Desarrollo: {}\nCaballeros: {} | Camino: {}",
+            id,
+            player.victory_points,
+            view.hand_size(id),
+            view.dev_card_count(id),
+            player.knights_played,
+            calculate_player_longest_road(board, id),
+        );
+    }
+    for (&PlayerBadge(id, badge), mut style) in &mut badges {
+        let holder = match badge {
+            Badge::LargestArmy => board.largest_army,
+            Badge::LongestRoad => board.longest_road,
+        };
+        style.display = if holder == Some(id) { Display::Flex } else { Display::None };
+    }
+}
+
+/// La mano de quien está frente a la pantalla; los espectadores no tienen.
+fn update_hand(
+    ui_state: Res<GameUiState>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut row: Query<&mut Style, With<HandRow>>,
+    mut cards: Query<(&ResourceCard, &mut BackgroundColor)>,
+    mut card_texts: Query<(&ResourceCardText, &mut Text), Without<PiecesText>>,
+    mut pieces_text: Query<&mut Text, With<PiecesText>>,
+) {
+    let Ok(mut row) = row.get_single_mut() else {
+        return;
+    };
+    let view = table_view(local.as_deref(), remote.as_deref(), ui_state.current_player);
+    let Some(me) = view.as_ref().and_then(|v| v.me()) else {
+        row.display = Display::None;
+        return;
+    };
+    row.display = Display::Flex;
+
+    let count = |m: MaterialType| me.resources.get(&m).copied().unwrap_or(0);
+    for (&ResourceCard(material), mut color) in &mut cards {
+        // las que no tiene, apagadas
+        let alpha = if count(material) > 0 { 1.0 } else { 0.3 };
+        *color = terrain_color(material).with_alpha(alpha).into();
+    }
+    for (&ResourceCardText(material), mut text) in &mut card_texts {
+        text.sections[0].value = format!("{}\n{}", material_label(material), count(material));
+    }
+    if let Ok(mut text) = pieces_text.get_single_mut() {
+        text.sections[0].value = format!(
+            "Piezas\nAsentamientos: {}\nCiudades: {}\nCaminos: {}",
+            me.settlement_quantity, me.city_quantity, me.road_quantity
+        );
+    }
+}
+
+/// Click en una carta de desarrollo: la elige (o la suelta).
+fn dev_card_buttons(
+    buttons: Query<(&Interaction, &DevCardButton), Changed<Interaction>>,
+    vis_board: Res<VisualBoard>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut ui_state: ResMut<GameUiState>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    for (interaction, &DevCardButton(card)) in &buttons {
+        if *interaction != Interaction::Pressed || !dev_card_playable(&status, &vis_board.board, card) {
+            continue;
+        }
+        let same = ui_state.selected_dev_card == Some(card);
+        ui_state.drop_dev_card();
+        if !same {
+            ui_state.selected_dev_card = Some(card);
+        }
+    }
+}
+
+/// Las cartas de desarrollo de la mano, agrupadas; apagadas las que no se pueden jugar ahora.
+fn update_dev_cards(
+    vis_board: Res<VisualBoard>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut ui_state: ResMut<GameUiState>,
+    mut buttons: Query<(&DevCardButton, &mut Style, &mut BackgroundColor)>,
+    mut texts: Query<(&DevCardText, &mut Text)>,
+) {
+    let status = turn_status(local.as_deref(), remote.as_deref());
+    let me = status.as_ref().and_then(|s| s.me);
+    let hand: &[DevelopmentCard] = vis_board
+        .board
+        .players
+        .iter()
+        .find(|p| Some(p.id) == me)
+        .map_or(&[], |p| p.dev_cards.as_slice());
+    let playable = |card| status.as_ref().is_some_and(|s| dev_card_playable(s, &vis_board.board, card));
+
+    if let Some(card) = ui_state.selected_dev_card {
+        if !playable(card) {
+            ui_state.drop_dev_card();
+        }
+    }
+    for (&DevCardButton(card), mut style, mut color) in &mut buttons {
+        let count = hand.iter().filter(|&&c| c == card).count();
+        style.display = if count > 0 { Display::Flex } else { Display::None };
+        *color = match card {
+            _ if ui_state.selected_dev_card == Some(card) => CARD_SELECTED,
+            _ if playable(card) => CARD_ENABLED,
+            _ => CARD_DISABLED,
+        }
+        .into();
+    }
+    for (&DevCardText(card), mut text) in &mut texts {
+        let count = hand.iter().filter(|&&c| c == card).count();
+        text.sections[0].value = format!("{}\nx{}", dev_card_label(card), count);
+    }
+}
+
+/// Soltar la carta elegida: con el botón o con Escape.
+fn dev_card_cancel(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<DevCardCancel>)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut ui_state: ResMut<GameUiState>,
+) {
+    if ui_state.selected_dev_card.is_none() {
+        return;
+    }
+    let pressed = buttons.iter().any(|i| *i == Interaction::Pressed);
+    if pressed || keys.just_pressed(KeyCode::Escape) {
+        ui_state.drop_dev_card();
+    }
+}
+
+fn update_dev_card_hint(
+    ui_state: Res<GameUiState>,
+    mut panel: Query<&mut Style, With<DevCardHint>>,
+    mut text: Query<&mut Text, With<DevCardHintText>>,
+) {
+    let Ok(mut style) = panel.get_single_mut() else {
+        return;
+    };
+    let Some(hint) = dev_card_hint(&ui_state) else {
+        style.display = Display::None;
+        return;
+    };
+    style.display = Display::Flex;
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = hint;
+    }
+}
@@ -0,0 +1,394 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use std::sync::mpsc::TryRecvError;
+use std::sync::Mutex;
+
+use crate::engine::Action;
+use crate::net::{ClientState, ServerConnection, ServerMessage, SpectatorMode};
+use crate::types::*;
+use crate::hex_layout::BoardGeometry;
+use crate::visual_game::{ActionRequest, AppState, BoardFlow, GameUiState, PlayedEvent, VisualBoard};
+
+// =====================================================
+// PLUGIN
+// =====================================================
+
+/// Modo en red del juego visual: pantalla de conexión, y después el
+/// `VisualBoard` se reconstruye con lo que manda el servidor.
+pub struct NetworkClientPlugin;
+
+impl Plugin for NetworkClientPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ConnectForm>()
+            .add_systems(OnEnter(AppState::Connecting), spawn_connect_screen)
+            .add_systems(OnExit(AppState::Connecting), despawn_connect_screen)
+            .add_systems(Update, (
+                connect_screen_typing,
+                connect_screen_buttons,
+                update_connect_screen,
+            ).run_if(in_state(AppState::Connecting)))
+            .add_systems(Update, poll_server.run_if(resource_exists::<RemoteSession>).in_set(BoardFlow::Apply))
+            .add_systems(Update, send_action_requests
+                .run_if(in_state(AppState::Playing))
+                .run_if(resource_exists::<RemoteSession>));
+    }
+}
+
+// =====================================================
+// RECURSOS
+// =====================================================
+
+/// Conexión abierta con el servidor y lo que sabemos de la partida.
+#[derive(Resource)]
+pub struct RemoteSession {
+    conn: Mutex<ServerConnection>,
+    pub state: ClientState,
+    pub connected: bool,
+    /// últimos eventos/errores, para el HUD
+    pub log: Vec<String>,
+}
+
+impl RemoteSession {
+    fn new(conn: ServerConnection) -> Self {
+        Self { conn: Mutex::new(conn), state: ClientState::default(), connected: true, log: Vec::new() }
+    }
+
+    /// Sin asiento: sólo miramos.
+    pub fn is_spectator(&self) -> bool {
+        self.state.spectating.is_some()
+    }
+
+    pub fn send_action(&self, action: Action) {
+        if self.is_spectator() {
+            return;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        if let Err(e) = conn.send_action(action) {
+            println!("Error enviando la acción: {}", e);
+        }
+    }
+
+    fn push_log(&mut self, line: String) {
+        println!("{}", line);
+        self.log.push(line);
+        if self.log.len() > 6 {
+            self.log.remove(0);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct ConnectForm {
+    address: String,
+    seat: Option<PlayerType>,
+    /// `Some` = entrar como espectador en vez de pedir asiento.
+    spectate: Option<SpectatorMode>,
+    status: String,
+}
+
+impl Default for ConnectForm {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:7878".to_string(),
+            seat: None,
+            spectate: None,
+            status: "Escribe la dirección y presiona Enter.".to_string(),
+        }
+    }
+}
+
+// =====================================================
+// COMPONENTES
+// =====================================================
+
+#[derive(Component)]
+struct ConnectScreenTag;
+
+#[derive(Component)]
+struct ConnectTextTag;
+
+#[derive(Component)]
+struct SeatButton(Option<PlayerType>);
+
+#[derive(Component)]
+struct SpectateButton(SpectatorMode);
+
+#[derive(Component)]
+struct ConnectButton;
+
+// =====================================================
+// PANTALLA DE CONEXIÓN
+// =====================================================
+
+fn spawn_connect_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("FiraSans-Bold.ttf");
+    let text_style = TextStyle { font: font.clone(), font_size: 22.0, color: Color::WHITE };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ConnectScreenTag,
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section("", text_style.clone()), ConnectTextTag));
+
+            // fila de asientos
+            parent
+                .spawn(NodeBundle {
+                    style: Style { column_gap: Val::Px(8.0), ..Default::default() },
+                    ..Default::default()
+                })
+                .with_children(|row| {
+                    let seats = [
+                        ("Auto", None),
+                        ("P1", Some(PlayerType::Player1)),
+                        ("P2", Some(PlayerType::Player2)),
+                        ("P3", Some(PlayerType::Player3)),
+                        ("P4", Some(PlayerType::Player4)),
+                    ];
+                    for (label, seat) in seats {
+                        row.spawn((button_bundle(), SeatButton(seat)))
+                            .with_children(|b| {
+                                b.spawn(TextBundle::from_section(label, text_style.clone()));
+                            });
+                    }
+                });
+
+            // fila de espectador
+            parent
+                .spawn(NodeBundle {
+                    style: Style { column_gap: Val::Px(8.0), ..Default::default() },
+                    ..Default::default()
+                })
+                .with_children(|row| {
+                    let modes = [
+                        ("Mirar", SpectatorMode::Public),
+                        ("Comentar", SpectatorMode::Omniscient { delay_turns: 0 }),
+                        ("Comentar (-2 turnos)", SpectatorMode::Omniscient { delay_turns: 2 }),
+                    ];
+                    for (label, mode) in modes {
+                        row.spawn((button_bundle(), SpectateButton(mode)))
+                            .with_children(|b| {
+                                b.spawn(TextBundle::from_section(label, text_style.clone()));
+                            });
+                    }
+                });
+
+            parent
+                .spawn((button_bundle(), ConnectButton))
+                .with_children(|b| {
+                    b.spawn(TextBundle::from_section("Conectar", text_style.clone()));
+                });
+        });
+}
+
+fn button_bundle() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            padding: UiRect::axes(Val::Px(14.0), Val::Px(6.0)),
+            ..Default::default()
+        },
+        background_color: Color::srgb(0.25, 0.28, 0.35).into(),
+        ..Default::default()
+    }
+}
+
+fn despawn_connect_screen(mut commands: Commands, q: Query<Entity, With<ConnectScreenTag>>) {
+    for entity in q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Editar la dirección con el teclado; Enter conecta.
+fn connect_screen_typing(
+    mut commands: Commands,
+    mut keys: EventReader<KeyboardInput>,
+    mut form: ResMut<ConnectForm>,
+    session: Option<Res<RemoteSession>>,
+) {
+    for ev in keys.read() {
+        if ev.state != ButtonState::Pressed || session.is_some() {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Character(s) => form.address.push_str(s.as_str()),
+            Key::Backspace => {
+                form.address.pop();
+            }
+            Key::Enter => try_connect(&mut commands, &mut form),
+            _ => {}
+        }
+    }
+}
+
+fn connect_screen_buttons(
+    mut commands: Commands,
+    mut form: ResMut<ConnectForm>,
+    session: Option<Res<RemoteSession>>,
+    seat_q: Query<(&Interaction, &SeatButton), Changed<Interaction>>,
+    spectate_q: Query<(&Interaction, &SpectateButton), Changed<Interaction>>,
+    connect_q: Query<&Interaction, (Changed<Interaction>, With<ConnectButton>)>,
+) {
+    for (interaction, seat) in seat_q.iter() {
+        if *interaction == Interaction::Pressed {
+            form.seat = seat.0;
+            form.spectate = None;
+        }
+    }
+    for (interaction, mode) in spectate_q.iter() {
+        if *interaction == Interaction::Pressed {
+            form.spectate = Some(mode.0);
+        }
+    }
+    for interaction in connect_q.iter() {
+        if *interaction == Interaction::Pressed && session.is_none() {
+            try_connect(&mut commands, &mut form);
+        }
+    }
+}
+
+fn try_connect(commands: &mut Commands, form: &mut ConnectForm) {
+    match ServerConnection::connect(&form.address) {
+        Ok(mut conn) => match form.spectate {
+            Some(mode) => match conn.spectate(None, mode) {
+                Ok(()) => {
+                    form.status = "Conectado. Buscando una partida en juego...".to_string();
+                    commands.insert_resource(RemoteSession::new(conn));
+                }
+                Err(e) => form.status = format!("Error pidiendo la partida: {}", e),
+            },
+            None => match conn.join(form.seat) {
+            Ok(()) => {
+                form.status = "Conectado. Esperando al servidor...".to_string();
+                commands.insert_resource(RemoteSession::new(conn));
+            }
+                Err(e) => form.status = format!("Error pidiendo asiento: {}", e),
+            },
+        },
+        Err(e) => form.status = format!("No se pudo conectar: {}", e),
+    }
+}
+
+fn update_connect_screen(
+    form: Res<ConnectForm>,
+    mut text_q: Query<&mut Text, With<ConnectTextTag>>,
+    mut seat_q: Query<(&SeatButton, &mut BackgroundColor), Without<SpectateButton>>,
+    mut spectate_q: Query<(&SpectateButton, &mut BackgroundColor), Without<SeatButton>>,
+) {
+    if !form.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = text_q.get_single_mut() {
+        let seat = match (form.spectate, form.seat) {
+            (Some(SpectatorMode::Public), _) => "espectador".to_string(),
+            (Some(SpectatorMode::Omniscient { delay_turns }), _) => {
+                format!("comentarista ({} turnos de retraso)", delay_turns)
+            }
+            (None, Some(s)) => format!("{:?}", s),
+            (None, None) => "el primero libre".to_string(),
+        };
+        text.sections[0].value = format!(
+            "CATAN EN RED\n\nServidor: {}_\nAsiento: {}\n\n{}",
+            form.address, seat, form.status
+        );
+    }
+    for (mode, mut color) in spectate_q.iter_mut() {
+        *color = if Some(mode.0) == form.spectate {
+            Color::srgb(0.35, 0.55, 0.35).into()
+        } else {
+            Color::srgb(0.25, 0.28, 0.35).into()
+        };
+    }
+    for (seat, mut color) in seat_q.iter_mut() {
+        *color = if form.spectate.is_none() && seat.0 == form.seat {
+            Color::srgb(0.35, 0.55, 0.35).into()
+        } else {
+            Color::srgb(0.25, 0.28, 0.35).into()
+        };
+    }
+}
+
+// =====================================================
+// SINCRONIZACIÓN CON EL SERVIDOR
+// =====================================================
+
+/// Aplica lo que llegó del servidor y reconstruye el `VisualBoard`.
+fn poll_server(
+    mut session: ResMut<RemoteSession>,
+    mut vis_board: ResMut<VisualBoard>,
+    mut ui_state: ResMut<GameUiState>,
+    mut form: ResMut<ConnectForm>,
+    mut played: EventWriter<PlayedEvent>,
+    app_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let mut messages = Vec::new();
+    let mut disconnected = false;
+    {
+        let conn = session.conn.lock().unwrap();
+        loop {
+            match conn.incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    if disconnected && session.connected {
+        session.connected = false;
+        session.push_log("El servidor cerró la conexión.".to_string());
+        form.status = "El servidor cerró la conexión.".to_string();
+    }
+    if messages.is_empty() {
+        return;
+    }
+
+    for message in messages {
+        if let ServerMessage::Error { message } = &message {
+            session.push_log(format!("Servidor: {}", message));
+        }
+        for event in session.state.apply(message) {
+            session.push_log(event.to_string());
+            played.send(PlayedEvent(event));
+        }
+    }
+
+    if let Some(seat) = session.state.seat {
+        ui_state.current_player = seat;
+    }
+    let Some(snapshot) = session.state.snapshot.as_ref() else {
+        return;
+    };
+    vis_board.board = snapshot.board.clone();
+    if vis_board.geometry.tile_centers.len() != vis_board.board.tiles.len() {
+        vis_board.geometry = BoardGeometry::new(&vis_board.board);
+    }
+
+    if *app_state.get() == AppState::Connecting {
+        next_state.set(AppState::Playing);
+    }
+}
+
+/// Lo que pide la interfaz va directo al servidor.
+fn send_action_requests(mut requests: EventReader<ActionRequest>, session: Res<RemoteSession>) {
+    for ActionRequest(action) in requests.read() {
+        session.send_action(action.clone());
+    }
+}
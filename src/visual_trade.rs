@@ -0,0 +1,487 @@
+// src/visual_trade.rs
+//
+// Comercio en la ventana. El jugador de turno arma en la ventana de
+// comercio lo que da y lo que pide; con eso cambia con el banco (a la
+// tasa que le dan sus puertos, como en `trade_with_bank`) o le hace una
+// oferta a un rival o a todos. La oferta abierta se ve en un panel
+// aparte, donde los destinatarios la aceptan o la rechazan y quien la
+// hizo la puede retirar, con el tiempo que queda para responder. En la
+// partida local la pantalla pasa a cada destinatario, igual que con los
+// descartes.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::engine::{Action, GamePhase, PendingTrade};
+use crate::game_logic::{bank_rate, has_cards};
+use crate::rules::RuleSet;
+use crate::types::*;
+use crate::visual_game::{ActionRequest, AppState, VisualBoard};
+use crate::visual_net::RemoteSession;
+use crate::visual_turns::{
+    material_label, turn_status, LocalGame, TurnStatus, BUTTON_DISABLED, BUTTON_ENABLED, BUTTON_SELECTED,
+};
+
+const ALL_PLAYERS: [PlayerType; 4] = [PlayerType::Player1, PlayerType::Player2, PlayerType::Player3, PlayerType::Player4];
+
+// =====================================================
+// PLUGIN
+// =====================================================
+
+pub struct TradePlugin;
+
+impl Plugin for TradePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TradeDraft>()
+            .add_systems(OnEnter(AppState::Playing), spawn_trade_ui)
+            .add_systems(Update, (
+                trade_buttons,
+                update_trade_window,
+                update_trade_buttons,
+                offer_buttons,
+                update_offer_panel,
+            ).run_if(in_state(AppState::Playing)));
+    }
+}
+
+// =====================================================
+// RECURSOS Y COMPONENTES
+// =====================================================
+
+/// Lo que se está armando en la ventana de comercio (una entrada por carta).
+#[derive(Resource, Default)]
+pub struct TradeDraft {
+    pub open: bool,
+    give: Vec<MaterialType>,
+    get: Vec<MaterialType>,
+}
+
+#[derive(Component)]
+struct TradeWindow;
+
+#[derive(Component)]
+struct TradeText;
+
+/// Texto del botón de dar, con la tasa del banco.
+#[derive(Component)]
+struct GiveLabel(MaterialType);
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum TradeButton {
+    Give(MaterialType),
+    Get(MaterialType),
+    Clear,
+    Bank,
+    /// `None` = a todos los rivales.
+    Propose(Option<PlayerType>),
+    Close,
+}
+
+#[derive(Component)]
+struct OfferPanel;
+
+#[derive(Component)]
+struct OfferText;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum OfferButton {
+    Accept,
+    Decline,
+    Cancel,
+}
+
+// =====================================================
+// AUXILIARES
+// =====================================================
+
+/// "2 Madera, 1 Oveja"
+fn cards_label(materials: &[MaterialType]) -> String {
+    let parts: Vec<String> = MaterialType::RESOURCES
+        .iter()
+        .map(|&m| (m, materials.iter().filter(|&&c| c == m).count()))
+        .filter(|&(_, n)| n > 0)
+        .map(|(m, n)| format!("{} {}", n, material_label(m)))
+        .collect();
+    if parts.is_empty() {
+        "-".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// El cambio con el banco que arma la ventana, si es uno válido:
+/// tantas cartas de un material como pide su tasa, por una de otro.
+fn bank_trade(draft: &TradeDraft, rules: &RuleSet, me: &Player) -> Option<Action> {
+    let (&give, &get) = (draft.give.first()?, draft.get.first()?);
+    let single = draft.give.iter().all(|&m| m == give) && draft.get.len() == 1;
+    let rate = bank_rate(rules, me, give) as usize;
+    (single && give != get && draft.give.len() == rate).then_some(Action::BankTrade { give, get })
+}
+
+/// La oferta abierta y el orden de turno, de la partida local o del servidor.
+fn open_trade(local: Option<&LocalGame>, remote: Option<&RemoteSession>) -> Option<(PendingTrade, Vec<PlayerType>)> {
+    if let Some(local) = local {
+        return local.game.trade.clone().map(|t| (t, local.game.turn_order.clone()));
+    }
+    let snapshot = remote?.state.snapshot.as_ref()?;
+    snapshot.trade.clone().map(|t| (t, snapshot.turn_order.clone()))
+}
+
+/// Lo que queda para responder la oferta, si hay límite.
+fn offer_time_left(local: Option<&LocalGame>, remote: Option<&RemoteSession>) -> Option<Duration> {
+    if let Some(local) = local {
+        return local.game.clocks.decision_left();
+    }
+    let state = &remote?.state;
+    let left = state.snapshot.as_ref()?.clocks.decision_left()?;
+    Some(left.saturating_sub(state.received_at.map(|t| t.elapsed()).unwrap_or_default()))
+}
+
+/// La ventana sólo sirve en la fase principal del propio turno.
+fn can_trade(status: &TurnStatus) -> bool {
+    status.can_act() && status.phase == GamePhase::Main
+}
+
+fn me<'a>(status: &TurnStatus, board: &'a Board) -> Option<&'a Player> {
+    board.players.iter().find(|p| Some(p.id) == status.me)
+}
+
+// =====================================================
+// STARTUP
+// =====================================================
+
+fn spawn_trade_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("FiraSans-Bold.ttf");
+    let text_style = TextStyle { font, font_size: 16.0, color: Color::WHITE };
+    let button = |padding: f32| ButtonBundle {
+        style: Style { padding: UiRect::all(Val::Px(padding)), ..Default::default() },
+        background_color: BUTTON_ENABLED.into(),
+        ..Default::default()
+    };
+    let row = || NodeBundle {
+        style: Style { column_gap: Val::Px(6.0), align_items: AlignItems::Center, ..Default::default() },
+        ..Default::default()
+    };
+
+    // arriba al centro: la ventana de comercio
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Percent(25.0),
+                    width: Val::Percent(50.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(5),
+                ..Default::default()
+            },
+            TradeWindow,
+        ))
+        .with_children(|window| {
+            window.spawn((TextBundle::from_section("", text_style.clone()), TradeText));
+
+            window.spawn(row()).with_children(|give| {
+                give.spawn(TextBundle::from_section("Doy:", text_style.clone()));
+                for material in MaterialType::RESOURCES {
+                    give.spawn((button(6.0), TradeButton::Give(material))).with_children(|b| {
+                        b.spawn((TextBundle::from_section("", text_style.clone()), GiveLabel(material)));
+                    });
+                }
+            });
+            window.spawn(row()).with_children(|get| {
+                get.spawn(TextBundle::from_section("Pido:", text_style.clone()));
+                for material in MaterialType::RESOURCES {
+                    get.spawn((button(6.0), TradeButton::Get(material))).with_children(|b| {
+                        b.spawn(TextBundle::from_section(material_label(material), text_style.clone()));
+                    });
+                }
+            });
+            window.spawn(row()).with_children(|actions| {
+                let buttons = [
+                    (TradeButton::Bank, "Banco".to_string()),
+                    (TradeButton::Propose(None), "Ofrecer a todos".to_string()),
+                ]
+                .into_iter()
+                .chain(ALL_PLAYERS.map(|p| (TradeButton::Propose(Some(p)), format!("A {:?}", p))))
+                .chain([(TradeButton::Clear, "Borrar".to_string()), (TradeButton::Close, "Cerrar".to_string())]);
+                for (action, label) in buttons {
+                    actions.spawn((button(8.0), action)).with_children(|b| {
+                        b.spawn(TextBundle::from_section(label, text_style.clone()));
+                    });
+                }
+            });
+        });
+
+    // a la izquierda, a media altura: la oferta abierta
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(45.0),
+                    left: Val::Px(10.0),
+                    width: Val::Px(280.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(4),
+                ..Default::default()
+            },
+            OfferPanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn((TextBundle::from_section("", text_style.clone()), OfferText));
+            panel.spawn(row()).with_children(|actions| {
+                for (action, label) in
+                    [(OfferButton::Accept, "Aceptar"), (OfferButton::Decline, "Rechazar"), (OfferButton::Cancel, "Retirar")]
+                {
+                    actions.spawn((button(8.0), action)).with_children(|b| {
+                        b.spawn(TextBundle::from_section(label, text_style.clone()));
+                    });
+                }
+            });
+        });
+}
+
+// =====================================================
+// VENTANA DE COMERCIO
+// =====================================================
+
+fn trade_buttons(
+    buttons: Query<(&Interaction, &TradeButton), Changed<Interaction>>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    mut draft: ResMut<TradeDraft>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let Some(me) = me(&status, &vis_board.board) else {
+        return;
+    };
+    if !can_trade(&status) {
+        return;
+    }
+    for (interaction, &button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            TradeButton::Give(material) => {
+                // no se ofrece más de lo que se tiene
+                let mut give = draft.give.clone();
+                give.push(material);
+                if has_cards(me, &give) {
+                    draft.give = give;
+                }
+            }
+            TradeButton::Get(material) => draft.get.push(material),
+            TradeButton::Clear => {
+                draft.give.clear();
+                draft.get.clear();
+            }
+            TradeButton::Bank => match bank_trade(&draft, &vis_board.board.rules, me) {
+                Some(action) => {
+                    requests.send(ActionRequest(action));
+                    draft.give.clear();
+                    draft.get.clear();
+                }
+                None => println!("Para el banco: tantas cartas de un material como su tasa, por 1 de otro."),
+            },
+            TradeButton::Propose(to) => {
+                if draft.give.is_empty() || draft.get.is_empty() {
+                    println!("La oferta tiene que dar y pedir al menos una carta.");
+                    continue;
+                }
+                let offer = TradeOffer { to, give: draft.give.clone(), get: draft.get.clone() };
+                requests.send(ActionRequest(Action::ProposeTrade { offer }));
+            }
+            TradeButton::Close => draft.open = false,
+        }
+    }
+}
+
+/// Muestra la ventana en la fase principal del propio turno, con lo armado y las tasas del banco.
+fn update_trade_window(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    mut draft: ResMut<TradeDraft>,
+    mut window: Query<&mut Style, With<TradeWindow>>,
+    mut text: Query<&mut Text, (With<TradeText>, Without<GiveLabel>)>,
+    mut labels: Query<(&GiveLabel, &mut Text), Without<TradeText>>,
+) {
+    let Ok(mut style) = window.get_single_mut() else {
+        return;
+    };
+    let status = turn_status(local.as_deref(), remote.as_deref());
+    let me = status.as_ref().filter(|s| can_trade(s)).and_then(|s| me(s, &vis_board.board));
+    let Some(me) = me.filter(|_| draft.open) else {
+        style.display = Display::None;
+        // fuera del propio turno la ventana se cierra y se olvida lo armado
+        if status.as_ref().is_some_and(|s| !can_trade(s)) && (draft.open || !draft.give.is_empty() || !draft.get.is_empty()) {
+            *draft = TradeDraft::default();
+        }
+        return;
+    };
+    style.display = Display::Flex;
+
+    let rules = &vis_board.board.rules;
+    let bank = match bank_trade(&draft, rules, me) {
+        Some(Action::BankTrade { give, get }) => format!(
+            "Banco: {} {} por 1 {}",
+            bank_rate(rules, me, give),
+            material_label(give),
+            material_label(get)
+        ),
+        _ => "Banco: elige tantas cartas como la tasa y 1 a cambio".to_string(),
+    };
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = format!(
+            "Comercio de {:?}\nDoy: {}\nPido: {}\n{}",
+            me.id,
+            cards_label(&draft.give),
+            cards_label(&draft.get),
+            bank
+        );
+    }
+    for (&GiveLabel(material), mut text) in &mut labels {
+        text.sections[0].value = format!("{} {}:1", material_label(material), bank_rate(rules, me, material));
+    }
+}
+
+/// Apaga lo que no vale con lo armado y esconde las ofertas a uno mismo o a asientos vacíos.
+fn update_trade_buttons(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    draft: Res<TradeDraft>,
+    mut buttons: Query<(&TradeButton, &mut Style, &mut BackgroundColor)>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let Some(me) = me(&status, &vis_board.board) else {
+        return;
+    };
+    let board = &vis_board.board;
+    let complete = !draft.give.is_empty() && !draft.get.is_empty();
+    for (&button, mut style, mut color) in &mut buttons {
+        let (shown, enabled) = match button {
+            TradeButton::Give(material) => {
+                let mut give = draft.give.clone();
+                give.push(material);
+                (true, has_cards(me, &give))
+            }
+            TradeButton::Bank => (true, bank_trade(&draft, &board.rules, me).is_some()),
+            TradeButton::Propose(Some(p)) => (p != me.id && board.players.iter().any(|q| q.id == p), complete),
+            TradeButton::Propose(None) => (true, complete),
+            TradeButton::Get(_) | TradeButton::Clear | TradeButton::Close => (true, true),
+        };
+        style.display = if shown { Display::Flex } else { Display::None };
+        *color = if enabled { BUTTON_ENABLED } else { BUTTON_DISABLED }.into();
+    }
+}
+
+// =====================================================
+// OFERTA ABIERTA
+// =====================================================
+
+fn offer_buttons(
+    buttons: Query<(&Interaction, &OfferButton), Changed<Interaction>>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let Some((trade, turn_order)) = open_trade(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let Some(me) = status.me else {
+        return;
+    };
+    let responding = trade.waiting_on(&turn_order).contains(&me);
+    for (interaction, &button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let action = match button {
+            OfferButton::Accept if responding => Action::RespondTrade { accept: true },
+            OfferButton::Decline if responding => Action::RespondTrade { accept: false },
+            OfferButton::Cancel if trade.from == me => Action::CancelTrade,
+            _ => continue,
+        };
+        requests.send(ActionRequest(action));
+    }
+}
+
+/// La oferta abierta, a la vista de todos; los botones, sólo para quien puede usarlos.
+fn update_offer_panel(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    mut panel: Query<&mut Style, (With<OfferPanel>, Without<OfferButton>)>,
+    mut text: Query<&mut Text, With<OfferText>>,
+    mut buttons: Query<(&OfferButton, &mut Style, &mut BackgroundColor), Without<OfferPanel>>,
+) {
+    let Ok(mut style) = panel.get_single_mut() else {
+        return;
+    };
+    let Some((trade, turn_order)) = open_trade(local.as_deref(), remote.as_deref()) else {
+        style.display = Display::None;
+        return;
+    };
+    style.display = Display::Flex;
+
+    let waiting = trade.waiting_on(&turn_order);
+    let to = trade.offer.to.map(|p| format!(" a {:?}", p)).unwrap_or_default();
+    let clock = offer_time_left(local.as_deref(), remote.as_deref())
+        .map(|left| format!(" ({}s)", left.as_secs()))
+        .unwrap_or_default();
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = format!(
+            "{:?} ofrece{}:\nDa: {}\nPide: {}\nEsperando a: {:?}{}",
+            trade.from,
+            to,
+            cards_label(&trade.offer.give),
+            cards_label(&trade.offer.get),
+            waiting,
+            clock
+        );
+    }
+
+    let me = turn_status(local.as_deref(), remote.as_deref()).and_then(|s| s.me);
+    let me_player = vis_board.board.players.iter().find(|p| Some(p.id) == me);
+    let responding = me.is_some_and(|p| waiting.contains(&p));
+    for (&button, mut style, mut color) in &mut buttons {
+        let (shown, enabled) = match button {
+            OfferButton::Accept => (responding, me_player.is_some_and(|p| has_cards(p, &trade.offer.get))),
+            OfferButton::Decline => (responding, true),
+            OfferButton::Cancel => (me == Some(trade.from), true),
+        };
+        style.display = if shown { Display::Flex } else { Display::None };
+        *color = match button {
+            OfferButton::Accept if enabled => BUTTON_SELECTED,
+            _ if enabled => BUTTON_ENABLED,
+            _ => BUTTON_DISABLED,
+        }
+        .into();
+    }
+}
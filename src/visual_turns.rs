@@ -0,0 +1,1080 @@
+// src/visual_turns.rs
+//
+// El turno en la ventana. La partida local corre sobre un `Game` del motor
+// y todo lo que hace el jugador sale como `ActionRequest`; acá están además
+// la barra de acciones, los dados, el selector de descartes y oro y el robo.
+
+use bevy::prelude::*;
+use rand::Rng;
+use std::time::Duration;
+
+use crate::engine::{Action, Game, GameEvent, GamePhase};
+use crate::dice::DiceMode;
+use crate::game_logic::{
+    cards_to_discard, city_spots, has_resources, road_spots, robbable_players, settlement_spots, CITY_COST, ROAD_COST,
+    SETTLEMENT_COST,
+};
+use crate::history::reveals_hidden_info;
+use crate::stats::{snapshot_hands, GainSource, GameStats, HandSnapshot, LossCause};
+use crate::types::*;
+use crate::visual_game::{
+    finish_visual_game, ActionRequest, AppState, BoardFlow, CurrentTool, GameUiState, LocalHistory, PlayedEvent, VisualBoard,
+};
+use crate::visual_net::RemoteSession;
+use crate::visual_trade::TradeDraft;
+
+/// Cuánto ruedan los dados antes de mostrar lo que salió.
+const DICE_ROLL_SECS: f32 = 0.8;
+/// Cada cuánto cambian de cara mientras ruedan.
+const DICE_FLICKER_SECS: f32 = 0.07;
+
+pub(crate) const BUTTON_ENABLED: Color = Color::srgb(0.25, 0.3, 0.38);
+pub(crate) const BUTTON_DISABLED: Color = Color::srgb(0.16, 0.17, 0.2);
+pub(crate) const BUTTON_SELECTED: Color = Color::srgb(0.35, 0.5, 0.72);
+
+// =====================================================
+// PLUGIN
+// =====================================================
+
+pub struct TurnFlowPlugin;
+
+impl Plugin for TurnFlowPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DiceAnimation>()
+            .init_resource::<PickerState>()
+            .init_resource::<TableDice>()
+            .init_resource::<LegalMoves>()
+            .add_systems(OnEnter(AppState::Playing), spawn_action_bar)
+            .add_systems(Update, (
+                sync_local_turn.run_if(resource_exists::<LocalGame>),
+                apply_local_actions.run_if(resource_exists::<LocalGame>).in_set(BoardFlow::Apply),
+                tick_local_clock.run_if(resource_exists::<LocalGame>).in_set(BoardFlow::Apply),
+                sync_tool_with_phase,
+                update_legal_moves,
+                turn_keys,
+                action_bar_buttons,
+                update_action_bar,
+                start_dice_animation,
+                animate_dice,
+                picker_buttons,
+                update_picker,
+                victim_buttons,
+                update_victim_panel,
+                table_dice_buttons,
+                update_table_dice_panel,
+            ).run_if(in_state(AppState::Playing)));
+    }
+}
+
+// =====================================================
+// RECURSOS
+// =====================================================
+
+/// La partida local: todos juegan en la misma pantalla, por turnos.
+#[derive(Resource)]
+pub struct LocalGame {
+    pub game: Game,
+}
+
+impl LocalGame {
+    pub fn new(game: Game) -> Self {
+        Self { game }
+    }
+
+    /// Quien tiene que decidir ahora: el primero que debe descartar, elegir
+    /// oro o responder una oferta de comercio, y si no, el jugador de turno.
+    pub fn acting_player(&self) -> PlayerType {
+        let responding = self.game.trade.as_ref().and_then(|t| t.waiting_on(&self.game.turn_order).first().copied());
+        match &self.game.phase {
+            GamePhase::Discard { pending } if !pending.is_empty() => pending[0],
+            GamePhase::GoldChoice { pending } if !pending.is_empty() => pending[0].0,
+            GamePhase::Main => responding.unwrap_or(self.game.current_player()),
+            _ => self.game.current_player(),
+        }
+    }
+
+    /// Lo que le queda a quien tiene que decidir, si hay relojes.
+    pub fn time_left(&self) -> Option<Duration> {
+        let acting = self.acting_player();
+        let responding = self.game.trade.as_ref().is_some_and(|t| t.waiting_on(&self.game.turn_order).contains(&acting));
+        match self.game.phase {
+            GamePhase::Discard { .. } | GamePhase::GoldChoice { .. } => self.game.clocks.decision_left(),
+            _ if responding => self.game.clocks.decision_left(),
+            _ => self.game.clocks.time_left(self.game.current_player()),
+        }
+    }
+}
+
+/// Fase, jugador de turno y quién está frente a la pantalla (`None` = espectador).
+pub struct TurnStatus {
+    pub phase: GamePhase,
+    pub current: PlayerType,
+    pub me: Option<PlayerType>,
+}
+
+impl TurnStatus {
+    /// ¿Le toca decidir algo a quien está frente a la pantalla?
+    pub fn can_act(&self) -> bool {
+        let Some(me) = self.me else {
+            return false;
+        };
+        match &self.phase {
+            GamePhase::Finished { .. } => false,
+            GamePhase::Discard { pending } => pending.contains(&me),
+            GamePhase::GoldChoice { pending } => pending.iter().any(|&(p, _)| p == me),
+            _ => self.current == me,
+        }
+    }
+
+    /// Herramientas que tienen sentido en esta fase.
+    pub fn allowed_tools(&self) -> &'static [CurrentTool] {
+        match self.phase {
+            GamePhase::Setup { anchor_vertex: None, .. } => &[CurrentTool::PlaceSettlement],
+            GamePhase::Setup { anchor_vertex: Some(_), .. } => &[CurrentTool::PlaceRoad],
+            GamePhase::MoveRobber => &[CurrentTool::MoveRobber],
+            GamePhase::Main => &[CurrentTool::PlaceSettlement, CurrentTool::PlaceRoad, CurrentTool::PlaceCity],
+            _ => &[],
+        }
+    }
+}
+
+/// El estado del turno, de la partida local o de lo último que mandó el servidor.
+pub fn turn_status(local: Option<&LocalGame>, remote: Option<&RemoteSession>) -> Option<TurnStatus> {
+    if let Some(local) = local {
+        return Some(TurnStatus {
+            phase: local.game.phase.clone(),
+            current: local.game.current_player(),
+            me: Some(local.acting_player()),
+        });
+    }
+    let session = remote?;
+    let snapshot = session.state.snapshot.as_ref()?;
+    Some(TurnStatus {
+        phase: snapshot.phase.clone(),
+        current: snapshot.current_player,
+        me: session.state.seat.filter(|_| !session.is_spectator()),
+    })
+}
+
+/// ¿Le alcanzan las cartas y las piezas para usar la herramienta? En la fundación no se paga.
+pub fn can_afford(status: &TurnStatus, board: &Board, tool: CurrentTool) -> bool {
+    let Some(player) = status.me.and_then(|me| board.players.iter().find(|p| p.id == me)) else {
+        return false;
+    };
+    if matches!(status.phase, GamePhase::Setup { .. }) {
+        return true;
+    }
+    match tool {
+        CurrentTool::PlaceSettlement => player.settlement_quantity > 0 && has_resources(player, SETTLEMENT_COST),
+        CurrentTool::PlaceRoad => player.road_quantity > 0 && has_resources(player, ROAD_COST),
+        CurrentTool::PlaceCity => player.city_quantity > 0 && has_resources(player, CITY_COST),
+        CurrentTool::MoveRobber => true,
+    }
+}
+
+/// Dónde se puede usar la herramienta actual. Lo resalta el tablero y sólo ahí valen los clicks.
+#[derive(Resource, Default, PartialEq)]
+pub struct LegalMoves {
+    pub vertices: Vec<VertexId>,
+    pub edges: Vec<EdgeId>,
+    pub tiles: Vec<TileId>,
+}
+
+impl LegalMoves {
+    fn new(status: &TurnStatus, board: &Board, ui_state: &GameUiState) -> Self {
+        let mut moves = Self::default();
+        let Some(me) = status.me else {
+            return moves;
+        };
+        if !status.can_act() {
+            return moves;
+        }
+        // con una carta de desarrollo elegida, manda la carta y no la herramienta
+        match ui_state.selected_dev_card {
+            Some(DevelopmentCard::Knight) => {
+                moves.tiles = robber_tiles(board);
+                return moves;
+            }
+            Some(DevelopmentCard::RoadBuilding) => {
+                // el segundo camino puede seguir al primero
+                let mut board = board.clone();
+                for &edge in &ui_state.free_roads {
+                    board.edges[edge].owner = Some(me);
+                }
+                moves.edges = road_spots(&board, me, TurnPhase::FreeRoad);
+                return moves;
+            }
+            Some(_) => return moves,
+            None => {}
+        }
+        let tool = ui_state.current_tool;
+        if !status.allowed_tools().contains(&tool) || !can_afford(status, board, tool) {
+            return moves;
+        }
+        let setup_anchor = match status.phase {
+            GamePhase::Setup { anchor_vertex, .. } => Some(anchor_vertex),
+            _ => None,
+        };
+        match tool {
+            CurrentTool::PlaceSettlement => moves.vertices = settlement_spots(board, me, setup_anchor.is_some()),
+            CurrentTool::PlaceCity => moves.vertices = city_spots(board, me),
+            CurrentTool::PlaceRoad => {
+                let phase = match setup_anchor {
+                    Some(Some(anchor_vertex)) => TurnPhase::Setup { anchor_vertex },
+                    _ => TurnPhase::Normal,
+                };
+                moves.edges = road_spots(board, me, phase);
+            }
+            CurrentTool::MoveRobber => moves.tiles = robber_tiles(board),
+        }
+        moves
+    }
+}
+
+/// Casillas adonde se puede llevar el ladrón: cualquiera menos donde ya están él o el pirata.
+fn robber_tiles(board: &Board) -> Vec<TileId> {
+    (0..board.tiles.len())
+        .filter(|&t| !board.tiles[t].has_robber && !board.tiles[t].has_pirate)
+        .collect()
+}
+
+pub fn phase_label(phase: &GamePhase) -> String {
+    match phase {
+        GamePhase::Setup { round, anchor_vertex: None } => format!("Fundación {}: asentamiento", round),
+        GamePhase::Setup { round, anchor_vertex: Some(_) } => format!("Fundación {}: camino", round),
+        GamePhase::Roll => "Tirar dados (R)".to_string(),
+        GamePhase::Discard { pending } => format!("Descartes: {:?}", pending),
+        GamePhase::GoldChoice { pending } => format!("Oro: {:?}", pending),
+        GamePhase::MoveRobber => "Mover ladrón".to_string(),
+        GamePhase::Main => "Acciones (E = terminar)".to_string(),
+        GamePhase::Finished { winner } => format!("Ganó {:?}", winner),
+    }
+}
+
+pub(crate) fn material_label(material: MaterialType) -> &'static str {
+    match material {
+        MaterialType::Wood => "Madera",
+        MaterialType::Brick => "Ladrillo",
+        MaterialType::Sheep => "Oveja",
+        MaterialType::Wheat => "Trigo",
+        MaterialType::Stone => "Piedra",
+        MaterialType::Dessert => "Desierto",
+        MaterialType::Gold => "Oro",
+        MaterialType::Sea => "Mar",
+        MaterialType::Paper => "Papel",
+        MaterialType::Cloth => "Tela",
+        MaterialType::Coin => "Moneda",
+    }
+}
+
+#[derive(Resource, Default)]
+struct DiceAnimation {
+    /// `Some` mientras los dados ruedan.
+    rolling: Option<Timer>,
+    since_flicker: f32,
+}
+
+/// Dados físicos: lo que se marcó de cada dado de la mesa (0 = blanco, 1 = rojo).
+#[derive(Resource, Default)]
+struct TableDice([Option<u8>; 2]);
+
+/// Cartas elegidas en el selector de descarte / oro.
+#[derive(Resource, Default)]
+struct PickerState {
+    picked: Vec<MaterialType>,
+}
+
+// =====================================================
+// COMPONENTES
+// =====================================================
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum TurnButton {
+    Roll,
+    EndTurn,
+    /// Abre y cierra la ventana de comercio.
+    Trade,
+    Tool(CurrentTool),
+}
+
+/// Cara de uno de los dos dados (0 = blanco, 1 = rojo).
+#[derive(Component)]
+struct DieFace(usize);
+
+#[derive(Component)]
+struct PickerPanel;
+
+#[derive(Component)]
+struct PickerText;
+
+#[derive(Component, Clone, Copy)]
+enum PickerButton {
+    Material(MaterialType),
+    Clear,
+    Confirm,
+}
+
+#[derive(Component)]
+struct VictimPanel;
+
+#[derive(Component)]
+struct TableDicePanel;
+
+/// Una cara de uno de los dados de la mesa: (dado, valor).
+#[derive(Component, Clone, Copy)]
+struct TableDiceButton(usize, u8);
+
+/// A quién robarle; `None` = cancelar y elegir otra casilla.
+#[derive(Component, Clone, Copy)]
+struct VictimButton(Option<PlayerType>);
+
+// =====================================================
+// PARTIDA LOCAL
+// =====================================================
+
+/// En la partida local la pantalla es de quien tiene que decidir.
+fn sync_local_turn(local: Res<LocalGame>, mut ui_state: ResMut<GameUiState>) {
+    let acting = local.acting_player();
+    if ui_state.current_player != acting {
+        ui_state.current_player = acting;
+    }
+}
+
+fn apply_local_actions(
+    mut requests: EventReader<ActionRequest>,
+    mut played: EventWriter<PlayedEvent>,
+    mut local: ResMut<LocalGame>,
+    mut vis_board: ResMut<VisualBoard>,
+    mut history: ResMut<LocalHistory>,
+) {
+    for ActionRequest(action) in requests.read() {
+        let player = local.acting_player();
+        let before = (local.game.clone(), vis_board.stats.clone());
+        let hands = snapshot_hands(&local.game.board);
+
+        let events = match local.game.apply(player, action.clone()) {
+            Ok(events) => events,
+            Err(msg) => {
+                println!("Error: {}", msg);
+                continue;
+            }
+        };
+
+        // los dados, el mazo y el cambio de turno no se deshacen
+        let sealed = matches!(action, Action::RollDice | Action::EnterDice { .. } | Action::BuyDevCard)
+            || events.iter().any(|e| matches!(e, GameEvent::TurnEnded { .. }))
+            || reveals_hidden_info(&before.0.board, &local.game.board, player);
+        if sealed {
+            history.0.seal();
+        } else {
+            history.0.record(before);
+        }
+
+        publish_local_events(&local.game, &mut vis_board, &hands, events, &mut played);
+    }
+}
+
+/// Los relojes de la partida local corren con el tiempo de la ventana. Lo
+/// que juega el motor cuando a alguien se le acaba el tiempo no se deshace.
+fn tick_local_clock(
+    time: Res<Time>,
+    mut played: EventWriter<PlayedEvent>,
+    mut local: ResMut<LocalGame>,
+    mut vis_board: ResMut<VisualBoard>,
+    mut history: ResMut<LocalHistory>,
+) {
+    if !local.game.clocks.config.is_enabled() {
+        return;
+    }
+    let hands = snapshot_hands(&local.game.board);
+    let events = local.game.advance_clock(time.delta());
+    if events.is_empty() {
+        return;
+    }
+    history.0.seal();
+    publish_local_events(&local.game, &mut vis_board, &hands, events, &mut played);
+}
+
+/// Pasa al tablero, a las estadísticas y a las animaciones lo que dejó una jugada local.
+fn publish_local_events(
+    game: &Game,
+    vis_board: &mut VisualBoard,
+    hands: &HandSnapshot,
+    events: Vec<GameEvent>,
+    played: &mut EventWriter<PlayedEvent>,
+) {
+    vis_board.board = game.board.clone();
+    record_stats(&mut vis_board.stats, hands, &game.board, &events);
+    for event in &events {
+        println!("{}", event);
+        if let GameEvent::GameOver { winner } = *event {
+            finish_visual_game(vis_board, winner);
+        }
+    }
+    played.send_batch(events.into_iter().map(PlayedEvent));
+}
+
+/// Suma a las estadísticas lo que dejó una acción.
+fn record_stats(stats: &mut GameStats, before: &HandSnapshot, board: &Board, events: &[GameEvent]) {
+    let mut gains = None;
+    for event in events {
+        match *event {
+            GameEvent::DiceRolled { player, roll } => stats.record_roll(player, roll),
+            GameEvent::SettlementBuilt { player, .. } => stats.record_build(player, Some(BuildingType::Settlement)),
+            GameEvent::CityBuilt { player, .. } => stats.record_build(player, Some(BuildingType::City)),
+            GameEvent::RoadBuilt { player, .. } => stats.record_build(player, None),
+            GameEvent::DevCardBought { player, .. } => stats.record_dev_card_bought(player),
+            GameEvent::DevCardPlayed { player, card } => stats.record_dev_card_played(player, card),
+            GameEvent::ResourcesProduced { .. } => gains = Some(GainSource::Production),
+            GameEvent::BankTrade { .. } | GameEvent::PlayersTraded { .. } => gains = Some(GainSource::Trade),
+            GameEvent::ResourceStolen { .. } => {
+                gains = Some(GainSource::Robbery);
+                stats.record_losses(before, board, LossCause::Robber);
+            }
+            GameEvent::Discarded { .. } => stats.record_losses(before, board, LossCause::Discard),
+            GameEvent::ResourcesFromBank { .. } => gains = Some(GainSource::DevCard),
+            GameEvent::MonopolyPlayed { .. } => {
+                gains = Some(GainSource::DevCard);
+                stats.record_losses(before, board, LossCause::Monopoly);
+            }
+            GameEvent::TurnEnded { .. } => stats.end_turn(board),
+            _ => {}
+        }
+    }
+    if let Some(source) = gains {
+        stats.record_gains(before, board, source);
+    }
+}
+
+// =====================================================
+// FASE Y TECLAS
+// =====================================================
+
+/// Si la herramienta elegida no vale en esta fase, pasa a la primera que sí.
+fn sync_tool_with_phase(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut ui_state: ResMut<GameUiState>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let allowed = status.allowed_tools();
+    if !allowed.is_empty() && !allowed.contains(&ui_state.current_tool) {
+        ui_state.current_tool = allowed[0];
+    }
+    let knight = ui_state.selected_dev_card == Some(DevelopmentCard::Knight);
+    if ui_state.robber_tile.is_some() && !knight && !allowed.contains(&CurrentTool::MoveRobber) {
+        ui_state.robber_tile = None;
+    }
+    if !ui_state.free_roads.is_empty() && ui_state.selected_dev_card != Some(DevelopmentCard::RoadBuilding) {
+        ui_state.free_roads.clear();
+    }
+}
+
+fn update_legal_moves(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    mut legal: ResMut<LegalMoves>,
+) {
+    let moves = turn_status(local.as_deref(), remote.as_deref())
+        .map(|status| LegalMoves::new(&status, &vis_board.board, &ui_state))
+        .unwrap_or_default();
+    legal.set_if_neq(moves);
+}
+
+/// R = tirar dados, E = terminar turno, T = comerciar.
+fn turn_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    mut draft: ResMut<TradeDraft>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    if !status.can_act() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyR) && status.phase == GamePhase::Roll {
+        requests.send(ActionRequest(Action::RollDice));
+    }
+    if keys.just_pressed(KeyCode::KeyE) && status.phase == GamePhase::Main {
+        requests.send(ActionRequest(Action::EndTurn));
+    }
+    if keys.just_pressed(KeyCode::KeyT) && status.phase == GamePhase::Main {
+        draft.open = !draft.open;
+    }
+}
+
+// =====================================================
+// BARRA DE ACCIONES Y DADOS
+// =====================================================
+
+fn spawn_action_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("FiraSans-Bold.ttf");
+    let text_style = TextStyle { font: font.clone(), font_size: 18.0, color: Color::WHITE };
+
+    // abajo al centro: dados, herramientas y terminar turno
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                column_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|bar| {
+            let buttons = [
+                (TurnButton::Roll, "Tirar dados (R)".to_string()),
+                (TurnButton::Tool(CurrentTool::PlaceSettlement), "Asentamiento (1)".to_string()),
+                (TurnButton::Tool(CurrentTool::PlaceRoad), "Camino (2)".to_string()),
+                (TurnButton::Tool(CurrentTool::MoveRobber), "Ladrón (3)".to_string()),
+                (TurnButton::Tool(CurrentTool::PlaceCity), "Ciudad (4)".to_string()),
+                (TurnButton::Trade, "Comerciar (T)".to_string()),
+                (TurnButton::EndTurn, "Terminar turno (E)".to_string()),
+            ];
+            for (button, label) in buttons {
+                bar.spawn((
+                    ButtonBundle {
+                        style: Style { padding: UiRect::axes(Val::Px(12.0), Val::Px(8.0)), ..Default::default() },
+                        background_color: BUTTON_DISABLED.into(),
+                        ..Default::default()
+                    },
+                    button,
+                ))
+                .with_children(|b| {
+                    b.spawn(TextBundle::from_section(label, text_style.clone()));
+                });
+            }
+        });
+
+    // arriba a la derecha: los dos dados
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                column_gap: Val::Px(8.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|dice| {
+            for (i, color) in [(0, Color::WHITE), (1, Color::srgb(0.85, 0.2, 0.2))] {
+                dice.spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(48.0),
+                        height: Val::Px(48.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: color.into(),
+                    ..Default::default()
+                })
+                .with_children(|die| {
+                    die.spawn((
+                        TextBundle::from_section(
+                            "-",
+                            TextStyle { font: font.clone(), font_size: 30.0, color: Color::BLACK },
+                        ),
+                        DieFace(i),
+                    ));
+                });
+            }
+        });
+
+    // al centro: el selector de cartas para descartar o elegir el oro
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(35.0),
+                    left: Val::Percent(30.0),
+                    width: Val::Percent(40.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(5),
+                ..Default::default()
+            },
+            PickerPanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn((TextBundle::from_section("", text_style.clone()), PickerText));
+            panel
+                .spawn(NodeBundle {
+                    style: Style { column_gap: Val::Px(6.0), ..Default::default() },
+                    ..Default::default()
+                })
+                .with_children(|row| {
+                    let buttons = MaterialType::RESOURCES
+                        .iter()
+                        .map(|&m| (PickerButton::Material(m), material_label(m)))
+                        .chain([(PickerButton::Clear, "Borrar"), (PickerButton::Confirm, "Confirmar")]);
+                    for (button, label) in buttons {
+                        row.spawn((
+                            ButtonBundle {
+                                style: Style { padding: UiRect::all(Val::Px(8.0)), ..Default::default() },
+                                background_color: BUTTON_ENABLED.into(),
+                                ..Default::default()
+                            },
+                            button,
+                        ))
+                        .with_children(|b| {
+                            b.spawn(TextBundle::from_section(label, text_style.clone()));
+                        });
+                    }
+                });
+        });
+
+    // arriba a la derecha, bajo los dados: lo que salió en los dados de la mesa
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(70.0),
+                    right: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(5),
+                ..Default::default()
+            },
+            TableDicePanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section("Dados de la mesa:", text_style.clone()));
+            for (die, color) in [(0, Color::WHITE), (1, Color::srgb(0.85, 0.2, 0.2))] {
+                panel
+                    .spawn(NodeBundle {
+                        style: Style { column_gap: Val::Px(4.0), ..Default::default() },
+                        ..Default::default()
+                    })
+                    .with_children(|row| {
+                        for value in 1..=6 {
+                            row.spawn((
+                                ButtonBundle {
+                                    style: Style { padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)), ..Default::default() },
+                                    background_color: BUTTON_ENABLED.into(),
+                                    ..Default::default()
+                                },
+                                TableDiceButton(die, value),
+                            ))
+                            .with_children(|b| {
+                                b.spawn(TextBundle::from_section(
+                                    value.to_string(),
+                                    TextStyle { color, ..text_style.clone() },
+                                ));
+                            });
+                        }
+                    });
+            }
+        });
+
+    // al centro: a quién robarle cuando en la casilla hay más de uno
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(40.0),
+                    left: Val::Percent(30.0),
+                    width: Val::Percent(40.0),
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(5),
+                ..Default::default()
+            },
+            VictimPanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section("Robar a:", text_style.clone()));
+            let players = [PlayerType::Player1, PlayerType::Player2, PlayerType::Player3, PlayerType::Player4];
+            let buttons = players
+                .into_iter()
+                .map(|p| (VictimButton(Some(p)), format!("{:?}", p)))
+                .chain([(VictimButton(None), "Cancelar".to_string())]);
+            for (button, label) in buttons {
+                panel
+                    .spawn((
+                        ButtonBundle {
+                            style: Style { padding: UiRect::all(Val::Px(8.0)), ..Default::default() },
+                            background_color: BUTTON_ENABLED.into(),
+                            ..Default::default()
+                        },
+                        button,
+                    ))
+                    .with_children(|b| {
+                        b.spawn(TextBundle::from_section(label, text_style.clone()));
+                    });
+            }
+        });
+}
+
+fn action_bar_buttons(
+    buttons: Query<(&Interaction, &TurnButton), Changed<Interaction>>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    mut ui_state: ResMut<GameUiState>,
+    mut draft: ResMut<TradeDraft>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed || !button_enabled(*button, &status, &vis_board.board) {
+            continue;
+        }
+        match *button {
+            TurnButton::Roll => {
+                requests.send(ActionRequest(Action::RollDice));
+            }
+            TurnButton::EndTurn => {
+                requests.send(ActionRequest(Action::EndTurn));
+            }
+            TurnButton::Tool(tool) => ui_state.current_tool = tool,
+            TurnButton::Trade => draft.open = !draft.open,
+        }
+    }
+}
+
+fn button_enabled(button: TurnButton, status: &TurnStatus, board: &Board) -> bool {
+    if !status.can_act() {
+        return false;
+    }
+    match button {
+        TurnButton::Roll => status.phase == GamePhase::Roll,
+        TurnButton::EndTurn | TurnButton::Trade => status.phase == GamePhase::Main,
+        TurnButton::Tool(tool) => status.allowed_tools().contains(&tool) && can_afford(status, board, tool),
+    }
+}
+
+/// Apagados los botones que no sirven ahora o no se pueden pagar; resaltada la herramienta actual.
+fn update_action_bar(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    draft: Res<TradeDraft>,
+    mut buttons: Query<(&TurnButton, &mut BackgroundColor)>,
+) {
+    let status = turn_status(local.as_deref(), remote.as_deref());
+    for (&button, mut color) in &mut buttons {
+        let enabled = status.as_ref().is_some_and(|s| button_enabled(button, s, &vis_board.board));
+        *color = match button {
+            TurnButton::Tool(tool) if enabled && tool == ui_state.current_tool => BUTTON_SELECTED,
+            TurnButton::Trade if enabled && draft.open => BUTTON_SELECTED,
+            _ if enabled => BUTTON_ENABLED,
+            _ => BUTTON_DISABLED,
+        }
+        .into();
+    }
+}
+
+fn start_dice_animation(mut played: EventReader<PlayedEvent>, mut animation: ResMut<DiceAnimation>) {
+    if played.read().any(|e| matches!(e.0, GameEvent::DiceRolled { .. })) {
+        animation.rolling = Some(Timer::from_seconds(DICE_ROLL_SECS, TimerMode::Once));
+        animation.since_flicker = DICE_FLICKER_SECS;
+    }
+}
+
+/// Mientras ruedan, caras al azar; al parar, lo que salió.
+fn animate_dice(
+    time: Res<Time>,
+    vis_board: Res<VisualBoard>,
+    mut animation: ResMut<DiceAnimation>,
+    mut faces: Query<(&DieFace, &mut Text)>,
+) {
+    let DiceAnimation { rolling, since_flicker } = &mut *animation;
+    let Some(timer) = rolling.as_mut() else {
+        return;
+    };
+    timer.tick(time.delta());
+    if timer.finished() {
+        *rolling = None;
+        let last = vis_board.board.dice.last;
+        for (face, mut text) in &mut faces {
+            text.sections[0].value = match last {
+                Some((white, red)) => if face.0 == 0 { white } else { red }.to_string(),
+                None => "-".to_string(),
+            };
+        }
+        return;
+    }
+
+    *since_flicker += time.delta_seconds();
+    if *since_flicker < DICE_FLICKER_SECS {
+        return;
+    }
+    *since_flicker = 0.0;
+    let mut rng = rand::rng();
+    for (_, mut text) in &mut faces {
+        text.sections[0].value = rng.random_range(1..=6u8).to_string();
+    }
+}
+
+// =====================================================
+// SELECTOR DE CARTAS (DESCARTE, ORO, ABUNDANCIA, MONOPOLIO)
+// =====================================================
+
+/// Lo que pide el selector de cartas.
+#[derive(Clone, Copy, PartialEq)]
+enum Choice {
+    Discard(usize),
+    Gold(usize),
+    YearOfPlenty,
+    Monopoly,
+}
+
+impl Choice {
+    fn needed(self) -> usize {
+        match self {
+            Choice::Discard(n) | Choice::Gold(n) => n,
+            Choice::YearOfPlenty => 2,
+            Choice::Monopoly => 1,
+        }
+    }
+
+    fn prompt(self) -> String {
+        match self {
+            Choice::Discard(n) => format!("descarta {} cartas", n),
+            Choice::Gold(n) => format!("elige del oro {} cartas", n),
+            Choice::YearOfPlenty => "Año de la Abundancia: elige 2 cartas".to_string(),
+            Choice::Monopoly => "Monopolio: elige 1 recurso".to_string(),
+        }
+    }
+
+    /// La acción que cierra la elección; `None` si faltan o sobran cartas.
+    fn action(self, materials: &[MaterialType]) -> Option<Action> {
+        if materials.len() != self.needed() {
+            return None;
+        }
+        Some(match self {
+            Choice::Discard(_) => Action::Discard { materials: materials.to_vec() },
+            Choice::Gold(_) => Action::ChooseGold { materials: materials.to_vec() },
+            Choice::YearOfPlenty => Action::PlayYearOfPlenty { first: materials[0], second: materials[1] },
+            Choice::Monopoly => Action::PlayMonopoly { material: materials[0] },
+        })
+    }
+}
+
+/// Qué hay que elegir, si le toca a quien está frente a la pantalla.
+/// Descartes y oro van antes que la carta de desarrollo seleccionada.
+fn pending_choice(status: &TurnStatus, board: &Board, selected: Option<DevelopmentCard>) -> Option<Choice> {
+    let me = status.me?;
+    match &status.phase {
+        GamePhase::Discard { pending } if pending.contains(&me) => {
+            let player = board.players.iter().find(|p| p.id == me)?;
+            Some(Choice::Discard(cards_to_discard(&board.rules, player) as usize))
+        }
+        GamePhase::GoldChoice { pending } => {
+            pending.iter().find(|&&(p, _)| p == me).map(|&(_, amount)| Choice::Gold(amount as usize))
+        }
+        _ => match selected {
+            Some(DevelopmentCard::YearOfPlenty) => Some(Choice::YearOfPlenty),
+            Some(DevelopmentCard::Monopoly) => Some(Choice::Monopoly),
+            _ => None,
+        },
+    }
+}
+
+fn picker_buttons(
+    buttons: Query<(&Interaction, &PickerButton), Changed<Interaction>>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    mut picker: ResMut<PickerState>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let Some(status) = turn_status(local.as_deref(), remote.as_deref()) else {
+        return;
+    };
+    let Some(choice) = pending_choice(&status, &vis_board.board, ui_state.selected_dev_card) else {
+        return;
+    };
+    let needed = choice.needed();
+    for (interaction, &button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PickerButton::Material(material) if picker.picked.len() < needed => picker.picked.push(material),
+            PickerButton::Material(_) => {}
+            PickerButton::Clear => picker.picked.clear(),
+            PickerButton::Confirm => match choice.action(&picker.picked) {
+                Some(action) => {
+                    picker.picked.clear();
+                    requests.send(ActionRequest(action));
+                }
+                None => println!("Tienes que elegir {} cartas.", needed),
+            },
+        }
+    }
+}
+
+fn update_picker(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    mut picker: ResMut<PickerState>,
+    mut panel: Query<&mut Style, With<PickerPanel>>,
+    mut text: Query<&mut Text, With<PickerText>>,
+) {
+    let status = turn_status(local.as_deref(), remote.as_deref());
+    let choice = status.as_ref().and_then(|s| pending_choice(s, &vis_board.board, ui_state.selected_dev_card));
+    let Ok(mut style) = panel.get_single_mut() else {
+        return;
+    };
+    let Some(choice) = choice else {
+        style.display = Display::None;
+        if !picker.picked.is_empty() {
+            picker.picked.clear();
+        }
+        return;
+    };
+    style.display = Display::Flex;
+    if picker.picked.len() > choice.needed() {
+        picker.picked.truncate(choice.needed());
+    }
+
+    let who = status.and_then(|s| s.me).map(|p| format!("{:?}", p)).unwrap_or_default();
+    let picked: Vec<&str> = picker.picked.iter().map(|&m| material_label(m)).collect();
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = format!("{}: {}\n{}", who, choice.prompt(), picked.join(", "));
+    }
+}
+
+// =====================================================
+// VÍCTIMA DEL LADRÓN
+// =====================================================
+
+fn victim_buttons(
+    buttons: Query<(&Interaction, &VictimButton), Changed<Interaction>>,
+    mut ui_state: ResMut<GameUiState>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let Some(tile) = ui_state.robber_tile else {
+        return;
+    };
+    for (interaction, &VictimButton(victim)) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        ui_state.robber_tile = None;
+        if let Some(victim) = victim {
+            requests.send(ActionRequest(ui_state.robber_action(tile, Some(victim))));
+        }
+    }
+}
+
+/// El panel sólo con los jugadores a los que se puede robar desde la casilla elegida.
+fn update_victim_panel(
+    vis_board: Res<VisualBoard>,
+    ui_state: Res<GameUiState>,
+    mut panel: Query<&mut Style, (With<VictimPanel>, Without<VictimButton>)>,
+    mut buttons: Query<(&VictimButton, &mut Style), Without<VictimPanel>>,
+) {
+    let Ok(mut panel) = panel.get_single_mut() else {
+        return;
+    };
+    let Some(tile) = ui_state.robber_tile else {
+        panel.display = Display::None;
+        return;
+    };
+    panel.display = Display::Flex;
+    let candidates = robbable_players(&vis_board.board, tile, ui_state.current_player);
+    for (&VictimButton(victim), mut style) in &mut buttons {
+        let shown = victim.is_none_or(|v| candidates.contains(&v));
+        style.display = if shown { Display::Flex } else { Display::None };
+    }
+}
+
+// =====================================================
+// DADOS FÍSICOS
+// =====================================================
+
+/// ¿Hay que cargar los dados de la mesa? Sólo con dados físicos y al tirar.
+fn enters_table_dice(status: Option<&TurnStatus>, board: &Board) -> bool {
+    board.rules.dice == DiceMode::Manual && status.is_some_and(|s| s.can_act() && s.phase == GamePhase::Roll)
+}
+
+/// Marcadas las dos caras, se cargan; "Tirar dados" sigue tirando con la aplicación.
+fn table_dice_buttons(
+    buttons: Query<(&Interaction, &TableDiceButton), Changed<Interaction>>,
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    mut table: ResMut<TableDice>,
+    mut requests: EventWriter<ActionRequest>,
+) {
+    let status = turn_status(local.as_deref(), remote.as_deref());
+    if !enters_table_dice(status.as_ref(), &vis_board.board) {
+        return;
+    }
+    for (interaction, &TableDiceButton(die, value)) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        table.0[die] = Some(value);
+        if let [Some(first), Some(second)] = table.0 {
+            table.0 = [None, None];
+            requests.send(ActionRequest(Action::EnterDice { first, second }));
+        }
+    }
+}
+
+fn update_table_dice_panel(
+    local: Option<Res<LocalGame>>,
+    remote: Option<Res<RemoteSession>>,
+    vis_board: Res<VisualBoard>,
+    mut table: ResMut<TableDice>,
+    mut panel: Query<&mut Style, With<TableDicePanel>>,
+    mut buttons: Query<(&TableDiceButton, &mut BackgroundColor)>,
+) {
+    let Ok(mut style) = panel.get_single_mut() else {
+        return;
+    };
+    let status = turn_status(local.as_deref(), remote.as_deref());
+    if !enters_table_dice(status.as_ref(), &vis_board.board) {
+        style.display = Display::None;
+        if table.0 != [None, None] {
+            table.0 = [None, None];
+        }
+        return;
+    }
+    style.display = Display::Flex;
+    for (&TableDiceButton(die, value), mut color) in &mut buttons {
+        *color = if table.0[die] == Some(value) { BUTTON_SELECTED } else { BUTTON_ENABLED }.into();
+    }
+}
@@ -0,0 +1,100 @@
+// tests/loopback.rs
+//
+// Una partida de dos por TCP en 127.0.0.1: el servidor de verdad, dos
+// `ServerConnection` que juegan la fundación con el bot, y en cada
+// cliente sólo su propia mano a la vista.
+
+use bevy_hexx_demo::engine::{bot, GameEvent, GamePhase};
+use bevy_hexx_demo::net::{serve, ClientState, GameOptions, ServerConnection, ServerMessage};
+use bevy_hexx_demo::types::*;
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Un jugador conectado: la conexión, lo que sabe y si espera respuesta a su jugada.
+struct Seat {
+    conn: ServerConnection,
+    state: ClientState,
+    waiting: bool,
+    /// Cartas iniciales de cada jugador, según los eventos que le llegaron.
+    starting: HashMap<PlayerType, u8>,
+}
+
+impl Seat {
+    fn connect(addr: &str) -> Self {
+        let mut conn = ServerConnection::connect(addr).unwrap();
+        conn.join(None).unwrap();
+        Seat { conn, state: ClientState::default(), waiting: false, starting: HashMap::new() }
+    }
+
+    /// Procesa lo que haya llegado y, si le toca en la fundación, juega.
+    fn step(&mut self) {
+        loop {
+            let message = match self.conn.incoming.recv_timeout(Duration::from_millis(10)) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => panic!("el servidor cortó la conexión"),
+            };
+            if let ServerMessage::Error { message } = &message {
+                panic!("el servidor rechazó una jugada: {}", message);
+            }
+            if matches!(message, ServerMessage::Snapshot { .. } | ServerMessage::Diff { .. }) {
+                self.waiting = false;
+            }
+            for event in self.state.apply(message) {
+                if let GameEvent::StartingResources { player, materials } = event {
+                    *self.starting.entry(player).or_insert(0) += materials.len() as u8;
+                }
+            }
+        }
+
+        let Some(view) = self.state.snapshot.as_ref() else {
+            return;
+        };
+        let my_turn = Some(view.current_player) == self.state.seat;
+        if !self.waiting && my_turn && matches!(view.phase, GamePhase::Setup { .. }) {
+            let action = bot::choose_action(view).expect("en la fundación siempre hay jugada");
+            self.conn.send_action(action).unwrap();
+            self.waiting = true;
+        }
+    }
+
+    fn setup_done(&self) -> bool {
+        self.state.snapshot.as_ref().is_some_and(|v| v.phase == GamePhase::Roll)
+    }
+}
+
+#[test]
+fn two_clients_play_the_setup_and_only_see_their_own_hand() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || serve(listener, GameOptions { players: 2, ..GameOptions::default() }));
+
+    let mut seats = [Seat::connect(&addr), Seat::connect(&addr)];
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !seats.iter().all(Seat::setup_done) {
+        assert!(Instant::now() < deadline, "la fundación no terminó a tiempo");
+        for seat in seats.iter_mut() {
+            seat.step();
+        }
+    }
+
+    for seat in &seats {
+        let me = seat.state.seat.unwrap();
+        let view = seat.state.snapshot.as_ref().unwrap();
+        assert_eq!(view.viewer, Some(me));
+        for player in &view.board.players {
+            let held: u8 = player.resources.values().sum();
+            // el tamaño de todas las manos es público, el contenido sólo el propio
+            assert_eq!(view.hand_sizes[&player.id], seat.starting.get(&player.id).copied().unwrap_or(0));
+            if player.id == me {
+                assert_eq!(held, view.hand_sizes[&me]);
+            } else {
+                assert_eq!(held, 0, "{:?} ve la mano de {:?}", me, player.id);
+            }
+        }
+    }
+    assert_ne!(seats[0].state.seat, seats[1].state.seat);
+}